@@ -0,0 +1,128 @@
+//! # Byte Arrays
+//!
+//! `serde` serializes a `[u8; N]` as a tuple of `N` separate `u8`s, so in the
+//! nibble format every element is encoded on its own as a nibble varint. For
+//! opaque binary data (keys, hashes, raw samples) this is both larger and slower
+//! than copying the bytes as-is.
+//!
+//! The [`byte_array`](self) module, for use with `#[serde(with = ...)]`, encodes
+//! the array like a byte slice instead: a short length prefix followed by a single
+//! aligned run of bytes, which flavors receive through one `try_extend` call.
+
+use core::fmt;
+use serde::de::Visitor;
+use serde::{Deserializer, Serializer};
+
+/// Serialize the array as a length prefixed run of bytes.
+///
+/// ```rust
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// pub struct Sample {
+///     #[serde(with = "postcard::byte_array")]
+///     raw: [u8; 256],
+/// }
+/// ```
+pub fn serialize<S, const N: usize>(val: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(val)
+}
+
+/// Deserialize the array from a length prefixed run of bytes, which must contain exactly `N` bytes.
+pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(ByteArrayVisitor::<N>)
+}
+
+struct ByteArrayVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for ByteArrayVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} bytes", N)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let mut out = [0u8; N];
+        if v.len() != N {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        out.copy_from_slice(v);
+        Ok(out)
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[cfg(test)]
+mod tests {
+    use crate::ser::nibble_flavors::{NibbleFlavor, NibbleHVec};
+    use crate::ser::serialize_with_nibble_flavor;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Sample {
+        flag: bool,
+        #[serde(with = "crate::byte_array")]
+        raw: [u8; 256],
+    }
+
+    /// Counts the individual storage calls made while serializing
+    #[derive(Default)]
+    struct CallCount {
+        inner: NibbleHVec<512>,
+        extends: usize,
+        bytes: usize,
+    }
+
+    impl NibbleFlavor for CallCount {
+        type Output = (usize, usize, heapless::Vec<u8, 512>);
+
+        fn try_extend(&mut self, data: &[u8]) -> crate::Result<()> {
+            self.extends += 1;
+            self.inner.try_extend(data)
+        }
+
+        fn try_push_u8(&mut self, data: u8) -> crate::Result<()> {
+            self.bytes += 1;
+            self.inner.try_push_u8(data)
+        }
+
+        fn try_push_nib(&mut self, nib: u8) -> crate::Result<()> {
+            self.inner.try_push_nib(nib)
+        }
+
+        fn finalize(self) -> crate::Result<Self::Output> {
+            Ok((self.extends, self.bytes, self.inner.finalize()?))
+        }
+    }
+
+    #[test]
+    fn single_bulk_copy() {
+        let mut raw = [0u8; 256];
+        raw.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+        let data = Sample { flag: true, raw };
+
+        let (extends, bytes, out) =
+            serialize_with_nibble_flavor(&data, CallCount::default()).unwrap();
+        assert_eq!(extends, 1);
+        assert_eq!(bytes, 0);
+        // bool, three nibble length prefix, then the bytes as-is
+        assert_eq!(&out[..2], &[0x1C, 0x80]);
+        assert_eq!(&out[2..], &raw[..]);
+
+        let back: Sample = crate::from_nibbles(&out).unwrap();
+        assert_eq!(back, data);
+
+        // The length has to match exactly
+        let short: heapless::Vec<u8, 16> = crate::to_nibble_vec(&(true, &raw[..4])).unwrap();
+        assert!(crate::from_nibbles::<Sample>(&short).is_err());
+    }
+}
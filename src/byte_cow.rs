@@ -0,0 +1,85 @@
+//! # Byte Cows
+//!
+//! Like a `Vec<u8>`, a `Cow<'a, [u8]>` is a sequence of `u8`s to `serde`, and
+//! it always deserializes as `Cow::Owned`, copying element by element.
+//!
+//! The [`byte_cow`](self) module, for use with `#[serde(borrow, with = ...)]`,
+//! encodes the data like [`byte_vec`](crate::byte_vec) does, a short length
+//! prefix followed by a single aligned run of bytes, whether the `Cow` is
+//! borrowed or owned. In the nibble format such runs are always aligned, so the
+//! decoded `Cow` is `Cow::Borrowed`, pointing into the input. Formats that can't
+//! lend out their input get a `Cow::Owned`.
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::Visitor;
+use serde::{Deserializer, Serializer};
+
+/// Serialize the data as a length prefixed run of bytes.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use std::borrow::Cow;
+/// #[derive(Serialize, Deserialize)]
+/// pub struct Blob<'a> {
+///     #[serde(borrow, with = "postcard::byte_cow")]
+///     data: Cow<'a, [u8]>,
+/// }
+///
+/// let blob = Blob { data: Cow::Owned(vec![1, 2, 3]) };
+/// let mut buf = [0u8; 8];
+/// let used = postcard::to_nibble_slice(&blob, &mut buf).unwrap();
+/// assert_eq!(used, &[0x30, 1, 2, 3]);
+///
+/// let back: Blob = postcard::from_nibbles(used).unwrap();
+/// assert!(matches!(back.data, Cow::Borrowed(&[1, 2, 3])));
+/// ```
+pub fn serialize<S>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(val)
+}
+
+/// Deserialize the data from a length prefixed run of bytes, borrowing it from the input if
+/// possible.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Cow<'de, [u8]>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(ByteCowVisitor)
+}
+
+struct ByteCowVisitor;
+
+impl<'de> Visitor<'de> for ByteCowVisitor {
+    type Value = Cow<'de, [u8]>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte slice")
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Cow::Borrowed(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Cow::Owned(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Cow::Owned(v))
+    }
+}
@@ -0,0 +1,58 @@
+//! # Byte Postcard Escape
+//!
+//! While migrating from the standard byte based postcard format to the nibble
+//! format, it can be useful to keep some values in their old encoding. The
+//! [`PostcardByteEscape`] wrapper serializes its contents with the byte format,
+//! and embeds the result as a length prefixed byte blob in the surrounding
+//! message, which works the same way in both formats.
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialize `T` using the byte postcard format, and embed it as a byte blob.
+///
+/// `N` is the size of the stack buffer used to hold the byte encoded value while
+/// serializing, serialization fails if the encoded value does not fit.
+///
+/// On the wire this is exactly the same as a `&[u8]` containing the output of
+/// `postcard::to_slice(&value)`, so the blob can also be extracted and decoded separately.
+///
+/// ```rust
+/// use postcard::byte_escape::PostcardByteEscape;
+///
+/// let mut buf = [0u8; 16];
+/// let used = postcard::to_slice(&PostcardByteEscape::<_, 8>(0x1234u16), &mut buf).unwrap();
+/// assert_eq!(used, &[0x02, 0xB4, 0x24]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PostcardByteEscape<T, const N: usize>(pub T);
+
+impl<T, const N: usize> Serialize for PostcardByteEscape<T, N>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf = [0u8; N];
+        let used = crate::to_slice(&self.0, &mut buf).map_err(ser::Error::custom)?;
+        serializer.serialize_bytes(used)
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for PostcardByteEscape<T, N>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = <&'de [u8]>::deserialize(deserializer)?;
+        let (value, rest) = crate::take_from_bytes(bytes).map_err(de::Error::custom)?;
+        if !rest.is_empty() {
+            return Err(de::Error::custom("trailing bytes in byte postcard blob"));
+        }
+        Ok(Self(value))
+    }
+}
@@ -0,0 +1,137 @@
+//! # Byte Vectors
+//!
+//! `serde` treats a `Vec<u8>` as a sequence of `u8`s, and every element is
+//! encoded on its own as a nibble varint. Decoding such a sequence has to go
+//! element by element, as the varints don't form a contiguous run of bytes.
+//!
+//! The [`byte_vec`](self) module, for use with `#[serde(with = ...)]`, is the
+//! `Vec<u8>` counterpart of [`byte_array`](crate::byte_array): the data is
+//! encoded like a byte slice, a short length prefix followed by a single aligned
+//! run of bytes. When decoding, the whole run is borrowed from the input with one
+//! `try_take_n` call and copied into the `Vec` in one go.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::Visitor;
+use serde::{Deserializer, Serializer};
+
+/// Serialize the vector as a length prefixed run of bytes.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// pub struct Blob {
+///     #[serde(with = "postcard::byte_vec")]
+///     data: Vec<u8>,
+/// }
+/// ```
+pub fn serialize<S>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(val)
+}
+
+/// Deserialize the vector from a length prefixed run of bytes.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_byte_buf(ByteVecVisitor)
+}
+
+struct ByteVecVisitor;
+
+impl<'de> Visitor<'de> for ByteVecVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte buffer")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let mut out = Vec::with_capacity(v.len());
+        out.extend_from_slice(v);
+        Ok(out)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vec;
+    use crate::de::nibble_deserializer::NibbleDeserializer;
+    use crate::de::nibble_flavors::{NibbleFlavor, NibbleSlice};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Sample {
+        flag: bool,
+        #[serde(with = "crate::byte_vec")]
+        raw: Vec<u8>,
+    }
+
+    /// Counts the individual storage calls made while deserializing
+    struct CallCount<'de> {
+        inner: NibbleSlice<'de>,
+        bulk: usize,
+        single: usize,
+    }
+
+    impl<'de> NibbleFlavor<'de> for CallCount<'de> {
+        type Remainder = (usize, usize);
+        type Source = &'de [u8];
+
+        fn try_take_nib(&mut self) -> crate::Result<u8> {
+            self.single += 1;
+            self.inner.try_take_nib()
+        }
+
+        fn try_take_u8(&mut self) -> crate::Result<u8> {
+            self.single += 1;
+            self.inner.try_take_u8()
+        }
+
+        fn try_take_n(&mut self, ct: usize) -> crate::Result<&'de [u8]> {
+            self.bulk += 1;
+            self.inner.try_take_n(ct)
+        }
+
+        fn finalize(self) -> crate::Result<Self::Remainder> {
+            Ok((self.bulk, self.single))
+        }
+    }
+
+    #[test]
+    fn single_bulk_copy() {
+        let raw: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+        let data = Sample { flag: true, raw };
+
+        let mut buf = [0u8; 4200];
+        let (used, _) = crate::to_nibble_slice_reserve(&data, &mut buf, 0).unwrap();
+
+        let mut deserializer = NibbleDeserializer::from_flavor(CallCount {
+            inner: NibbleSlice::new(used),
+            bulk: 0,
+            single: 0,
+        });
+        let back = Sample::deserialize(&mut deserializer).unwrap();
+        assert_eq!(back, data);
+
+        // The bool and the length prefix are read nibble by nibble, the data in one go
+        let (bulk, single) = deserializer.finalize().unwrap();
+        assert_eq!(bulk, 1);
+        assert!(single < 8, "{} single reads", single);
+    }
+}
@@ -1,4 +1,6 @@
 use cobs::{decode_in_place, decode_in_place_report};
+use core::marker::PhantomData;
+use serde::de::{MapAccess, Visitor};
 use serde::Deserialize;
 
 pub(crate) mod deserializer;
@@ -6,10 +8,21 @@ pub mod flavors;
 pub(crate) mod nibble_deserializer;
 pub mod nibble_flavors;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use crate::error::NibblePathError;
 use crate::error::{Error, Result};
 use deserializer::Deserializer;
 
-use self::nibble_deserializer::NibbleDeserializer;
+use self::nibble_deserializer::{FieldOffsetProbe, NibbleDeserializer, VariantProbe};
+use self::nibble_flavors::{
+    NibbleFieldTypes, NibbleFlavor, NibbleNamedVariants, NibblePackedBools, NibbleSeparated,
+    NibbleSlice, NibbleTwoLevelTags,
+};
+use crate::ser::nibble_flavors::NibbleTagMap;
+use crate::vlu32n::Vlu32N;
 
 /// Deserialize a message of type `T` from a byte slice. The unused portion (if any)
 /// of the byte slice is not returned.
@@ -24,6 +37,13 @@ where
 
 /// Deserialize a message of type `T` from a nibble byte slice. The unused portion (if any)
 /// of the byte slice is not returned.
+///
+/// ## Flattened fields
+///
+/// Structs with `#[serde(flatten)]` fields, such as a flattened `Option` of another struct,
+/// need the `alloc` feature, as serde buffers their entries. Such a struct is written as a
+/// map in which every field name and value is self-described, which is considerably larger
+/// than a regular struct, and 128-bit integers are limited to the 64-bit range in it.
 pub fn from_nibbles<'a, T>(s: &'a [u8]) -> Result<T>
 where
     T: Deserialize<'a>,
@@ -33,6 +53,765 @@ where
     Ok(t)
 }
 
+/// Deserialize a message of type `T` from a nibble byte slice like [`from_nibbles()`], but
+/// report where decoding failed along with the error.
+///
+/// The position is the [nibble offset](NibbleSlice::nibble_offset) at the point of failure: the
+/// nibbles before it were consumed, so for a truncated message it is usually the length of the
+/// input, or the start of a byte run that doesn't fit anymore.
+///
+/// ```rust
+/// use postcard::{from_nibbles_located, Error};
+///
+/// // A u8 and the first nibble of a two nibble u16
+/// assert_eq!(from_nibbles_located::<(u8, u16)>(&[0x79]), Err((Error::DeserializeUnexpectedEnd, 2)));
+/// ```
+pub fn from_nibbles_located<'a, T>(s: &'a [u8]) -> core::result::Result<T, (Error, usize)>
+where
+    T: Deserialize<'a>,
+{
+    let mut flavor = NibbleSlice::new(s);
+    let mut deserializer = NibbleDeserializer::from_flavor(&mut flavor);
+    let res = T::deserialize(&mut deserializer);
+    res.map_err(|e| (e, flavor.nibble_offset()))
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice like [`from_nibbles()`], and
+/// return the number of nibbles decoding it consumed along with it.
+///
+/// Unlike the remainder returned by [`take_from_nibbles()`], which starts at the next byte,
+/// the count is exact: a message ending in the middle of a byte doesn't include the padding
+/// nibble after it. Comparing it to a length declared elsewhere helps debugging framing.
+///
+/// ```rust
+/// use postcard::from_nibbles_measured;
+///
+/// assert_eq!(from_nibbles_measured::<(bool, u8)>(&[0x17, 0xFF]), Ok(((true, 7), 2)));
+/// assert_eq!(from_nibbles_measured::<bool>(&[0x10]), Ok((true, 1)));
+/// ```
+pub fn from_nibbles_measured<'a, T>(s: &'a [u8]) -> Result<(T, usize)>
+where
+    T: Deserialize<'a>,
+{
+    let mut flavor = NibbleSlice::new(s);
+    let mut deserializer = NibbleDeserializer::from_flavor(&mut flavor);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok((t, flavor.nibble_offset()))
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice into an existing value, instead
+/// of returning a new one. The unused portion (if any) of the byte slice is not returned.
+///
+/// Collections and strings reuse their allocations. Structs derived with serde are decoded
+/// field by field into `place` when the `deserialize-in-place` feature is enabled, avoiding
+/// the move of a large value, and are otherwise decoded as a whole and then assigned.
+///
+/// If decoding fails, `place` may be left partially overwritten.
+///
+/// ```rust
+/// let mut place = (false, 0u8);
+/// postcard::from_nibbles_in_place(&[0x17], &mut place).unwrap();
+/// assert_eq!(place, (true, 7));
+/// ```
+pub fn from_nibbles_in_place<'a, T>(s: &'a [u8], place: &mut T) -> Result<()>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    T::deserialize_in_place(&mut deserializer, place)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice, returning the unused portion of
+/// the byte slice, so that a stream of concatenated messages can be decoded in a loop.
+///
+/// Serialized messages always take up whole bytes, so the remainder is byte aligned as well:
+/// if the message ends in the middle of a byte, the padding nibble is skipped.
+///
+/// ```rust
+/// use postcard::take_from_nibbles;
+///
+/// // Two messages of three nibbles each, padded to whole bytes
+/// let (first, rest) = take_from_nibbles::<(u8, bool)>(&[0x91, 0x10, 0xA2, 0x00]).unwrap();
+/// assert_eq!(first, (9, true));
+/// assert_eq!(rest, &[0xA2, 0x00]);
+/// ```
+pub fn take_from_nibbles<'a, T>(s: &'a [u8]) -> Result<(T, &'a [u8])>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok((t, deserializer.finalize_aligned()?))
+}
+
+/// An iterator over a log of concatenated messages of type `T`, decoding one per call to
+/// `next()` with [`take_from_nibbles`].
+///
+/// The iteration ends with `None` once the input is used up exactly. A partial message at the
+/// end, or any other invalid one, yields its error, after which the iteration ends, since the
+/// start of the next message isn't known.
+///
+/// ```rust
+/// use postcard::{Error, NibbleSeqReader};
+///
+/// let mut reader = NibbleSeqReader::<(u8, bool)>::new(&[0x91, 0x10, 0xA2, 0x00, 0x91]);
+/// assert_eq!(reader.next(), Some(Ok((9, true))));
+/// assert_eq!(reader.next(), Some(Ok((18, false))));
+/// assert_eq!(reader.next(), Some(Err(Error::DeserializeUnexpectedEnd)));
+/// assert_eq!(reader.next(), None);
+/// ```
+pub struct NibbleSeqReader<'de, T> {
+    rest: &'de [u8],
+    _pl: PhantomData<T>,
+}
+
+impl<'de, T> NibbleSeqReader<'de, T> {
+    /// Create a reader for the messages in `s`
+    pub fn new(s: &'de [u8]) -> Self {
+        Self {
+            rest: s,
+            _pl: PhantomData,
+        }
+    }
+
+    /// The input not yet decoded, starting with the next message
+    pub fn remaining(&self) -> &'de [u8] {
+        self.rest
+    }
+}
+
+impl<'de, T> Iterator for NibbleSeqReader<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        match take_from_nibbles(self.rest) {
+            Ok((t, rest)) => {
+                self.rest = rest;
+                Some(Ok(t))
+            }
+            Err(e) => {
+                self.rest = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice that has to contain exactly
+/// this message.
+///
+/// Unlike [`from_nibbles`], which ignores anything after the message, leftover input results
+/// in [`Error::DeserializeUnexpectedLength`]. Only a zero padding nibble, completing the last
+/// byte of a message that ends in the middle of it, is accepted.
+///
+/// ```rust
+/// use postcard::{from_nibbles_strict, Error};
+///
+/// assert_eq!(from_nibbles_strict::<(u8, bool)>(&[0x91, 0x10]), Ok((9, true)));
+/// assert_eq!(
+///     from_nibbles_strict::<(u8, bool)>(&[0x91, 0x10, 0x00]),
+///     Err(Error::DeserializeUnexpectedLength)
+/// );
+/// ```
+pub fn from_nibbles_strict<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    let t = T::deserialize(&mut deserializer)?;
+    deserializer.finalize_strict()?;
+    Ok(t)
+}
+
+/// Deserialize a length delimited frame produced by `to_nibble_frame()` from the start of `s`,
+/// returning the message and the bytes after the frame, such as the next frame.
+///
+/// The message has to take exactly the declared number of bytes, otherwise the frame fails
+/// with [`Error::DeserializeUnexpectedLength`], like [`from_nibbles_strict`]. A frame longer
+/// than `s` results in [`Error::DeserializeUnexpectedEnd`].
+///
+/// ```rust
+/// use postcard::{read_nibble_frame, Error};
+///
+/// // A second frame of two bytes is cut short
+/// let stream = [0x10, 0x17, 0x20, 0x30];
+/// let (first, rest) = read_nibble_frame::<(bool, u8)>(&stream).unwrap();
+/// assert_eq!(first, (true, 7));
+/// assert_eq!(rest, &[0x20, 0x30]);
+/// assert_eq!(
+///     read_nibble_frame::<(u8, u8, u8)>(rest),
+///     Err(Error::DeserializeUnexpectedEnd)
+/// );
+/// ```
+pub fn read_nibble_frame<'a, T>(s: &'a [u8]) -> Result<(T, &'a [u8])>
+where
+    T: Deserialize<'a>,
+{
+    let mut flavor = NibbleSlice::new(s);
+    let len = Vlu32N::de(&mut flavor)?.0 as usize;
+    // The length is padded to a whole byte
+    let header = (s.len() * 2 - flavor.nibbles_left()).div_ceil(2);
+    let end = header
+        .checked_add(len)
+        .filter(|end| *end <= s.len())
+        .ok_or(Error::DeserializeUnexpectedEnd)?;
+    let t = from_nibbles_strict(&s[header..end])?;
+    Ok((t, &s[end..]))
+}
+
+/// Deserialize a message of type `T` followed by a raw blob of `trailer_len` bytes, whose length
+/// is known from outside the nibble stream, e.g. from a transport header. Returns the message
+/// and the borrowed blob.
+///
+/// The message has to take up exactly the bytes in front of the blob, otherwise this fails
+/// with [`Error::DeserializeUnexpectedLength`], like [`from_nibbles_strict`]. A `trailer_len`
+/// longer than `data` results in [`Error::DeserializeUnexpectedEnd`].
+///
+/// ```rust
+/// use postcard::{from_nibbles_with_trailer, Error};
+///
+/// let data = [0x17, 0xDE, 0xAD];
+/// assert_eq!(
+///     from_nibbles_with_trailer::<(bool, u8)>(&data, 2),
+///     Ok(((true, 7), &[0xDE, 0xAD][..]))
+/// );
+/// assert_eq!(
+///     from_nibbles_with_trailer::<(bool, u8)>(&data, 1),
+///     Err(Error::DeserializeUnexpectedLength)
+/// );
+/// ```
+pub fn from_nibbles_with_trailer<'a, T>(data: &'a [u8], trailer_len: usize) -> Result<(T, &'a [u8])>
+where
+    T: Deserialize<'a>,
+{
+    let split = data
+        .len()
+        .checked_sub(trailer_len)
+        .ok_or(Error::DeserializeUnexpectedEnd)?;
+    let (message, trailer) = data.split_at(split);
+    let t = from_nibbles_strict(message)?;
+    Ok((t, trailer))
+}
+
+/// Deserialize a message of type `T` using any nibble deserialization flavor, returning the
+/// value together with the flavor's remainder.
+///
+/// This is how modifier flavors are placed in front of the storage flavor, e.g. to check the
+/// data while it is being decoded.
+///
+/// ```rust
+/// use postcard::{from_nibbles_with_flavor, de_nibble_flavors::NibbleSlice};
+///
+/// let (value, rest) = from_nibbles_with_flavor::<(bool, u8), _>(
+///     NibbleSlice::new(&[0x17, 0xAB]),
+/// ).unwrap();
+/// assert_eq!(value, (true, 7));
+/// assert_eq!(rest, &[0xAB]);
+/// ```
+pub fn from_nibbles_with_flavor<'de, T, F>(flavor: F) -> Result<(T, F::Remainder)>
+where
+    T: Deserialize<'de>,
+    F: NibbleFlavor<'de>,
+{
+    let mut deserializer = NibbleDeserializer::from_flavor(flavor);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok((t, deserializer.finalize()?))
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice, and pass the result to `f`.
+///
+/// This is a scoped form of [`from_nibbles`]: any data `T` borrows from `s` is only
+/// handed to the closure, which returns whatever owned projection the caller needs. The
+/// result has to be `'static`, so that borrowed data can't leave the closure:
+///
+/// ```rust
+/// use postcard::with_nibbles;
+///
+/// let data = [0x20, b'H', b'i'];
+/// let len = with_nibbles(&data, |res: postcard::Result<&str>| res.map(|s| s.len()));
+/// assert_eq!(len, Ok(2));
+/// ```
+///
+/// ```compile_fail
+/// fn leak(data: &[u8]) -> &str {
+///     postcard::with_nibbles(data, |res: postcard::Result<&str>| res.unwrap())
+/// }
+/// ```
+pub fn with_nibbles<'a, T, R, F>(s: &'a [u8], f: F) -> R
+where
+    T: Deserialize<'a>,
+    R: 'static,
+    F: FnOnce(Result<T>) -> R,
+{
+    f(from_nibbles(s))
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice, collecting [`Warning`]s about
+/// non-fatal encoding issues instead of silently accepting them.
+///
+/// [`Warning`]: crate::de_nibble_flavors::Warning
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn from_nibbles_lenient<'a, T>(
+    s: &'a [u8],
+) -> Result<(T, alloc::vec::Vec<nibble_flavors::Warning>)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_flavor(nibble_flavors::NibbleLenient::new(s));
+    let t = T::deserialize(&mut deserializer)?;
+    let (_rest, warnings) = deserializer.finalize()?;
+    Ok((t, warnings))
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice like [`from_nibbles()`], but
+/// report the path to the value that failed along with the error, see [`NibblePathError`].
+///
+/// Tracking the path costs a little time for every value, so this is meant for debugging.
+///
+/// ```rust
+/// use postcard::{from_nibbles_with_path, Error};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Outer {
+///     id: u8,
+///     items: Vec<(u8, bool)>,
+/// }
+///
+/// // The bool of the second item is 7
+/// let err = from_nibbles_with_path::<Outer>(&[0x12, 0x31, 0x27]).unwrap_err();
+/// assert_eq!(err.error, Error::DeserializeBadBool);
+/// assert_eq!(err.path, "items[1][1]");
+/// ```
+///
+/// [`NibblePathError`]: crate::NibblePathError
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn from_nibbles_with_path<'a, T>(s: &'a [u8]) -> core::result::Result<T, NibblePathError>
+where
+    T: Deserialize<'a>,
+{
+    use self::nibble_deserializer::PathSegment;
+    use core::fmt::Write;
+
+    let mut deserializer = NibbleDeserializer::from_bytes(s).with_path();
+    T::deserialize(&mut deserializer).map_err(|error| {
+        let mut path = alloc::string::String::new();
+        for segment in deserializer.path().unwrap_or_default() {
+            // Writing to a string can't fail
+            let _ = match segment {
+                PathSegment::Field(name) if path.is_empty() => write!(path, "{}", name),
+                PathSegment::Field(name) => write!(path, ".{}", name),
+                PathSegment::Index(index) => write!(path, "[{}]", index),
+                PathSegment::Variant(name) if path.is_empty() => write!(path, "{}", name),
+                PathSegment::Variant(name) => write!(path, "::{}", name),
+            };
+        }
+        NibblePathError { error, path }
+    })
+}
+
+/// Deserialize a message of type `T` from a string of hex digits, as written by
+/// `to_nibble_hex()`. Upper and lower case digits are accepted, and an odd number of digits
+/// is padded with a zero nibble.
+///
+/// Any other character, including whitespace, results in [`Error::DeserializeBadHex`].
+///
+/// ```rust
+/// use postcard::from_nibble_hex;
+///
+/// assert_eq!(from_nibble_hex::<(bool, u8)>("17"), Ok((true, 7)));
+/// assert_eq!(from_nibble_hex::<u8>("5"), Ok(5));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn from_nibble_hex<T>(s: &str) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let mut bytes = alloc::vec::Vec::with_capacity(s.len().div_ceil(2));
+    for (i, c) in s.chars().enumerate() {
+        let nib = c.to_digit(16).ok_or(Error::DeserializeBadHex)? as u8;
+        if i % 2 == 0 {
+            bytes.push(nib << 4);
+        } else if let Some(last) = bytes.last_mut() {
+            *last |= nib;
+        }
+    }
+    from_nibbles(&bytes)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_separated()`, checking the separator nibble after every struct field.
+pub fn from_nibbles_separated<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer =
+        NibbleDeserializer::from_flavor(NibbleSeparated::new(NibbleSlice::new(s)));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_field_types()`, checking the type tag in front of every struct field.
+pub fn from_nibbles_field_types<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer =
+        NibbleDeserializer::from_flavor(NibbleFieldTypes::new(NibbleSlice::new(s)));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice, failing with
+/// [`Error::DeserializeStepLimit`] once decoding takes more than `max_steps` steps.
+///
+/// Every `serde::Deserializer` method call and every read from the input is a step, see
+/// [`NibbleStepLimit`](nibble_flavors::NibbleStepLimit). This bounds the work spent on
+/// untrusted input, which the size of the input alone doesn't.
+pub fn from_nibbles_limited<'a, T>(s: &'a [u8], max_steps: usize) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_flavor(nibble_flavors::NibbleStepLimit::new(
+        NibbleSlice::new(s),
+        max_steps,
+    ));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice, failing with
+/// [`Error::DeserializeMaxDepthExceeded`] if sequences, maps, structs, enums, `Option`s or
+/// newtype structs are nested more than `max_depth` levels deep.
+///
+/// The other entry points allow [`NIBBLE_DEFAULT_MAX_DEPTH`](crate::NIBBLE_DEFAULT_MAX_DEPTH)
+/// levels, this one is for types that legitimately nest deeper, or to be stricter with
+/// untrusted input.
+pub fn from_nibbles_with_depth<'a, T>(s: &'a [u8], max_depth: usize) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s).with_max_depth(max_depth);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_prefix_versioned()`, which may have been written by a newer version of `T`.
+///
+/// Newer versions of a struct may append fields, which are ignored: `T` only decodes the common
+/// prefix. If the message has fewer fields than `T`, i.e. it was written by an older version,
+/// decoding fails with [`Error::DeserializeMissingFields`], see
+/// [`NibbleFieldLimit`](nibble_flavors::NibbleFieldLimit).
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize)]
+/// struct SampleV2 { id: u8, ok: bool, name: &'static str }
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct SampleV1 { id: u8, ok: bool }
+///
+/// let out: heapless::Vec<u8, 8> = postcard::to_nibble_vec_prefix_versioned(&SampleV2 { id: 5, ok: true, name: "x" }).unwrap();
+/// let old: SampleV1 = postcard::from_nibbles_prefix_versioned(&out).unwrap();
+/// assert_eq!(old, SampleV1 { id: 5, ok: true });
+/// ```
+pub fn from_nibbles_prefix_versioned<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut flavor = NibbleSlice::new(s);
+    let fields = Vlu32N::de(&mut flavor)?.0;
+    let mut deserializer =
+        NibbleDeserializer::from_flavor(nibble_flavors::NibbleFieldLimit::new(flavor, fields));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_packed()`, where consecutive booleans share nibbles.
+pub fn from_nibbles_packed<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer =
+        NibbleDeserializer::from_flavor(NibblePackedBools::new(NibbleSlice::new(s)));
+    let t = T::deserialize(&mut deserializer)?;
+    deserializer.finalize()?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_xor()`, restoring it with the same `key` in place first.
+///
+/// See [`NibbleXor`](nibble_flavors::NibbleXor). A wrong key most likely results in an error,
+/// but may also decode to different values.
+pub fn from_nibbles_xor<'a, T>(s: &'a mut [u8], key: &[u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_flavor(nibble_flavors::NibbleXor::new(s, key));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_with_tags()`, decoding two-level enum tags with the same `map`.
+pub fn from_nibbles_with_tags<'a, T, M>(s: &'a [u8], map: M) -> Result<T>
+where
+    T: Deserialize<'a>,
+    M: NibbleTagMap + 'a,
+{
+    let mut deserializer =
+        NibbleDeserializer::from_flavor(NibbleTwoLevelTags::new(NibbleSlice::new(s), map));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice produced by
+/// `to_nibble_vec_named_variants()`, matching enum variants by their name.
+pub fn from_nibbles_named_variants<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer =
+        NibbleDeserializer::from_flavor(NibbleNamedVariants::new(NibbleSlice::new(s)));
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize exactly `N` consecutive values of type `T` from a nibble byte slice, for
+/// arrays that are sent without a length prefix because both sides know the count.
+///
+/// The unused portion of the byte slice is returned. Like with [`take_from_nibbles`], it is
+/// byte aligned: if the last value ends in the middle of a byte, the padding nibble is skipped.
+///
+/// ```rust
+/// use postcard::from_nibbles_seq_n;
+///
+/// let (values, rest) = from_nibbles_seq_n::<u8, 4>(&[0x12, 0x34, 0xAB]).unwrap();
+/// assert_eq!(values, [1, 2, 3, 4]);
+/// assert_eq!(rest, &[0xAB]);
+///
+/// let (values, rest) = from_nibbles_seq_n::<u8, 3>(&[0x12, 0x30, 0xAB]).unwrap();
+/// assert_eq!(values, [1, 2, 3]);
+/// assert_eq!(rest, &[0xAB]);
+/// ```
+pub fn from_nibbles_seq_n<'a, T, const N: usize>(s: &'a [u8]) -> Result<([T; N], &'a [u8])>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    let mut err = None;
+    let values = [(); N].map(|_| {
+        if err.is_some() {
+            return None;
+        }
+        T::deserialize(&mut deserializer)
+            .map_err(|e| err = Some(e))
+            .ok()
+    });
+    if let Some(e) = err {
+        return Err(e);
+    }
+    // No element failed, so every slot is filled
+    let values = values.map(|v| v.unwrap());
+    Ok((values, deserializer.finalize_aligned()?))
+}
+
+/// Deserialize a message of type `T` from a nibble byte slice, also returning the
+/// variant index that was decoded if `T` is an enum.
+///
+/// Only the top-level discriminant is reported; for any other `T` (including structs
+/// containing enums) the index is `None`.
+pub fn from_nibbles_with_variant<'a, T>(s: &'a [u8]) -> Result<(T, Option<u32>)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    let mut probe = VariantProbe {
+        deserializer: &mut deserializer,
+        variant: None,
+    };
+    let t = T::deserialize(&mut probe)?;
+    Ok((t, probe.variant))
+}
+
+/// Read the discriminant of a nibble encoded top-level enum, without decoding the payload.
+///
+/// This is cheaper than [`from_nibbles_with_variant`] when a dispatcher only needs to know
+/// the variant. Nothing checks that the message actually starts with an enum, or that the
+/// payload is valid.
+///
+/// ```rust
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// enum Command {
+///     Stop,
+///     Move(u16),
+/// }
+///
+/// let mut buf = [0u8; 8];
+/// let ser = postcard::to_nibble_slice(&Command::Move(500), &mut buf).unwrap();
+/// assert_eq!(postcard::peek_nibble_discriminant(ser), Ok(1));
+/// ```
+pub fn peek_nibble_discriminant(data: &[u8]) -> Result<u32> {
+    Vlu32N::de(&mut NibbleSlice::new(data)).map(|v| v.0)
+}
+
+/// Split a buffer of concatenated frames into the byte ranges of the individual frames,
+/// without decoding the messages.
+///
+/// Each frame is a nibble varint holding the length of the message in nibbles, followed by
+/// the message, as checked by [`NibbleFramedCheck`](nibble_flavors::NibbleFramedCheck). A
+/// frame ends at the byte boundary after its message, so every range can be decoded on its
+/// own later on. A truncated last frame results in [`Error::DeserializeUnexpectedEnd`].
+///
+/// ```rust
+/// // `(true, 26u8)` in three nibbles, then `(true, 5u8)` in two
+/// let ranges = postcard::frame_ranges(&[0x31, 0xB2, 0x21, 0x50]).unwrap();
+/// assert_eq!(ranges, [0..2, 2..4]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn frame_ranges(data: &[u8]) -> Result<alloc::vec::Vec<core::ops::Range<usize>>> {
+    let mut ranges = alloc::vec::Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let mut flav = NibbleSlice::new(&data[start..]);
+        let len = Vlu32N::de(&mut flav)?.0 as usize;
+        let header = (data.len() - start) * 2 - flav.nibbles_left();
+        let end = header
+            .checked_add(len)
+            .map(|nibbles| start + nibbles.div_ceil(2))
+            .filter(|end| *end <= data.len())
+            .ok_or(Error::DeserializeUnexpectedEnd)?;
+        ranges.push(start..end);
+        start = end;
+    }
+    Ok(ranges)
+}
+
+/// Locate the start of the top-level struct or tuple field at `field_index` in a nibble
+/// encoded message of type `T`, without decoding the remaining fields.
+///
+/// Returns the byte offset of the field and whether it starts at a byte boundary. When
+/// the boundary flag is `false`, the field starts in the low nibble of that byte.
+///
+/// The preceding fields still need to be decoded to find their length. An index past
+/// the last field returns [`Error::SerdeDeCustom`].
+pub fn nibble_field_offset<'a, T>(s: &'a [u8], field_index: usize) -> Result<(usize, bool)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    let mut probe = FieldOffsetProbe {
+        deserializer: &mut deserializer,
+        total_nibbles: s.len() * 2,
+        target: field_index,
+        offset: None,
+    };
+    let res = T::deserialize(&mut probe);
+    match (probe.offset, res) {
+        (Some(nibbles), _) => Ok((nibbles / 2, nibbles % 2 == 0)),
+        (None, Err(e)) => Err(e),
+        (None, Ok(_)) => Err(Error::SerdeDeCustom),
+    }
+}
+
+/// Deserialize a message of type `T` from a frame that was front-padded with zero bytes by
+/// the `NibbleMinSizeFront` flavor.
+///
+/// `payload_len` is the size of the serialized message in bytes, typically carried out-of-band
+/// in a transport header. Everything before the payload must be zero padding, otherwise
+/// [`Error::DeserializeBadEncoding`] is returned.
+pub fn from_nibbles_min_size_front<'a, T>(s: &'a [u8], payload_len: usize) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let pad = s
+        .len()
+        .checked_sub(payload_len)
+        .ok_or(Error::DeserializeUnexpectedEnd)?;
+    let (padding, payload) = s.split_at(pad);
+    if padding.iter().any(|b| *b != 0) {
+        return Err(Error::DeserializeBadEncoding);
+    }
+    from_nibbles(payload)
+}
+
+/// Deserialize a nibble encoded map entry by entry, folding each decoded key/value
+/// pair into an accumulator instead of building a container. The unused portion
+/// (if any) of the byte slice is returned for further usage. If the map ends inside
+/// a byte, the rest of that byte is padding and is skipped.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::fold_nibble_map;
+///
+/// // Two entries: 1 => 5, 2 => 6
+/// let data = [0x21, 0x52, 0x60, 0xAA];
+/// let (sum, rest) = fold_nibble_map(&data, 0u32, |acc, _k: u8, v: u8| acc + v as u32).unwrap();
+/// assert_eq!(sum, 11);
+/// assert_eq!(rest, &[0xAA]);
+/// ```
+pub fn fold_nibble_map<'a, K, V, A, F>(s: &'a [u8], init: A, f: F) -> Result<(A, &'a [u8])>
+where
+    K: Deserialize<'a>,
+    V: Deserialize<'a>,
+    F: FnMut(A, K, V) -> A,
+{
+    let mut deserializer = NibbleDeserializer::from_bytes(s);
+    let acc = serde::Deserializer::deserialize_map(
+        &mut deserializer,
+        FoldMap {
+            acc: init,
+            f,
+            _pd: PhantomData,
+        },
+    )?;
+    Ok((acc, deserializer.finalize_aligned()?))
+}
+
+struct FoldMap<K, V, A, F> {
+    acc: A,
+    f: F,
+    _pd: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'de, K, V, A, F> Visitor<'de> for FoldMap<K, V, A, F>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    F: FnMut(A, K, V) -> A,
+{
+    type Value = A;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<M>(mut self, mut map: M) -> core::result::Result<A, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        while let Some((k, v)) = map.next_entry()? {
+            self.acc = (self.f)(self.acc, k, v);
+        }
+        Ok(self.acc)
+    }
+}
+
 /// Deserialize a message of type `T` from a cobs-encoded byte slice. The
 /// unused portion (if any) of the byte slice is not returned.
 /// The used portion of the input slice is modified during deserialization (even if an error is returned).
@@ -45,6 +824,18 @@ where
     from_bytes::<T>(&s[..sz])
 }
 
+/// Deserialize a message of type `T` from a cobs-encoded nibble frame, as written by
+/// `to_nibble_vec_cobs()`. The unused portion (if any) of the byte slice is not returned.
+/// The used portion of the input slice is modified during deserialization (even if an error is returned).
+/// Therefore, if this is not desired, pass a clone of the original slice.
+pub fn from_nibbles_cobs<'a, T>(s: &'a mut [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let sz = decode_in_place(s).map_err(|_| Error::DeserializeBadEncoding)?;
+    from_nibbles::<T>(&s[..sz])
+}
+
 /// Deserialize a message of type `T` from a cobs-encoded byte slice. The
 /// unused portion (if any) of the byte slice is returned for further usage.
 /// The used portion of the input slice is modified during deserialization (even if an error is returned).
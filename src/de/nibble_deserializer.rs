@@ -1,32 +1,127 @@
+use core::convert::TryFrom;
 use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
 
 use crate::de::nibble_flavors::{NibbleFlavor, NibbleSlice};
 use crate::error::{Error, Result};
+use crate::nibble_decode::{RawRead, DECODE_NAME};
+use crate::ser::nibble_flavors::NibbleFieldType;
+use crate::ser::nibble_serializer::{tag, UNSIZED_MAP};
+use crate::untagged::UNTAGGED_NAME;
 use crate::varint::{max_of_last_byte, varint_max};
-use crate::vlu32n::Vlu32N;
+use crate::vlu32n::{Vlu128N, Vlu32N, Vlu64N};
+use crate::NIBBLE_DEFAULT_MAX_DEPTH;
 use core::marker::PhantomData;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// A step on the way from the message to the value being decoded, see
+/// [`NibbleDeserializer::with_path()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PathSegment {
+    /// A struct field
+    Field(&'static str),
+    /// An element of a sequence or tuple, or an entry of a map
+    Index(usize),
+    /// The variant of an enum
+    Variant(&'static str),
+}
+
 /// A `serde` compatible deserializer, generic over “Flavors” of deserializing plugins.
 ///
 /// Please note that postcard messages are not self-describing and therefore incompatible with
 /// [internally tagged enums](https://serde.rs/enum-representations.html#internally-tagged).
 pub struct NibbleDeserializer<'de, F: NibbleFlavor<'de>> {
     flavor: F,
+    /// The number of further nesting levels allowed
+    depth: usize,
+    /// The path to the value being decoded, if tracked
+    #[cfg(feature = "alloc")]
+    path: Option<alloc::vec::Vec<PathSegment>>,
     _plt: PhantomData<&'de ()>,
 }
 
 impl<'de, F> NibbleDeserializer<'de, F>
 where
-    F: NibbleFlavor<'de> + 'de,
+    F: NibbleFlavor<'de>,
 {
     /// Obtain a Deserializer from a slice of bytes
     pub fn from_flavor(flavor: F) -> Self {
         NibbleDeserializer {
             flavor,
+            depth: NIBBLE_DEFAULT_MAX_DEPTH,
+            #[cfg(feature = "alloc")]
+            path: None,
             _plt: PhantomData,
         }
     }
 
+    /// Limit how deeply sequences, maps, structs, enums, `Option`s and newtype structs may be
+    /// nested, instead of
+    /// [`NIBBLE_DEFAULT_MAX_DEPTH`]. Going deeper fails with [`Error::DeserializeMaxDepthExceeded`],
+    /// so that a hostile message can't exhaust the stack.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.depth = max_depth;
+        self
+    }
+
+    /// Track the path to the value being decoded. After a failure, [`path()`](Self::path)
+    /// leads to the value that failed.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn with_path(mut self) -> Self {
+        self.path = Some(alloc::vec::Vec::new());
+        self
+    }
+
+    /// The path to the value being decoded, or that failed to decode, if tracked
+    #[cfg(feature = "alloc")]
+    pub(crate) fn path(&self) -> Option<&[PathSegment]> {
+        self.path.as_deref()
+    }
+
+    /// Step into a value, returning where to go back to with [`leave()`](Self::leave) once
+    /// it was decoded. A failing value isn't left, so the path leads to it.
+    #[inline]
+    fn enter(&mut self, _segment: PathSegment) -> usize {
+        #[cfg(feature = "alloc")]
+        if let Some(path) = &mut self.path {
+            path.push(_segment);
+            return path.len() - 1;
+        }
+        0
+    }
+
+    /// Go back to a position previously returned by [`enter()`](Self::enter)
+    #[inline]
+    fn leave(&mut self, _to: usize) {
+        #[cfg(feature = "alloc")]
+        if let Some(path) = &mut self.path {
+            path.truncate(_to);
+        }
+    }
+
+    /// The current length of the path, to go back to with [`leave()`](Self::leave)
+    #[inline]
+    fn path_len(&self) -> usize {
+        #[cfg(feature = "alloc")]
+        if let Some(path) = &self.path {
+            return path.len();
+        }
+        0
+    }
+
+    /// Decode a nested value with `f`, one level deeper
+    #[inline]
+    fn nested<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        self.depth = self
+            .depth
+            .checked_sub(1)
+            .ok_or(Error::DeserializeMaxDepthExceeded)?;
+        let res = f(self);
+        self.depth += 1;
+        res
+    }
+
     /// Return the remaining (unused) bytes in the Deserializer along with any
     /// additional data provided by the [`Flavor`]
     pub fn finalize(self) -> Result<F::Remainder> {
@@ -39,9 +134,30 @@ impl<'de> NibbleDeserializer<'de, NibbleSlice<'de>> {
     pub fn from_bytes(input: &'de [u8]) -> Self {
         NibbleDeserializer {
             flavor: NibbleSlice::new(input),
+            depth: NIBBLE_DEFAULT_MAX_DEPTH,
+            #[cfg(feature = "alloc")]
+            path: None,
             _plt: PhantomData,
         }
     }
+
+    /// Return the remaining (unused) bytes, starting at the first byte boundary after the
+    /// data that was read
+    pub(crate) fn finalize_aligned(mut self) -> Result<&'de [u8]> {
+        // Taking no bytes skips the padding nibble, if any
+        self.flavor.try_take_n(0)?;
+        self.flavor.finalize()
+    }
+
+    /// Check that nothing is left over, except for a zero padding nibble completing the last
+    /// byte
+    pub(crate) fn finalize_strict(mut self) -> Result<()> {
+        match self.flavor.nibbles_left() {
+            0 => Ok(()),
+            1 if self.flavor.try_take_nib()? == 0 => Ok(()),
+            _ => Err(Error::DeserializeUnexpectedLength),
+        }
+    }
 }
 
 impl<'de, F: NibbleFlavor<'de>> NibbleDeserializer<'de, F> {
@@ -85,9 +201,11 @@ impl<'de, F: NibbleFlavor<'de>> NibbleDeserializer<'de, F> {
             if (val & 0x80) == 0 {
                 if i == varint_max::<u16>() - 1 && val > max_of_last_byte::<u16>() {
                     return Err(Error::DeserializeBadVarint);
-                } else {
-                    return Ok(out);
                 }
+                if i != 0 && val == 0 {
+                    self.flavor.non_canonical_varint()?;
+                }
+                return Ok(out);
             }
         }
         Err(Error::DeserializeBadVarint)
@@ -104,9 +222,11 @@ impl<'de, F: NibbleFlavor<'de>> NibbleDeserializer<'de, F> {
             if (val & 0x80) == 0 {
                 if i == varint_max::<u32>() - 1 && val > max_of_last_byte::<u32>() {
                     return Err(Error::DeserializeBadVarint);
-                } else {
-                    return Ok(out);
                 }
+                if i != 0 && val == 0 {
+                    self.flavor.non_canonical_varint()?;
+                }
+                return Ok(out);
             }
         }
         Err(Error::DeserializeBadVarint)
@@ -114,46 +234,22 @@ impl<'de, F: NibbleFlavor<'de>> NibbleDeserializer<'de, F> {
 
     #[inline]
     fn try_take_varint_u64(&mut self) -> Result<u64> {
-        let mut out = 0;
-        for i in 0..varint_max::<u64>() {
-            let val = self.flavor.try_take_u8()?;
-            let carry = (val & 0x7F) as u64;
-            out |= carry << (7 * i);
-
-            if (val & 0x80) == 0 {
-                if i == varint_max::<u64>() - 1 && val > max_of_last_byte::<u64>() {
-                    return Err(Error::DeserializeBadVarint);
-                } else {
-                    return Ok(out);
-                }
-            }
-        }
-        Err(Error::DeserializeBadVarint)
+        Vlu64N::de(&mut self.flavor).map(|v| v.0)
     }
 
     #[inline]
     fn try_take_varint_u128(&mut self) -> Result<u128> {
-        let mut out = 0;
-        for i in 0..varint_max::<u128>() {
-            let val = self.flavor.try_take_u8()?;
-            let carry = (val & 0x7F) as u128;
-            out |= carry << (7 * i);
-
-            if (val & 0x80) == 0 {
-                if i == varint_max::<u128>() - 1 && val > max_of_last_byte::<u128>() {
-                    return Err(Error::DeserializeBadVarint);
-                } else {
-                    return Ok(out);
-                }
-            }
-        }
-        Err(Error::DeserializeBadVarint)
+        Vlu128N::de(&mut self.flavor).map(|v| v.0)
     }
 }
 
 struct SeqAccess<'a, 'b: 'a, F: NibbleFlavor<'b>> {
     deserializer: &'a mut NibbleDeserializer<'b, F>,
     len: usize,
+    /// The names of the elements if they are struct fields, the flavor is notified after each one
+    fields: Option<&'static [&'static str]>,
+    /// The position of the next element
+    index: usize,
 }
 
 impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::SeqAccess<'b> for SeqAccess<'a, 'b, F> {
@@ -163,10 +259,21 @@ impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::SeqAccess<'b> for SeqAccess<'a,
     fn next_element_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
         if self.len > 0 {
             self.len -= 1;
-            Ok(Some(DeserializeSeed::deserialize(
-                seed,
-                &mut *self.deserializer,
-            )?))
+            let segment = match self.fields {
+                Some(fields) => PathSegment::Field(fields.get(self.index).copied().unwrap_or("?")),
+                None => PathSegment::Index(self.index),
+            };
+            self.index += 1;
+            let back = self.deserializer.enter(segment);
+            if self.fields.is_some() {
+                self.deserializer.flavor.field_start()?;
+            }
+            let value = DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
+            if self.fields.is_some() {
+                self.deserializer.flavor.field_end()?;
+            }
+            self.deserializer.leave(back);
+            Ok(Some(value))
         } else {
             Ok(None)
         }
@@ -178,9 +285,114 @@ impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::SeqAccess<'b> for SeqAccess<'a,
     }
 }
 
+/// Yields the first alternative of a `NibbleUntagged`, or nothing if it fails to decode, in
+/// which case the input is rewound for the second alternative
+struct UntaggedAccess<'a, 'b: 'a, F: NibbleFlavor<'b>> {
+    deserializer: &'a mut NibbleDeserializer<'b, F>,
+    index: usize,
+}
+
+impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::SeqAccess<'b> for UntaggedAccess<'a, 'b, F> {
+    type Error = Error;
+
+    fn next_element_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
+        self.index += 1;
+        match self.index {
+            1 => {
+                let mark = match self.deserializer.flavor.mark() {
+                    Some(mark) => mark,
+                    // Can't rewind, so there is no second chance
+                    None => return seed.deserialize(&mut *self.deserializer).map(Some),
+                };
+                let path_len = self.deserializer.path_len();
+                match seed.deserialize(&mut *self.deserializer) {
+                    Ok(value) => Ok(Some(value)),
+                    Err(_) => {
+                        self.deserializer.leave(path_len);
+                        self.deserializer.flavor.reset_to(mark)?;
+                        Ok(None)
+                    }
+                }
+            }
+            2 => seed.deserialize(&mut *self.deserializer).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Offers raw reads of the nibble stream to a `NibbleDecode` implementation, as a sequence
+/// of elements requested with `RawRead` seeds
+struct RawAccess<'a, 'b: 'a, F: NibbleFlavor<'b>> {
+    deserializer: &'a mut NibbleDeserializer<'b, F>,
+    /// The error of the failed read, if any
+    error: Option<Error>,
+}
+
+impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::SeqAccess<'b> for &mut RawAccess<'a, 'b, F> {
+    type Error = Error;
+
+    fn next_element_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
+        seed.deserialize(RawDeserializer { access: self }).map(Some)
+    }
+}
+
+struct RawDeserializer<'r, 'a, 'b: 'a, F: NibbleFlavor<'b>> {
+    access: &'r mut RawAccess<'a, 'b, F>,
+}
+
+impl<'r, 'a, 'b: 'a, F: NibbleFlavor<'b>> RawDeserializer<'r, 'a, 'b, F> {
+    fn keep<T>(&mut self, res: Result<T>) -> Result<T> {
+        if let Err(e) = &res {
+            self.access.error = Some(e.clone());
+        }
+        res
+    }
+}
+
+impl<'r, 'a, 'b: 'a, F: NibbleFlavor<'b>> de::Deserializer<'b> for RawDeserializer<'r, 'a, 'b, F> {
+    type Error = Error;
+
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'b>,
+    {
+        let flavor = &mut self.access.deserializer.flavor;
+        match RawRead::from_len(len) {
+            RawRead::Nib => {
+                let res = flavor.try_take_nib();
+                visitor.visit_u8(self.keep(res)?)
+            }
+            RawRead::U8 => {
+                let res = flavor.try_take_u8();
+                visitor.visit_u8(self.keep(res)?)
+            }
+            RawRead::N(ct) => {
+                let res = flavor.try_take_n(ct);
+                visitor.visit_borrowed_bytes(self.keep(res)?)
+            }
+        }
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'b>,
+    {
+        Err(Error::WontImplement)
+    }
+
+    serde::forward_to_deserialize_any! {
+        <W: Visitor<'b>>
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
 struct MapAccess<'a, 'b: 'a, F: NibbleFlavor<'b>> {
     deserializer: &'a mut NibbleDeserializer<'b, F>,
     len: usize,
+    /// The position of the next entry
+    index: usize,
 }
 
 impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::MapAccess<'b> for MapAccess<'a, 'b, F> {
@@ -190,6 +402,9 @@ impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::MapAccess<'b> for MapAccess<'a,
     fn next_key_seed<K: DeserializeSeed<'b>>(&mut self, seed: K) -> Result<Option<K::Value>> {
         if self.len > 0 {
             self.len -= 1;
+            // Left once the value was decoded as well
+            self.deserializer.enter(PathSegment::Index(self.index));
+            self.index += 1;
             Ok(Some(DeserializeSeed::deserialize(
                 seed,
                 &mut *self.deserializer,
@@ -201,7 +416,10 @@ impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::MapAccess<'b> for MapAccess<'a,
 
     #[inline]
     fn next_value_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<V::Value> {
-        DeserializeSeed::deserialize(seed, &mut *self.deserializer)
+        let value = DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
+        let back = self.deserializer.path_len().saturating_sub(1);
+        self.deserializer.leave(back);
+        Ok(value)
     }
 
     #[inline]
@@ -234,11 +452,8 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
-        let val = match self.flavor.try_take_nib()? {
-            0 => false,
-            1 => true,
-            _ => return Err(Error::DeserializeBadBool),
-        };
+        self.flavor.value_start(NibbleFieldType::Bool)?;
+        let val = self.flavor.try_take_bool()?;
         visitor.visit_bool(val)
     }
 
@@ -247,7 +462,13 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i8(self.flavor.try_take_u8()? as i8)
+        self.flavor.value_start(NibbleFieldType::Signed)?;
+        let v = Vlu32N::de(&mut self.flavor)?.0;
+        if v <= 255 {
+            visitor.visit_i8(v as u8 as i8)
+        } else {
+            Err(Error::DeserializeBadVlu32N)
+        }
     }
 
     #[inline]
@@ -255,6 +476,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Signed)?;
         let v = self.try_take_varint_u16()?;
         visitor.visit_i16(de_zig_zag_i16(v))
     }
@@ -264,6 +486,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Signed)?;
         let v = self.try_take_varint_u32()?;
         visitor.visit_i32(de_zig_zag_i32(v))
     }
@@ -273,6 +496,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Signed)?;
         let v = self.try_take_varint_u64()?;
         visitor.visit_i64(de_zig_zag_i64(v))
     }
@@ -282,6 +506,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Signed)?;
         let v = self.try_take_varint_u128()?;
         visitor.visit_i128(de_zig_zag_i128(v))
     }
@@ -291,6 +516,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Unsigned)?;
         let v = Vlu32N::de(&mut self.flavor)?.0;
         if v <= 255 {
             visitor.visit_u8(v as u8)
@@ -304,6 +530,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Unsigned)?;
         let v = Vlu32N::de(&mut self.flavor)?.0;
         if v <= 65_535 {
             visitor.visit_u16(v as u16)
@@ -317,6 +544,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Unsigned)?;
         let v = self.try_take_varint_u32()?;
         visitor.visit_u32(v)
     }
@@ -326,6 +554,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Unsigned)?;
         let v = self.try_take_varint_u64()?;
         visitor.visit_u64(v)
     }
@@ -335,6 +564,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Unsigned)?;
         let v = self.try_take_varint_u128()?;
         visitor.visit_u128(v)
     }
@@ -344,6 +574,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Float)?;
         let bytes = self.flavor.try_take_n(4)?;
         let mut buf = [0u8; 4];
         buf.copy_from_slice(bytes);
@@ -355,6 +586,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Float)?;
         let bytes = self.flavor.try_take_n(8)?;
         let mut buf = [0u8; 8];
         buf.copy_from_slice(bytes);
@@ -366,6 +598,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Char)?;
         let sz = self.try_take_varint_usize()?;
         if sz > 4 {
             return Err(Error::DeserializeBadChar);
@@ -388,6 +621,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Str)?;
         let sz = self.try_take_varint_usize()?;
         let bytes: &'de [u8] = self.flavor.try_take_n(sz)?;
         let str_sl = core::str::from_utf8(bytes).map_err(|_| Error::DeserializeBadUtf8)?;
@@ -408,6 +642,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Bytes)?;
         let sz = self.try_take_varint_usize()?;
         let bytes: &'de [u8] = self.flavor.try_take_n(sz)?;
         visitor.visit_borrowed_bytes(bytes)
@@ -426,9 +661,10 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
-        match self.flavor.try_take_u8()? {
+        self.flavor.value_start(NibbleFieldType::Option)?;
+        match Vlu32N::de(&mut self.flavor)?.0 {
             0 => visitor.visit_none(),
-            1 => visitor.visit_some(self),
+            1 => self.nested(|de| visitor.visit_some(de)),
             _ => Err(Error::DeserializeBadOption),
         }
     }
@@ -440,6 +676,7 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Unit)?;
         visitor.visit_unit()
     }
 
@@ -454,11 +691,24 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     }
 
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        if name == DECODE_NAME {
+            let mut raw = RawAccess {
+                deserializer: self,
+                error: None,
+            };
+            let res = visitor.visit_seq(&mut raw);
+            // Errors pass through the visitor as `SerdeDeCustom`, report the original one
+            return match (res, raw.error) {
+                (Err(_), Some(e)) => Err(e),
+                (res, _) => res,
+            };
+        }
+        self.flavor.value_start(NibbleFieldType::Newtype)?;
+        self.nested(|de| visitor.visit_newtype_struct(de))
     }
 
     #[inline]
@@ -466,11 +716,16 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
+        self.flavor.value_start(NibbleFieldType::Seq)?;
         let len = self.try_take_varint_usize()?;
 
-        visitor.visit_seq(SeqAccess {
-            deserializer: self,
-            len,
+        self.nested(|de| {
+            visitor.visit_seq(SeqAccess {
+                deserializer: de,
+                len,
+                fields: None,
+                index: 0,
+            })
         })
     }
 
@@ -479,22 +734,33 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(SeqAccess {
-            deserializer: self,
-            len,
+        self.flavor.value_start(NibbleFieldType::Seq)?;
+        self.nested(|de| {
+            visitor.visit_seq(SeqAccess {
+                deserializer: de,
+                len,
+                fields: None,
+                index: 0,
+            })
         })
     }
 
     #[inline]
     fn deserialize_tuple_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == UNTAGGED_NAME {
+            return visitor.visit_seq(UntaggedAccess {
+                deserializer: self,
+                index: 0,
+            });
+        }
         self.deserialize_tuple(len, visitor)
     }
 
@@ -503,11 +769,38 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
-        let len = self.try_take_varint_usize()?;
-
-        visitor.visit_map(MapAccess {
-            deserializer: self,
-            len,
+        self.flavor.value_start(NibbleFieldType::Map)?;
+        // No length starts with the whole marker of unsized maps, but it may start with a part
+        let mut nib = self.flavor.try_take_nib()?;
+        let mut taken = 0;
+        while nib == UNSIZED_MAP[taken] {
+            taken += 1;
+            if taken == UNSIZED_MAP.len() {
+                // Structs with flattened fields, the entries describe themselves
+                return self.nested(|de| {
+                    visitor.visit_map(DescribedAccess {
+                        deserializer: de,
+                        len: None,
+                    })
+                });
+            }
+            nib = self.flavor.try_take_nib()?;
+        }
+        let len = if taken == 0 {
+            Vlu32N::de_from(nib, &mut self.flavor)?
+        } else {
+            // The digits of the marker taken so far are all 7
+            let num = (1 << (3 * taken)) - 1;
+            Vlu32N::de_continued(num, taken, nib, &mut self.flavor)?
+        };
+        let len = len.0 as usize;
+
+        self.nested(|de| {
+            visitor.visit_map(MapAccess {
+                deserializer: de,
+                len,
+                index: 0,
+            })
         })
     }
 
@@ -521,20 +814,39 @@ impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'a mut NibbleDese
     where
         V: Visitor<'de>,
     {
-        self.deserialize_tuple(fields.len(), visitor)
+        self.flavor.value_start(NibbleFieldType::Struct)?;
+        self.nested(|de| {
+            visitor.visit_seq(SeqAccess {
+                deserializer: de,
+                len: fields.len(),
+                fields: Some(fields),
+                index: 0,
+            })
+        })
     }
 
     #[inline]
     fn deserialize_enum<V>(
         self,
-        _name: &'static str,
-        _variants: &'static [&'static str],
+        name: &'static str,
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_enum(self)
+        self.flavor.value_start(NibbleFieldType::Enum)?;
+        self.nested(|de| {
+            // The variant is entered once known, and left after its contents were decoded
+            let back = de.path_len();
+            let value = visitor.visit_enum(EnumAccess {
+                deserializer: de,
+                name,
+                variants,
+            })?;
+            de.leave(back);
+            Ok(value)
+        })
     }
 
     // As a binary format, Postcard does not encode identifiers
@@ -583,21 +895,294 @@ impl<'de, 'a, F: NibbleFlavor<'de>> serde::de::VariantAccess<'de>
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        serde::de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        visitor.visit_seq(SeqAccess {
+            deserializer: self,
+            len: fields.len(),
+            fields: Some(fields),
+            index: 0,
+        })
     }
 }
 
-impl<'de, 'a, F: NibbleFlavor<'de>> serde::de::EnumAccess<'de>
-    for &'a mut NibbleDeserializer<'de, F>
-{
+struct EnumAccess<'a, 'de: 'a, F: NibbleFlavor<'de>> {
+    deserializer: &'a mut NibbleDeserializer<'de, F>,
+    /// The name of the enum
+    name: &'static str,
+    /// The names of the enum's variants
+    variants: &'static [&'static str],
+}
+
+impl<'de, F: NibbleFlavor<'de>> NibbleDeserializer<'de, F> {
+    /// Decode an enum discriminant, rejecting it if the enum has no such variant
+    #[inline]
+    fn take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        let index = self.flavor.try_take_variant(name, variants)?;
+        if index as usize >= variants.len() {
+            return Err(Error::DeserializeBadEnum {
+                index,
+                count: variants.len(),
+            });
+        }
+        Ok(index)
+    }
+}
+
+impl<'de, 'a, F: NibbleFlavor<'de>> serde::de::EnumAccess<'de> for EnumAccess<'a, 'de, F> {
     type Error = Error;
-    type Variant = Self;
+    type Variant = &'a mut NibbleDeserializer<'de, F>;
 
     #[inline]
-    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
-        let v = Vlu32N::de(&mut self.flavor)?.0;
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let v = self.deserializer.take_variant(self.name, self.variants)?;
+        self.deserializer
+            .enter(PathSegment::Variant(self.variants[v as usize]));
         let v = DeserializeSeed::deserialize(seed, v.into_deserializer())?;
-        Ok((v, self))
+        Ok((v, self.deserializer))
+    }
+}
+
+////////////////////////////////////////
+// Self-described values
+////////////////////////////////////////
+
+/// Deserializes values written with a type tag nibble in front, see the `Described`
+/// serializer. Typed requests are answered with whatever the tag says, like any self-describing
+/// format does, and the visitor checks whether that fits.
+struct Described<'a, 'de: 'a, F: NibbleFlavor<'de>> {
+    deserializer: &'a mut NibbleDeserializer<'de, F>,
+    /// The tag, if it was already taken to check for the end of a terminated compound
+    tag: Option<u8>,
+}
+
+impl<'a, 'de: 'a, F: NibbleFlavor<'de>> Described<'a, 'de, F> {
+    fn new(deserializer: &'a mut NibbleDeserializer<'de, F>) -> Self {
+        Described {
+            deserializer,
+            tag: None,
+        }
+    }
+
+    fn take_tag(&mut self) -> Result<u8> {
+        match self.tag.take() {
+            Some(tag) => Ok(tag),
+            None => self.deserializer.flavor.try_take_nib(),
+        }
+    }
+}
+
+impl<'de, 'a, F: NibbleFlavor<'de>> de::Deserializer<'de> for Described<'a, 'de, F> {
+    type Error = Error;
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let tag = self.take_tag()?;
+        let inner = self.deserializer;
+        match tag {
+            tag::UNIT => visitor.visit_unit(),
+            tag::BOOL => de::Deserializer::deserialize_bool(inner, visitor),
+            tag::UNSIGNED => {
+                inner.flavor.value_start(NibbleFieldType::Unsigned)?;
+                let v = inner.try_take_varint_u128()?;
+                match u64::try_from(v) {
+                    Ok(v) => visitor.visit_u64(v),
+                    Err(_) => visitor.visit_u128(v),
+                }
+            }
+            tag::SIGNED => {
+                inner.flavor.value_start(NibbleFieldType::Signed)?;
+                let v = de_zig_zag_i128(inner.try_take_varint_u128()?);
+                match i64::try_from(v) {
+                    Ok(v) => visitor.visit_i64(v),
+                    Err(_) => visitor.visit_i128(v),
+                }
+            }
+            tag::F32 => de::Deserializer::deserialize_f32(inner, visitor),
+            tag::F64 => de::Deserializer::deserialize_f64(inner, visitor),
+            tag::STR => de::Deserializer::deserialize_str(inner, visitor),
+            tag::BYTES => de::Deserializer::deserialize_bytes(inner, visitor),
+            tag::NONE => visitor.visit_none(),
+            tag::SOME => inner.nested(|de| visitor.visit_some(Described::new(de))),
+            tag::SEQ => {
+                let len = inner.try_take_varint_usize()?;
+                inner.nested(|de| {
+                    visitor.visit_seq(DescribedAccess {
+                        deserializer: de,
+                        len: Some(len),
+                    })
+                })
+            }
+            tag::MAP => {
+                let len = inner.try_take_varint_usize()?;
+                inner.nested(|de| {
+                    visitor.visit_map(DescribedAccess {
+                        deserializer: de,
+                        len: Some(len),
+                    })
+                })
+            }
+            tag::UNSIZED_SEQ => inner.nested(|de| {
+                visitor.visit_seq(DescribedAccess {
+                    deserializer: de,
+                    len: None,
+                })
+            }),
+            tag::UNSIZED_MAP => inner.nested(|de| {
+                visitor.visit_map(DescribedAccess {
+                    deserializer: de,
+                    len: None,
+                })
+            }),
+            _ => Err(Error::DeserializeBadEncoding),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let tag = self.tag;
+        self.deserializer.nested(|de| {
+            visitor.visit_newtype_struct(Described {
+                deserializer: de,
+                tag,
+            })
+        })
+    }
+
+    fn deserialize_enum<V>(
+        mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.take_tag()? {
+            // Unit variants are just their name
+            tag::STR => {
+                let inner = &mut *self.deserializer;
+                let sz = inner.try_take_varint_usize()?;
+                let bytes: &'de [u8] = inner.flavor.try_take_n(sz)?;
+                let name = core::str::from_utf8(bytes).map_err(|_| Error::DeserializeBadUtf8)?;
+                visitor.visit_enum(name.into_deserializer())
+            }
+            // Others are a single `variant => contents` entry
+            tag::MAP => {
+                if self.deserializer.try_take_varint_usize()? != 1 {
+                    return Err(Error::DeserializeBadEncoding);
+                }
+                visitor.visit_enum(Described::new(self.deserializer))
+            }
+            _ => Err(Error::DeserializeBadEncoding),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de, 'a, F: NibbleFlavor<'de>> serde::de::EnumAccess<'de> for Described<'a, 'de, F> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(Described::new(&mut *self.deserializer))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, F: NibbleFlavor<'de>> serde::de::VariantAccess<'de> for Described<'a, 'de, F> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<V::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+/// The elements of a self-described sequence, or the entries of a self-described map
+struct DescribedAccess<'a, 'b: 'a, F: NibbleFlavor<'b>> {
+    deserializer: &'a mut NibbleDeserializer<'b, F>,
+    /// The remaining number of elements, or `None` if they are terminated
+    len: Option<usize>,
+}
+
+impl<'a, 'b: 'a, F: NibbleFlavor<'b>> DescribedAccess<'a, 'b, F> {
+    fn next<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
+        let tag = match &mut self.len {
+            Some(0) => return Ok(None),
+            Some(len) => {
+                *len -= 1;
+                None
+            }
+            None => match self.deserializer.flavor.try_take_nib()? {
+                tag::END => return Ok(None),
+                tag => Some(tag),
+            },
+        };
+        let value = seed.deserialize(Described {
+            deserializer: &mut *self.deserializer,
+            tag,
+        })?;
+        Ok(Some(value))
+    }
+}
+
+impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::SeqAccess<'b> for DescribedAccess<'a, 'b, F> {
+    type Error = Error;
+
+    fn next_element_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
+        self.next(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len
+    }
+}
+
+impl<'a, 'b: 'a, F: NibbleFlavor<'b>> serde::de::MapAccess<'b> for DescribedAccess<'a, 'b, F> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'b>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        self.next(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(Described::new(&mut *self.deserializer))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len
     }
 }
 
@@ -616,3 +1201,263 @@ fn de_zig_zag_i64(n: u64) -> i64 {
 fn de_zig_zag_i128(n: u128) -> i128 {
     ((n >> 1) as i128) ^ (-((n & 0b1) as i128))
 }
+
+/// Wraps a [`NibbleDeserializer`] for a single top-level value, recording the
+/// variant index if that value turns out to be an enum.
+///
+/// Only the outermost call is intercepted: nested values are handed the inner
+/// deserializer directly, so enums inside structs or sequences are not recorded.
+pub(crate) struct VariantProbe<'a, 'de, F: NibbleFlavor<'de>> {
+    pub(crate) deserializer: &'a mut NibbleDeserializer<'de, F>,
+    pub(crate) variant: Option<u32>,
+}
+
+macro_rules! forward_to_inner {
+    ($($method:ident)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                de::Deserializer::$method(&mut *self.deserializer, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a, 'b, F: NibbleFlavor<'de>> de::Deserializer<'de> for &'b mut VariantProbe<'a, 'de, F> {
+    type Error = Error;
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_inner! {
+        deserialize_any deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier deserialize_ignored_any
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_unit_struct(&mut *self.deserializer, name, visitor)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_newtype_struct(&mut *self.deserializer, name, visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple(&mut *self.deserializer, len, visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple_struct(&mut *self.deserializer, name, len, visitor)
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(&mut *self.deserializer, name, fields, visitor)
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(ProbeEnumAccess {
+            probe: self,
+            name,
+            variants,
+        })
+    }
+}
+
+struct ProbeEnumAccess<'b, 'a, 'de: 'a, F: NibbleFlavor<'de>> {
+    probe: &'b mut VariantProbe<'a, 'de, F>,
+    name: &'static str,
+    variants: &'static [&'static str],
+}
+
+impl<'de, 'a, 'b, F: NibbleFlavor<'de>> serde::de::EnumAccess<'de>
+    for ProbeEnumAccess<'b, 'a, 'de, F>
+{
+    type Error = Error;
+    type Variant = &'b mut NibbleDeserializer<'de, F>;
+
+    #[inline]
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let v = self
+            .probe
+            .deserializer
+            .take_variant(self.name, self.variants)?;
+        self.probe.variant = Some(v);
+        let v = DeserializeSeed::deserialize(seed, v.into_deserializer())?;
+        Ok((v, &mut *self.probe.deserializer))
+    }
+}
+
+/// Wraps a [`NibbleDeserializer`] over a [`NibbleSlice`] for a single top-level struct or
+/// tuple, stopping before the field at `target` and recording the nibble offset it starts at.
+pub(crate) struct FieldOffsetProbe<'a, 'de> {
+    pub(crate) deserializer: &'a mut NibbleDeserializer<'de, NibbleSlice<'de>>,
+    pub(crate) total_nibbles: usize,
+    pub(crate) target: usize,
+    pub(crate) offset: Option<usize>,
+}
+
+struct FieldOffsetAccess<'p, 'a, 'de> {
+    probe: &'p mut FieldOffsetProbe<'a, 'de>,
+    index: usize,
+    len: usize,
+}
+
+impl<'p, 'a, 'de> serde::de::SeqAccess<'de> for FieldOffsetAccess<'p, 'a, 'de> {
+    type Error = Error;
+
+    #[inline]
+    fn next_element_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<Option<V::Value>> {
+        if self.index == self.len {
+            return Ok(None);
+        }
+        if self.index == self.probe.target {
+            let left = self.probe.deserializer.flavor.nibbles_left();
+            self.probe.offset = Some(self.probe.total_nibbles - left);
+            // Stop here, the visitor will bail out on the missing fields
+            return Ok(None);
+        }
+        self.index += 1;
+        Ok(Some(DeserializeSeed::deserialize(
+            seed,
+            &mut *self.probe.deserializer,
+        )?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+impl<'de, 'a, 'b> de::Deserializer<'de> for &'b mut FieldOffsetProbe<'a, 'de> {
+    type Error = Error;
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_inner! {
+        deserialize_any deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier deserialize_ignored_any
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_unit_struct(&mut *self.deserializer, name, visitor)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_newtype_struct(&mut *self.deserializer, name, visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(FieldOffsetAccess {
+            probe: self,
+            index: 0,
+            len,
+        })
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_enum(&mut *self.deserializer, name, variants, visitor)
+    }
+}
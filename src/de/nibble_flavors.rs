@@ -1,8 +1,23 @@
 //! # Nibble Deserialization Flavors
 //!
 
+use crate::de::nibble_deserializer::NibbleDeserializer;
+use crate::ser::nibble_flavors::{
+    parity_decode, Fletcher16, NibbleAeadCipher, NibbleFieldType, NibbleTagMap, LINE_SAFE_BASE,
+};
+use crate::vlu32n::Vlu32N;
 use crate::{Error, Result};
 use core::marker::PhantomData;
+use serde::Deserialize;
+
+#[cfg(feature = "alloc")]
+pub use lenient::*;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "use-std")]
+pub use io_reader::*;
 
 /// The deserialization Flavor trait
 ///
@@ -10,7 +25,7 @@ use core::marker::PhantomData;
 /// or modify that data in a middleware style pattern.
 ///
 /// See the module level docs for an example of how flavors are used.
-pub trait NibbleFlavor<'de>: 'de {
+pub trait NibbleFlavor<'de> {
     /// The remaining data of this flavor after deserializing has completed.
     ///
     /// Typically, this includes the remaining buffer that was not used for
@@ -23,17 +38,102 @@ pub trait NibbleFlavor<'de>: 'de {
     ///
     /// This is typically some sort of data buffer, or another Flavor, when
     /// chained behavior is desired
-    type Source: 'de;
+    type Source;
 
     /// Obtain the next nibble for deserialization
     fn try_take_nib(&mut self) -> Result<u8>;
 
+    /// Look at the next nibble without consuming it.
+    ///
+    /// By default this takes the nibble and rewinds with [`mark()`](Self::mark) and
+    /// [`reset_to()`](Self::reset_to), flavors that can't rewind return
+    /// [`Error::NotYetImplemented`].
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        let mark = self.mark().ok_or(Error::NotYetImplemented)?;
+        let nib = self.try_take_nib()?;
+        self.reset_to(mark)?;
+        Ok(nib)
+    }
+
     /// Obtain the next byte for deserialization
     fn try_take_u8(&mut self) -> Result<u8>;
 
+    /// Obtain the next boolean. By default booleans take up a whole nibble, modifier flavors
+    /// such as [`NibblePackedBools`] can store them more densely.
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        match self.try_take_nib()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::DeserializeBadBool),
+        }
+    }
+
+    /// Obtain the next enum discriminant, `name` being the name of the enum and `variants` the
+    /// names of its variants. By default it is a nibble varint, modifier flavors such as
+    /// [`NibbleTwoLevelTags`] can decode it differently.
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+    ) -> Result<u32>
+    where
+        Self: Sized,
+    {
+        Vlu32N::de(self).map(|v| v.0)
+    }
+
     /// Attempt to take the next `ct` bytes from the serialized message
+    ///
+    /// The bytes start at a byte boundary, skipping the padding nibble of a partially read byte.
+    /// Taking zero bytes skips just the padding, mirroring an empty `try_extend` when serializing.
     fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]>;
 
+    /// Remember the current position, so that decoding can be retried from here with
+    /// [`reset_to()`](Self::reset_to). Returns `None` if the flavor can't rewind.
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        None
+    }
+
+    /// Rewind to a position previously obtained from [`mark()`](Self::mark)
+    #[inline]
+    fn reset_to(&mut self, _mark: NibbleMark) -> Result<()> {
+        Err(Error::NotYetImplemented)
+    }
+
+    /// Called by the deserializer when a varint used more digits than needed for its value.
+    /// The decoded value is still valid, so by default this is silently accepted.
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by the deserializer before each struct field is deserialized, the counterpart of
+    /// [`field_end()`](Self::field_end).
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by the deserializer at the start of every `serde::Deserializer` method, with the
+    /// kind of value it is about to decode. Storage flavors can ignore it, it exists so that
+    /// modifier flavors such as [`NibbleFieldTypes`] can check the types of fields.
+    #[inline]
+    fn value_start(&mut self, _ty: NibbleFieldType) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by the deserializer after each struct field has been deserialized. Storage
+    /// flavors can ignore it, it exists so that modifier flavors such as [`NibbleSeparated`]
+    /// can check field boundaries.
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        Ok(())
+    }
+
     /// Complete the deserialization process.
     ///
     /// This is typically called separately, after the `serde` deserialization
@@ -41,10 +141,173 @@ pub trait NibbleFlavor<'de>: 'de {
     fn finalize(self) -> Result<Self::Remainder>;
 }
 
+/// A borrowed flavor is a flavor too, so that a value can be deserialized from a flavor that is
+/// still used afterwards, e.g. after reading a header by hand. Finalizing the borrow does
+/// nothing, the owner finalizes the flavor itself to get its remainder.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::{NibbleFlavor, NibbleSlice};
+/// use postcard::from_nibbles_with_flavor;
+///
+/// let mut flavor = NibbleSlice::new(&[0xA1, 0x50, 0xFF]);
+/// assert_eq!(flavor.try_take_nib().unwrap(), 0xA);
+/// let (value, ()) = from_nibbles_with_flavor::<(bool, u8, bool), _>(&mut flavor).unwrap();
+/// assert_eq!(value, (true, 5, false));
+/// assert_eq!(flavor.finalize().unwrap(), &[0xFF]);
+/// ```
+impl<'de, F> NibbleFlavor<'de> for &mut F
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = ();
+    type Source = F;
+
+    #[inline(always)]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        (**self).try_take_nib()
+    }
+
+    #[inline(always)]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        (**self).try_peek_nib()
+    }
+
+    #[inline(always)]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        (**self).try_take_u8()
+    }
+
+    #[inline(always)]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        (**self).try_take_bool()
+    }
+
+    #[inline(always)]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        (**self).try_take_variant(name, variants)
+    }
+
+    #[inline(always)]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        (**self).try_take_n(ct)
+    }
+
+    #[inline(always)]
+    fn mark(&self) -> Option<NibbleMark> {
+        (**self).mark()
+    }
+
+    #[inline(always)]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        (**self).reset_to(mark)
+    }
+
+    #[inline(always)]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        (**self).non_canonical_varint()
+    }
+
+    #[inline(always)]
+    fn field_start(&mut self) -> Result<()> {
+        (**self).field_start()
+    }
+
+    #[inline(always)]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        (**self).value_start(ty)
+    }
+
+    #[inline(always)]
+    fn field_end(&mut self) -> Result<()> {
+        (**self).field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        Ok(())
+    }
+}
+
+/// A position in the input, see [`NibbleFlavor::mark`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NibbleMark {
+    /// Unread nibbles at the marked position
+    nibbles_left: usize,
+}
+
+/// The number of positions [`Marks`] remembers
+const MARKS: usize = 8;
+
+/// The state of a modifier flavor at the positions handed out by its `mark()`, for flavors
+/// whose state doesn't follow from the position alone, such as a running checksum.
+///
+/// Marking a position forgets the ones at or after it, which were rewound past already. Of
+/// the positions before it, only the latest [`MARKS`] are kept, rewinding to an older one
+/// results in [`Error::NotYetImplemented`].
+pub(crate) struct Marks<S> {
+    /// Ordered by position, the unused slots at the end
+    saved: core::cell::RefCell<[Option<(NibbleMark, S)>; MARKS]>,
+}
+
+impl<S> Marks<S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            saved: core::cell::RefCell::new(Default::default()),
+        }
+    }
+}
+
+impl<S: Clone> Marks<S> {
+    /// Remember `state` as the state at `mark`
+    pub(crate) fn save(&self, mark: NibbleMark, state: S) {
+        let mut saved = self.saved.borrow_mut();
+        let mut len = saved
+            .iter()
+            .take_while(|s| matches!(s, Some((m, _)) if m.nibbles_left > mark.nibbles_left))
+            .count();
+        saved[len..].iter_mut().for_each(|s| *s = None);
+        if len == MARKS {
+            saved.rotate_left(1);
+            len -= 1;
+        }
+        saved[len] = Some((mark, state));
+    }
+
+    /// The state at `mark`, forgetting the positions after it
+    pub(crate) fn restore(&self, mark: NibbleMark) -> Result<S> {
+        let mut saved = self.saved.borrow_mut();
+        let i = saved
+            .iter()
+            .position(|s| matches!(s, Some((m, _)) if *m == mark))
+            .ok_or(Error::NotYetImplemented)?;
+        saved[i + 1..].iter_mut().for_each(|s| *s = None);
+        saved[i]
+            .as_ref()
+            .map(|(_, s)| s.clone())
+            .ok_or(Error::NotYetImplemented)
+    }
+}
+
+/// Whether the inner flavor took `len` nibbles between the positions `before` and `after`,
+/// i.e. decoded a value in its plain encoding. Used by the checksum flavors, which can only
+/// locate padding nibbles as long as the inner flavor reads the plain format.
+fn took(before: Option<NibbleMark>, after: Option<NibbleMark>, len: usize) -> bool {
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            before.nibbles_left.checked_sub(after.nibbles_left) == Some(len)
+        }
+        _ => false,
+    }
+}
+
 /// A simple [`Flavor`] representing the deserialization from a borrowed slice
 pub struct NibbleSlice<'de> {
     // This string starts with the input data and characters are truncated off
     // the beginning as data is parsed.
+    pub(crate) start: *const u8,
     pub(crate) cursor: *const u8,
     pub(crate) is_at_byte_boundary: bool,
     pub(crate) end: *const u8,
@@ -55,6 +318,7 @@ impl<'de> NibbleSlice<'de> {
     /// Create a new [Slice] from the given buffer
     pub fn new(sli: &'de [u8]) -> Self {
         Self {
+            start: sli.as_ptr(),
             cursor: sli.as_ptr(),
             is_at_byte_boundary: true,
             end: unsafe { sli.as_ptr().add(sli.len()) },
@@ -69,7 +333,9 @@ impl<'de> NibbleSlice<'de> {
         Ok(())
     }
 
-    fn nibbles_left(&self) -> usize {
+    /// The number of nibbles not yet consumed, including the padding nibble of a partially
+    /// read last byte
+    pub fn nibbles_left(&self) -> usize {
         let bytes_remain = (self.end as usize) - (self.cursor as usize);
         if self.is_at_byte_boundary {
             bytes_remain * 2
@@ -77,6 +343,23 @@ impl<'de> NibbleSlice<'de> {
             bytes_remain * 2 - 1
         }
     }
+
+    /// Whether the next nibble is the high nibble of a byte, i.e. no byte has been partially
+    /// consumed
+    pub fn is_at_byte_boundary(&self) -> bool {
+        self.is_at_byte_boundary
+    }
+
+    /// The number of nibbles consumed so far, i.e. the position of the next nibble counted
+    /// from the start of the input
+    pub fn nibble_offset(&self) -> usize {
+        let bytes_read = (self.cursor as usize) - (self.start as usize);
+        if self.is_at_byte_boundary {
+            bytes_read * 2
+        } else {
+            bytes_read * 2 + 1
+        }
+    }
 }
 
 impl<'de> NibbleFlavor<'de> for NibbleSlice<'de> {
@@ -87,6 +370,9 @@ impl<'de> NibbleFlavor<'de> for NibbleSlice<'de> {
     fn try_take_nib(&mut self) -> Result<u8> {
         unsafe {
             if self.is_at_byte_boundary {
+                if self.cursor == self.end {
+                    return Err(Error::DeserializeUnexpectedEnd);
+                }
                 self.is_at_byte_boundary = false;
                 Ok(((*self.cursor) & 0xf0) >> 4)
             } else {
@@ -98,6 +384,19 @@ impl<'de> NibbleFlavor<'de> for NibbleSlice<'de> {
         }
     }
 
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        if self.cursor == self.end {
+            return Err(Error::DeserializeUnexpectedEnd);
+        }
+        let byte = unsafe { *self.cursor };
+        if self.is_at_byte_boundary {
+            Ok(byte >> 4)
+        } else {
+            Ok(byte & 0x0f)
+        }
+    }
+
     #[inline]
     fn try_take_u8(&mut self) -> Result<u8> {
         if self.cursor == self.end {
@@ -109,8 +408,13 @@ impl<'de> NibbleFlavor<'de> for NibbleSlice<'de> {
                     self.cursor = self.cursor.add(1);
                     res
                 } else {
+                    // The low nibble comes from the next byte, which may not exist
+                    let next = self.cursor.add(1);
+                    if next == self.end {
+                        return Err(Error::DeserializeUnexpectedEnd);
+                    }
                     let msn = *self.cursor;
-                    self.cursor = self.cursor.add(1);
+                    self.cursor = next;
                     let lsn = *self.cursor;
                     Ok((msn << 4) | (lsn >> 4))
                 }
@@ -132,9 +436,2706 @@ impl<'de> NibbleFlavor<'de> for NibbleSlice<'de> {
         }
     }
 
+    fn mark(&self) -> Option<NibbleMark> {
+        Some(NibbleMark {
+            nibbles_left: self.nibbles_left(),
+        })
+    }
+
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        let total = ((self.end as usize) - (self.start as usize)) * 2;
+        if mark.nibbles_left > total {
+            return Err(Error::DeserializeUnexpectedEnd);
+        }
+        let consumed = total - mark.nibbles_left;
+        self.cursor = unsafe { self.start.add(consumed / 2) };
+        self.is_at_byte_boundary = consumed & 1 == 0;
+        Ok(())
+    }
+
     /// Return the remaining (unused) bytes in the Deserializer
     fn finalize(self) -> Result<&'de [u8]> {
         let remain = (self.end as usize) - (self.cursor as usize);
         unsafe { Ok(core::slice::from_raw_parts(self.cursor, remain)) }
     }
 }
+
+/// Reads the little-endian bit stream written by the serialization `BitFlavor` from a
+/// borrowed slice.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::BitFlavor;
+/// use postcard::from_nibbles_with_flavor;
+///
+/// let mut bits = BitFlavor::new(&[0b1111_1101, 0b0000_0111]);
+/// assert_eq!(bits.take_bits(3), Ok(0b101));
+/// assert_eq!(bits.take_bits(7), Ok(0x7F));
+/// let (flag, rest) = from_nibbles_with_flavor::<bool, _>(bits).unwrap();
+/// assert!(flag);
+/// assert!(rest.is_empty());
+/// ```
+pub struct BitFlavor<'de> {
+    buf: &'de [u8],
+    bits: usize,
+}
+
+impl<'de> BitFlavor<'de> {
+    /// Create a new `BitFlavor` from the given buffer
+    pub fn new(buf: &'de [u8]) -> Self {
+        Self { buf, bits: 0 }
+    }
+
+    /// Read an `nbits` wide field, least significant bit first
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbits` is larger than 64.
+    pub fn take_bits(&mut self, nbits: u32) -> Result<u64> {
+        let value = self.peek_bits(nbits)?;
+        self.bits += nbits as usize;
+        Ok(value)
+    }
+
+    fn peek_bits(&self, nbits: u32) -> Result<u64> {
+        assert!(nbits <= 64, "cannot take more than 64 bits at once");
+        if self.bits_left() < nbits as usize {
+            return Err(Error::DeserializeUnexpectedEnd);
+        }
+        let mut value = 0u64;
+        let mut pos = self.bits;
+        let mut done = 0;
+        while done < nbits as usize {
+            let offset = pos & 7;
+            let take = (nbits as usize - done).min(8 - offset);
+            let part = (self.buf[pos / 8] >> offset) & (0xFF >> (8 - take));
+            value |= (part as u64) << done;
+            pos += take;
+            done += take;
+        }
+        Ok(value)
+    }
+
+    /// The number of bits not yet read
+    pub fn bits_left(&self) -> usize {
+        self.buf.len() * 8 - self.bits
+    }
+
+    fn align(&mut self) -> Result<()> {
+        let pad = self.bits.wrapping_neg() & 7;
+        self.take_bits(pad as u32).map(drop)
+    }
+}
+
+impl<'de> NibbleFlavor<'de> for BitFlavor<'de> {
+    type Remainder = &'de [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.take_bits(4).map(|n| n as u8)
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.peek_bits(4).map(|n| n as u8)
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        if self.bits_left() < 8 {
+            return Err(Error::DeserializeUnexpectedEnd);
+        }
+        let msn = self.try_take_nib()?;
+        let lsn = self.try_take_nib()?;
+        Ok((msn << 4) | lsn)
+    }
+
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.align()?;
+        let start = self.bits / 8;
+        if self.buf.len() - start < ct {
+            return Err(Error::DeserializeUnexpectedEnd);
+        }
+        self.bits += ct * 8;
+        Ok(&self.buf[start..start + ct])
+    }
+
+    /// Return the bytes after the one holding the last bit read
+    fn finalize(self) -> Result<&'de [u8]> {
+        Ok(&self.buf[self.bits.div_ceil(8)..])
+    }
+}
+
+/// The nibble expected by [`NibbleSeparated`] after every struct field
+pub const FIELD_SEPARATOR: u8 = 0xF;
+
+/// Decodes the debugging format produced by the serialization `NibbleSeparated` flavor,
+/// consuming the separator nibble after every struct field.
+///
+/// A missing or misplaced separator results in [`Error::DeserializeBadEncoding`].
+pub struct NibbleSeparated<F> {
+    flav: F,
+}
+
+impl<F> NibbleSeparated<F> {
+    /// Create a new `NibbleSeparated` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self { flav }
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleSeparated<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.flav.try_take_bool()
+    }
+
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        self.flav.try_take_variant(name, variants)
+    }
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        if self.flav.try_take_nib()? != FIELD_SEPARATOR {
+            return Err(Error::DeserializeBadEncoding);
+        }
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the output of the serialization `NibblePackedBools` flavor, where consecutive
+/// booleans are stored as single bits, up to four of them in one nibble.
+///
+/// Set bits in the unused part of a boolean nibble result in [`Error::DeserializeBadBool`].
+pub struct NibblePackedBools<F> {
+    flav: F,
+    /// The not yet consumed booleans of the current nibble, the next one in the lowest bit
+    bits: u8,
+    left: u8,
+}
+
+impl<F> NibblePackedBools<F> {
+    /// Create a new `NibblePackedBools` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            bits: 0,
+            left: 0,
+        }
+    }
+
+    /// Drop the rest of the current boolean nibble, which must be padding
+    fn finish_bools(&mut self) -> Result<()> {
+        let bits = self.bits;
+        self.bits = 0;
+        self.left = 0;
+        if bits != 0 {
+            return Err(Error::DeserializeBadBool);
+        }
+        Ok(())
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibblePackedBools<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.finish_bools()?;
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.finish_bools()?;
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        if self.left == 0 {
+            self.bits = self.flav.try_take_nib()?;
+            self.left = 4;
+        }
+        let b = self.bits & 1 == 1;
+        self.bits >>= 1;
+        self.left -= 1;
+        Ok(b)
+    }
+
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        self.finish_bools()?;
+        self.flav.try_take_variant(name, variants)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.finish_bools()?;
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        // A position within a boolean nibble can't be expressed by the inner flavor
+        if self.left != 0 {
+            return None;
+        }
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.bits = 0;
+        self.left = 0;
+        self.flav.reset_to(mark)
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(mut self) -> Result<Self::Remainder> {
+        self.finish_bools()?;
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the two-level enum tags written by the serialization `NibbleTwoLevelTags` flavor,
+/// for the enums selected by the same [`NibbleTagMap`].
+///
+/// A tag that belongs to no variant results in [`Error::DeserializeBadEncoding`].
+pub struct NibbleTwoLevelTags<F, M> {
+    flav: F,
+    map: M,
+}
+
+impl<F, M> NibbleTwoLevelTags<F, M> {
+    /// Create a new `NibbleTwoLevelTags` modifier Flavor, using `map` for the tags
+    pub fn new(flav: F, map: M) -> Self {
+        Self { flav, map }
+    }
+}
+
+impl<'de, F, M> NibbleFlavor<'de> for NibbleTwoLevelTags<F, M>
+where
+    F: NibbleFlavor<'de>,
+    M: NibbleTagMap + 'de,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.flav.try_take_bool()
+    }
+
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        if !self.map.is_mapped(name) {
+            return self.flav.try_take_variant(name, variants);
+        }
+        let category = self.flav.try_take_nib()?;
+        let subtype = self.flav.try_take_nib()?;
+        self.map
+            .index(name, category, subtype)
+            .ok_or(Error::DeserializeBadEncoding)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the enum variant names written by the serialization `NibbleNamedVariants` flavor,
+/// matching them against the variant names of the enum being deserialized.
+///
+/// A name that belongs to no variant results in [`Error::DeserializeBadEncoding`].
+pub struct NibbleNamedVariants<F> {
+    flav: F,
+}
+
+impl<F> NibbleNamedVariants<F> {
+    /// Create a new `NibbleNamedVariants` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self { flav }
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleNamedVariants<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.flav.try_take_bool()
+    }
+
+    fn try_take_variant(
+        &mut self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        let len = Vlu32N::de(&mut self.flav)?.0 as usize;
+        let variant = self.flav.try_take_n(len)?;
+        variants
+            .iter()
+            .position(|v| v.as_bytes() == variant)
+            .map(|index| index as u32)
+            .ok_or(Error::DeserializeBadEncoding)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Maps the type ids written by the serialization `NibbleTyped` flavor to the types they stand
+/// for, see [`NibbleTyped`].
+pub trait NibbleTypeRegistry<'de> {
+    /// What the messages of all types are decoded into, e.g. an enum with a variant per type
+    type Output;
+
+    /// Decode the message with the type `id`, usually with `value.decode::<T>()` and the `T`
+    /// registered for it. Unknown ids should result in [`Error::DeserializeBadEncoding`].
+    fn decode<F>(&mut self, id: u32, value: NibbleTypedValue<'_, 'de, F>) -> Result<Self::Output>
+    where
+        F: NibbleFlavor<'de>;
+}
+
+/// The message following a type id, to be decoded by a [`NibbleTypeRegistry`]
+pub struct NibbleTypedValue<'a, 'de, F>
+where
+    F: NibbleFlavor<'de>,
+{
+    deserializer: &'a mut NibbleDeserializer<'de, F>,
+}
+
+impl<'a, 'de, F> NibbleTypedValue<'a, 'de, F>
+where
+    F: NibbleFlavor<'de>,
+{
+    /// Decode the message as a `T`
+    pub fn decode<T>(self) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(self.deserializer)
+    }
+}
+
+/// Decodes messages written by the serialization `NibbleTyped` flavor: the wire version and
+/// type id are read first, and a [`NibbleTypeRegistry`] decodes the rest of the message as the
+/// type registered for it.
+///
+/// Messages written with a version other than [`NIBBLE_WIRE_VERSION`](crate::NIBBLE_WIRE_VERSION)
+/// are rejected with [`Error::DeserializeWireVersionMismatch`], before their type id is looked at.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::{
+///     NibbleFlavor, NibbleSlice, NibbleTypeRegistry, NibbleTyped, NibbleTypedValue,
+/// };
+/// use postcard::{Error, Result};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Message {
+///     Flag(bool),
+///     Count(u16),
+/// }
+///
+/// struct Registry;
+///
+/// impl<'de> NibbleTypeRegistry<'de> for Registry {
+///     type Output = Message;
+///
+///     fn decode<F>(&mut self, id: u32, value: NibbleTypedValue<'_, 'de, F>) -> Result<Message>
+///     where
+///         F: NibbleFlavor<'de>,
+///     {
+///         match id {
+///             1 => value.decode().map(Message::Flag),
+///             2 => value.decode().map(Message::Count),
+///             _ => Err(Error::DeserializeBadEncoding),
+///         }
+///     }
+/// }
+///
+/// let (msg, _) = NibbleTyped::new(NibbleSlice::new(&[0x31, 0x10])).decode(&mut Registry).unwrap();
+/// assert_eq!(msg, Message::Flag(true));
+/// let (msg, _) = NibbleTyped::new(NibbleSlice::new(&[0x32, 0x70])).decode(&mut Registry).unwrap();
+/// assert_eq!(msg, Message::Count(7));
+///
+/// // The same message, from a peer speaking wire version 2
+/// let msg = NibbleTyped::new(NibbleSlice::new(&[0x22, 0x70])).decode(&mut Registry);
+/// assert_eq!(msg.err(), Some(Error::DeserializeWireVersionMismatch));
+/// ```
+pub struct NibbleTyped<F> {
+    flav: F,
+}
+
+impl<'de, F> NibbleTyped<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    /// Create a new `NibbleTyped` decoder, reading from `flav`
+    pub fn new(flav: F) -> Self {
+        Self { flav }
+    }
+
+    /// Read the wire version and type id, and decode the message with `registry`. Returns the
+    /// decoded message and the remainder of the flavor.
+    pub fn decode<R>(mut self, registry: &mut R) -> Result<(R::Output, F::Remainder)>
+    where
+        R: NibbleTypeRegistry<'de>,
+    {
+        if Vlu32N::de(&mut self.flav)?.0 != crate::NIBBLE_WIRE_VERSION as u32 {
+            return Err(Error::DeserializeWireVersionMismatch);
+        }
+        let id = Vlu32N::de(&mut self.flav)?.0;
+        let mut deserializer = NibbleDeserializer::from_flavor(self.flav);
+        let out = registry.decode(
+            id,
+            NibbleTypedValue {
+                deserializer: &mut deserializer,
+            },
+        )?;
+        Ok((out, deserializer.finalize()?))
+    }
+}
+
+/// Decodes the output of the serialization `NibbleFieldTypes` flavor, checking the type tag in
+/// front of every struct field against the type the field is deserialized as.
+///
+/// A tag of another type results in [`Error::DeserializeFieldTypeMismatch`]. Fields decoded
+/// with a custom [`NibbleDecode`](crate::nibble_decode::NibbleDecode) impl are not supported,
+/// and `NibbleUntagged` fields only get to try their first alternative.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::{NibbleFieldTypes, NibbleSlice};
+/// use postcard::{from_nibbles_with_flavor, Error};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Reading {
+///     valid: bool,
+///     value: u8,
+/// }
+///
+/// let flavor = NibbleFieldTypes::new(NibbleSlice::new(&[0x01, 0x15]));
+/// let (reading, _) = from_nibbles_with_flavor::<Reading, _>(flavor).unwrap();
+/// assert_eq!(reading, Reading { valid: true, value: 5 });
+///
+/// // The same message, decoded as a struct with the fields the other way around
+/// #[derive(Deserialize, Debug)]
+/// struct Swapped {
+///     value: u8,
+///     valid: bool,
+/// }
+///
+/// let flavor = NibbleFieldTypes::new(NibbleSlice::new(&[0x01, 0x15]));
+/// let swapped = from_nibbles_with_flavor::<Swapped, _>(flavor);
+/// assert_eq!(swapped.err(), Some(Error::DeserializeFieldTypeMismatch));
+/// ```
+pub struct NibbleFieldTypes<F> {
+    flav: F,
+    /// A field was started, its tag comes before the next value
+    awaiting: bool,
+}
+
+impl<F> NibbleFieldTypes<F> {
+    /// Create a new `NibbleFieldTypes` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            awaiting: false,
+        }
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleFieldTypes<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.flav.try_take_bool()
+    }
+
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        self.flav.try_take_variant(name, variants)
+    }
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        // Rewinding to before a tag would have to start the field over
+        if self.awaiting {
+            return None;
+        }
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.awaiting = false;
+        self.flav.reset_to(mark)
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.awaiting = true;
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        // Only the outermost value of the field, e.g. the `Option` rather than its contents
+        if self.awaiting {
+            self.awaiting = false;
+            if self.flav.try_take_nib()? != ty.nibble() {
+                return Err(Error::DeserializeFieldTypeMismatch);
+            }
+        }
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Limits the work spent on decoding a message, independent of its size in bytes.
+///
+/// Every `serde::Deserializer` method call and every read from the wrapped flavor counts as a
+/// step, and exceeding `max_steps` results in [`Error::DeserializeStepLimit`]. A few bytes of
+/// input can otherwise describe a sequence of millions of zero sized values, or deep nesting,
+/// which would take a long time to decode without ever running out of input.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::{NibbleSlice, NibbleStepLimit};
+/// use postcard::{from_nibbles_with_flavor, Error};
+///
+/// // A bool and a u8: two values, two nibbles
+/// let flavor = NibbleStepLimit::new(NibbleSlice::new(&[0x17]), 5);
+/// assert_eq!(from_nibbles_with_flavor::<(bool, u8), _>(flavor).unwrap().0, (true, 7));
+///
+/// let flavor = NibbleStepLimit::new(NibbleSlice::new(&[0x17]), 4);
+/// let res = from_nibbles_with_flavor::<(bool, u8), _>(flavor);
+/// assert_eq!(res.err(), Some(Error::DeserializeStepLimit));
+/// ```
+pub struct NibbleStepLimit<F> {
+    flav: F,
+    steps: usize,
+    max_steps: usize,
+}
+
+impl<F> NibbleStepLimit<F> {
+    /// Create a new `NibbleStepLimit` modifier Flavor, allowing up to `max_steps` steps
+    pub fn new(flav: F, max_steps: usize) -> Self {
+        Self {
+            flav,
+            steps: 0,
+            max_steps,
+        }
+    }
+
+    /// The number of steps taken so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    #[inline]
+    fn step(&mut self) -> Result<()> {
+        if self.steps == self.max_steps {
+            return Err(Error::DeserializeStepLimit);
+        }
+        self.steps += 1;
+        Ok(())
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleStepLimit<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.step()?;
+        self.flav.try_take_bool()
+    }
+
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        self.step()?;
+        self.flav.try_take_variant(name, variants)
+    }
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.step()?;
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.step()?;
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.step()?;
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.step()?;
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        // The steps taken since the mark were still spent
+        self.flav.reset_to(mark)
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.step()?;
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Limits the number of fields of the outermost struct, or of the outermost struct variant, to
+/// the count found in front of messages written by
+/// [`to_nibble_vec_prefix_versioned()`](crate::to_nibble_vec_prefix_versioned).
+///
+/// Decoding a type with more fields than that results in [`Error::DeserializeMissingFields`]
+/// as soon as the first missing field is reached. Fewer fields are fine, the fields after them
+/// are left undecoded.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::{NibbleFieldLimit, NibbleSlice};
+/// use postcard::from_nibbles_with_flavor;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// struct Sample { id: u8, ok: bool }
+///
+/// let flavor = NibbleFieldLimit::new(NibbleSlice::new(&[0x51]), 2);
+/// assert_eq!(from_nibbles_with_flavor::<Sample, _>(flavor).unwrap().0, Sample { id: 5, ok: true });
+/// ```
+pub struct NibbleFieldLimit<F> {
+    flav: F,
+    fields: u32,
+    /// The number of fields currently being decoded
+    open: usize,
+    marks: Marks<(u32, usize)>,
+}
+
+impl<F> NibbleFieldLimit<F> {
+    /// Create a new `NibbleFieldLimit` modifier Flavor, allowing up to `fields` fields
+    pub fn new(flav: F, fields: u32) -> Self {
+        Self {
+            flav,
+            fields,
+            open: 0,
+            marks: Marks::new(),
+        }
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleFieldLimit<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.flav.try_take_bool()
+    }
+
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        self.flav.try_take_variant(name, variants)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    fn mark(&self) -> Option<NibbleMark> {
+        let mark = self.flav.mark()?;
+        self.marks.save(mark, (self.fields, self.open));
+        Some(mark)
+    }
+
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        // Fields started after the mark are decoded again
+        let (fields, open) = self.marks.restore(mark)?;
+        self.flav.reset_to(mark)?;
+        self.fields = fields;
+        self.open = open;
+        Ok(())
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    fn field_start(&mut self) -> Result<()> {
+        if self.open == 0 {
+            self.fields = self
+                .fields
+                .checked_sub(1)
+                .ok_or(Error::DeserializeMissingFields)?;
+        }
+        self.open += 1;
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    fn field_end(&mut self) -> Result<()> {
+        self.open -= 1;
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Checks a frame against the length declared in front of it: the frame starts with a nibble
+/// varint holding the length of the message that follows, in nibbles, as messages don't
+/// necessarily end on a byte boundary.
+///
+/// Consuming more than the declared length, or less of it by the time the flavor is finalized,
+/// results in [`Error::DeserializeLengthMismatch`]. This catches encoder bugs and truncated or
+/// concatenated frames.
+///
+/// The frame has to start on a byte boundary of the wrapped flavor. Modifier flavors are
+/// applied on top of this one, so that everything they consume is counted.
+///
+/// ```rust
+/// use postcard::de_nibble_flavors::{NibbleFlavor, NibbleFramedCheck, NibbleSlice};
+/// use postcard::Error;
+///
+/// // Two nibbles of message, `(true, 5u8)`
+/// let mut flav = NibbleFramedCheck::new(NibbleSlice::new(&[0x21, 0x50])).unwrap();
+/// assert_eq!(flav.try_take_bool(), Ok(true));
+/// assert_eq!(flav.try_take_nib(), Ok(5));
+/// assert!(flav.finalize().is_ok());
+///
+/// // The frame claims three nibbles, the message only uses two
+/// let mut flav = NibbleFramedCheck::new(NibbleSlice::new(&[0x31, 0x50])).unwrap();
+/// assert_eq!(flav.try_take_bool(), Ok(true));
+/// assert_eq!(flav.try_take_nib(), Ok(5));
+/// assert_eq!(flav.finalize().err(), Some(Error::DeserializeLengthMismatch));
+/// ```
+pub struct NibbleFramedCheck<'de, F> {
+    flav: F,
+    /// The declared length of the message
+    len: usize,
+    /// Nibbles taken so far, including the length
+    consumed: usize,
+    /// Nibbles taken by the length
+    header: usize,
+    _pl: PhantomData<&'de ()>,
+}
+
+impl<'de, F> NibbleFramedCheck<'de, F>
+where
+    F: NibbleFlavor<'de>,
+{
+    /// Create a new `NibbleFramedCheck` modifier Flavor, reading the declared length from `flav`
+    pub fn new(flav: F) -> Result<Self> {
+        let mut framed = Self {
+            flav,
+            // Until the length itself has been read
+            len: usize::MAX,
+            consumed: 0,
+            header: 0,
+            _pl: PhantomData,
+        };
+        framed.len = Vlu32N::de(&mut framed)?.0 as usize;
+        framed.header = framed.consumed;
+        Ok(framed)
+    }
+
+    /// The declared length of the message, in nibbles
+    pub fn declared_len(&self) -> usize {
+        self.len
+    }
+
+    fn consume(&mut self, nibbles: usize) -> Result<()> {
+        self.consumed += nibbles;
+        if self.consumed - self.header > self.len {
+            return Err(Error::DeserializeLengthMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleFramedCheck<'de, F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        let nib = self.flav.try_take_nib()?;
+        self.consume(1)?;
+        Ok(nib)
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        let byte = self.flav.try_take_u8()?;
+        self.consume(2)?;
+        Ok(byte)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        let data = self.flav.try_take_n(ct)?;
+        // Including the padding nibble in front of the bytes
+        self.consume(self.consumed % 2 + ct * 2)?;
+        Ok(data)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        let now = self.flav.mark().ok_or(Error::NotYetImplemented)?;
+        let rewound = mark
+            .nibbles_left
+            .checked_sub(now.nibbles_left)
+            .filter(|n| *n <= self.consumed - self.header)
+            .ok_or(Error::DeserializeBadEncoding)?;
+        self.flav.reset_to(mark)?;
+        self.consumed -= rewound;
+        Ok(())
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        if self.consumed - self.header != self.len {
+            return Err(Error::DeserializeLengthMismatch);
+        }
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the output of the serialization `NibbleAead` flavor. The authentication tag is
+/// checked against the associated data and the message, which is then decrypted in place, when
+/// the flavor is created, so nothing is decoded from data that is not authentic.
+///
+/// The whole buffer has to be the encrypted message followed by the tag, the remainder is the
+/// unused part of the decrypted message.
+pub struct NibbleAead<'de> {
+    flav: NibbleSlice<'de>,
+}
+
+impl<'de> NibbleAead<'de> {
+    /// Create a new `NibbleAead` Flavor, authenticating and decrypting `buf` with `cipher`.
+    ///
+    /// Returns [`Error::DeserializeUnexpectedEnd`] if `buf` is shorter than a tag, or the
+    /// error of the cipher, usually [`Error::DeserializeBadEncoding`], if it is not authentic.
+    pub fn new<A>(buf: &'de mut [u8], aad: &[u8], mut cipher: A) -> Result<Self>
+    where
+        A: NibbleAeadCipher,
+    {
+        let len = buf
+            .len()
+            .checked_sub(A::TAG_LEN)
+            .ok_or(Error::DeserializeUnexpectedEnd)?;
+        let (message, tag) = buf.split_at_mut(len);
+        cipher.decrypt_in_place(aad, message, tag)?;
+        Ok(Self {
+            flav: NibbleSlice::new(message),
+        })
+    }
+}
+
+impl<'de> NibbleFlavor<'de> for NibbleAead<'de> {
+    type Remainder = &'de [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the output of the serialization `NibbleParity` flavor. All check nibbles are
+/// verified up front, and the message is restored in place in the first half of the buffer.
+///
+/// Single bit errors are corrected if enabled with [`with_correction`](Self::with_correction),
+/// anything else results in [`Error::DeserializeParityError`].
+pub struct NibbleParity<'de> {
+    flav: NibbleSlice<'de>,
+    corrected: usize,
+}
+
+impl<'de> NibbleParity<'de> {
+    /// Create a new `NibbleParity` Flavor, which rejects any bit error in `buf`
+    pub fn new(buf: &'de mut [u8]) -> Result<Self> {
+        Self::decode(buf, false)
+    }
+
+    /// Create a new `NibbleParity` Flavor, correcting single bit errors in `buf`
+    pub fn with_correction(buf: &'de mut [u8]) -> Result<Self> {
+        Self::decode(buf, true)
+    }
+
+    /// The number of nibbles with a corrected bit error
+    pub fn corrected(&self) -> usize {
+        self.corrected
+    }
+
+    fn decode(buf: &'de mut [u8], correct: bool) -> Result<Self> {
+        let mut corrected = 0;
+        for i in 0..buf.len() {
+            let nib = match parity_decode(buf[i]) {
+                Some((nib, false)) => nib,
+                Some((nib, true)) if correct => {
+                    corrected += 1;
+                    nib
+                }
+                _ => return Err(Error::DeserializeParityError),
+            };
+            // Never ahead of the byte being decoded
+            if i % 2 == 0 {
+                buf[i / 2] = nib << 4;
+            } else {
+                buf[i / 2] |= nib;
+            }
+        }
+        let len = buf.len().div_ceil(2);
+        Ok(Self {
+            flav: NibbleSlice::new(&buf[..len]),
+            corrected,
+        })
+    }
+}
+
+impl<'de> NibbleFlavor<'de> for NibbleParity<'de> {
+    type Remainder = &'de [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Decodes a line written by the serialization `NibbleLineSafe` flavor. The line is checked and
+/// restored to the plain message in place, in the first half of its buffer.
+///
+/// `buf` has to contain the whole line including its `\n`, otherwise this fails with
+/// [`Error::DeserializeUnexpectedEnd`]. Characters outside of the alphabet result in
+/// [`Error::DeserializeBadEncoding`]. Anything after the `\n`, such as the next lines, is
+/// left untouched and returned as the remainder.
+///
+/// ```rust
+/// use postcard::{from_nibbles_with_flavor, de_nibble_flavors::NibbleLineSafe};
+///
+/// let mut buf = *b"bh\nbf\n";
+/// let (value, rest) = from_nibbles_with_flavor::<(bool, u8), _>(NibbleLineSafe::new(&mut buf).unwrap()).unwrap();
+/// assert_eq!(value, (true, 7));
+/// assert_eq!(rest, b"bf\n");
+/// ```
+pub struct NibbleLineSafe<'de> {
+    flav: NibbleSlice<'de>,
+    rest: &'de [u8],
+}
+
+impl<'de> NibbleLineSafe<'de> {
+    /// Create a new `NibbleLineSafe` Flavor from a buffer starting with a line
+    pub fn new(buf: &'de mut [u8]) -> Result<Self> {
+        let end = buf
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or(Error::DeserializeUnexpectedEnd)?;
+        let (line, rest) = buf.split_at_mut(end);
+        for i in 0..line.len() {
+            let nib = line[i].wrapping_sub(LINE_SAFE_BASE);
+            if nib > 0x0F {
+                return Err(Error::DeserializeBadEncoding);
+            }
+            // Never ahead of the character being decoded
+            if i % 2 == 0 {
+                line[i / 2] = nib << 4;
+            } else {
+                line[i / 2] |= nib;
+            }
+        }
+        let len = line.len().div_ceil(2);
+        Ok(Self {
+            flav: NibbleSlice::new(&line[..len]),
+            rest: &rest[1..],
+        })
+    }
+}
+
+impl<'de> NibbleFlavor<'de> for NibbleLineSafe<'de> {
+    type Remainder = &'de [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        Ok(self.rest)
+    }
+}
+
+/// Decodes the output of the serialization `NibbleAligned` flavor, skipping the padding
+/// before byte runs and at the end of the message. `align` has to match the one used when
+/// serializing, and [`align_to()`](Self::align_to) calls have to be mirrored.
+///
+/// Positions are counted from the start of the message, so this flavor should wrap the
+/// storage flavor directly, and flavors such as `NibblePackedBools` wrap this one. Rewinding
+/// works as far as the storage flavor supports it.
+pub struct NibbleAligned<F> {
+    flav: F,
+    /// The boundary for byte runs and the end of the message, in nibbles
+    boundary: usize,
+    /// The number of nibbles read so far
+    nibbles: usize,
+    marks: Marks<usize>,
+}
+
+impl<F> NibbleAligned<F> {
+    /// Create a new `NibbleAligned` modifier Flavor, aligned to `align` bytes. An `align` of
+    /// zero is treated as one, which is the alignment of the plain format.
+    pub fn new(flav: F, align: usize) -> Self {
+        Self {
+            flav,
+            boundary: align.max(1) * 2,
+            nibbles: 0,
+            marks: Marks::new(),
+        }
+    }
+}
+
+impl<'de, F> NibbleAligned<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    /// Skip the padding up to the next multiple of `bytes` bytes, counted from the start of
+    /// the message
+    pub fn align_to(&mut self, bytes: usize) -> Result<()> {
+        let boundary = bytes.max(1) * 2;
+        while !self.nibbles.is_multiple_of(boundary) {
+            self.try_take_nib()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleAligned<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        let nib = self.flav.try_take_nib()?;
+        self.nibbles += 1;
+        Ok(nib)
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        let byte = self.flav.try_take_u8()?;
+        self.nibbles += 2;
+        Ok(byte)
+    }
+
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.align_to(self.boundary / 2)?;
+        let data = self.flav.try_take_n(ct)?;
+        self.nibbles += ct * 2;
+        Ok(data)
+    }
+
+    fn mark(&self) -> Option<NibbleMark> {
+        let mark = self.flav.mark()?;
+        self.marks.save(mark, self.nibbles);
+        Some(mark)
+    }
+
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        let nibbles = self.marks.restore(mark)?;
+        self.flav.reset_to(mark)?;
+        self.nibbles = nibbles;
+        Ok(())
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(mut self) -> Result<Self::Remainder> {
+        self.align_to(self.boundary / 2)?;
+        self.flav.finalize()
+    }
+}
+
+/// Decodes a message obfuscated by the serialization `NibbleXor` flavor. The whole buffer is
+/// XORed with the key in place, restoring the plain message, which is then read like with a
+/// [`NibbleSlice`].
+///
+/// Decoding with the wrong key yields an arbitrary plain message, which most likely fails to
+/// decode, but may also decode to different values. The remainder is restored as well.
+///
+/// ```rust
+/// use postcard::{from_nibbles_with_flavor, de_nibble_flavors::NibbleXor};
+///
+/// let mut buf = [0x17 ^ 0xFF, 0x10 ^ 0xFF, b'a' ^ 0xFF];
+/// let (value, _) = from_nibbles_with_flavor::<(bool, u8, &str), _>(NibbleXor::new(&mut buf, &[0xFF])).unwrap();
+/// assert_eq!(value, (true, 7, "a"));
+/// ```
+pub struct NibbleXor<'de> {
+    flav: NibbleSlice<'de>,
+}
+
+impl<'de> NibbleXor<'de> {
+    /// Create a new `NibbleXor` Flavor, restoring `buf` with `key`. An empty key leaves the
+    /// buffer unchanged.
+    pub fn new(buf: &'de mut [u8], key: &[u8]) -> Self {
+        if !key.is_empty() {
+            buf.iter_mut()
+                .zip(key.iter().cycle())
+                .for_each(|(b, k)| *b ^= k);
+        }
+        Self {
+            flav: NibbleSlice::new(buf),
+        }
+    }
+}
+
+impl<'de> NibbleFlavor<'de> for NibbleXor<'de> {
+    type Remainder = &'de [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.flav.try_take_nib()
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.flav.try_take_u8()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.flav.try_take_n(ct)
+    }
+
+    #[inline]
+    fn mark(&self) -> Option<NibbleMark> {
+        self.flav.mark()
+    }
+
+    #[inline]
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        self.flav.reset_to(mark)
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the output of the serialization `NibbleStringIntern` flavor, resolving repeated
+/// strings from the manifest of strings decoded so far.
+///
+/// A string referring to a manifest entry that doesn't exist results in
+/// [`Error::DeserializeBadEncoding`]. Repeated strings are borrowed from their first
+/// occurrence in the input. Rewinding to a mark, as untagged values do, also drops the
+/// manifest entries decoded since.
+///
+/// ```rust
+/// use postcard::from_nibbles_with_flavor;
+/// use postcard::de_nibble_flavors::{NibbleSlice, NibbleStringIntern};
+///
+/// let frame = [0x04, b'd', b'e', b'g', b'C', 0x11];
+/// let flavor = NibbleStringIntern::new(NibbleSlice::new(&frame));
+/// let (units, _) = from_nibbles_with_flavor::<[&str; 3], _>(flavor).unwrap();
+/// assert_eq!(units, ["degC", "degC", "degC"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub struct NibbleStringIntern<'de, F> {
+    flav: F,
+    manifest: alloc::vec::Vec<&'de [u8]>,
+    state: InternState<'de>,
+    /// The length of a repeated string, as nibbles of a varint still to be taken
+    length: VarintNibbles,
+    /// The manifest length and the string state at each mark
+    marks: Marks<(usize, InternState<'de>, VarintNibbles)>,
+}
+
+/// Where the `NibbleStringIntern` flavor is within a string
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy)]
+enum InternState<'de> {
+    /// Outside of strings
+    Idle,
+    /// At the start of a string, before its tag
+    Tag,
+    /// Within a string that is new to the manifest
+    New,
+    /// Within a repeated string
+    Repeated(&'de [u8]),
+}
+
+/// The nibbles of an encoded varint, to be taken one by one
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy)]
+struct VarintNibbles {
+    nibs: [u8; 11],
+    len: usize,
+    pos: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl VarintNibbles {
+    fn set(&mut self, val: u32) -> Result<()> {
+        self.len = 0;
+        self.pos = 0;
+        Vlu32N(val).ser(self)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.nibs[self.pos..self.len].first().copied()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl crate::ser::nibble_flavors::NibbleFlavor for VarintNibbles {
+    type Output = ();
+
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.try_push_nib(data >> 4)?;
+        self.try_push_nib(data & 0x0F)
+    }
+
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        let slot = self
+            .nibs
+            .get_mut(self.len)
+            .ok_or(Error::SerializeBufferFull)?;
+        *slot = nib;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, F> NibbleStringIntern<'de, F> {
+    /// Create a new `NibbleStringIntern` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            manifest: alloc::vec::Vec::new(),
+            state: InternState::Idle,
+            length: VarintNibbles {
+                nibs: [0; 11],
+                len: 0,
+                pos: 0,
+            },
+            marks: Marks::new(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, F> NibbleStringIntern<'de, F>
+where
+    F: NibbleFlavor<'de>,
+{
+    /// Take the tag in front of a string once its length is about to be taken
+    fn take_tag(&mut self) -> Result<()> {
+        if let InternState::Tag = self.state {
+            let tag = Vlu32N::de(&mut self.flav)?.0;
+            self.state = match tag.checked_sub(1) {
+                None => InternState::New,
+                Some(i) => {
+                    let s = *self
+                        .manifest
+                        .get(i as usize)
+                        .ok_or(Error::DeserializeBadEncoding)?;
+                    self.length.set(s.len() as u32)?;
+                    InternState::Repeated(s)
+                }
+            };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, F> NibbleFlavor<'de> for NibbleStringIntern<'de, F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        self.take_tag()?;
+        match self.length.peek() {
+            Some(nib) => {
+                self.length.pos += 1;
+                Ok(nib)
+            }
+            None => self.flav.try_take_nib(),
+        }
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.take_tag()?;
+        match self.length.peek() {
+            Some(nib) => Ok(nib),
+            None => self.flav.try_peek_nib(),
+        }
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        self.take_tag()?;
+        match self.length.peek() {
+            Some(_) => Ok((self.try_take_nib()? << 4) | self.try_take_nib()?),
+            None => self.flav.try_take_u8(),
+        }
+    }
+
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.take_tag()?;
+        match core::mem::replace(&mut self.state, InternState::Idle) {
+            InternState::Repeated(s) if s.len() == ct => Ok(s),
+            InternState::Repeated(_) => Err(Error::DeserializeBadEncoding),
+            InternState::New => {
+                let s = self.flav.try_take_n(ct)?;
+                self.manifest.push(s);
+                Ok(s)
+            }
+            _ => self.flav.try_take_n(ct),
+        }
+    }
+
+    #[inline]
+    fn try_take_bool(&mut self) -> Result<bool> {
+        self.flav.try_take_bool()
+    }
+
+    #[inline]
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        self.flav.try_take_variant(name, variants)
+    }
+
+    fn mark(&self) -> Option<NibbleMark> {
+        let mark = self.flav.mark()?;
+        self.marks
+            .save(mark, (self.manifest.len(), self.state, self.length));
+        Some(mark)
+    }
+
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        let (len, state, length) = self.marks.restore(mark)?;
+        self.flav.reset_to(mark)?;
+        // Strings decoded after the mark are decoded again
+        self.manifest.truncate(len);
+        self.state = state;
+        self.length = length;
+        Ok(())
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.state = match ty {
+            NibbleFieldType::Str => InternState::Tag,
+            _ => InternState::Idle,
+        };
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(self) -> Result<Self::Remainder> {
+        self.flav.finalize()
+    }
+}
+
+/// Verifies the Fletcher-16 checksum appended by the serialization `NibbleFletcher16` flavor
+/// when finalizing. Booleans and enum variants are taken from the wrapped flavor, mirroring
+/// the serialization flavor, and rewinding works as far as the wrapped flavor supports it.
+///
+/// A mismatching checksum results in [`Error::DeserializeBadChecksum`].
+///
+/// ```rust
+/// use postcard::{from_nibbles_with_flavor, Error};
+/// use postcard::de_nibble_flavors::{NibbleFletcher16, NibbleSlice};
+///
+/// let frame = [0x17, 0x00, 0x17, 0x2E];
+/// let (value, _) = from_nibbles_with_flavor::<(bool, u8, bool), _>(NibbleFletcher16::new(NibbleSlice::new(&frame))).unwrap();
+/// assert_eq!(value, (true, 7, false));
+///
+/// let corrupt = [0x17, 0x00, 0x17, 0x2F];
+/// let res = from_nibbles_with_flavor::<(bool, u8, bool), _>(NibbleFletcher16::new(NibbleSlice::new(&corrupt)));
+/// assert_eq!(res.err(), Some(Error::DeserializeBadChecksum));
+/// ```
+pub struct NibbleFletcher16<F> {
+    flav: F,
+    sum: Fletcher16,
+    /// Whether the inner flavor took everything in its plain encoding so far
+    plain: bool,
+    marks: Marks<(Fletcher16, bool)>,
+}
+
+impl<F> NibbleFletcher16<F> {
+    /// Create a new `NibbleFletcher16` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            sum: Fletcher16::new(),
+            plain: true,
+            marks: Marks::new(),
+        }
+    }
+}
+
+impl<'de, F> NibbleFletcher16<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    fn align(&mut self) -> Result<()> {
+        // The padding nibble is read rather than assumed to be zero, so that it is covered by
+        // the checksum as well. After booleans or variants in another encoding it can't be
+        // located, then the inner flavor skips it when aligning.
+        if !self.sum.is_aligned() {
+            let pad = if self.plain {
+                self.flav.try_take_nib()?
+            } else {
+                0
+            };
+            self.sum.nib(pad);
+        }
+        Ok(())
+    }
+}
+
+impl<'de, F> NibbleFlavor<'de> for NibbleFletcher16<F>
+where
+    F: NibbleFlavor<'de>,
+{
+    type Remainder = F::Remainder;
+    type Source = F;
+
+    #[inline]
+    fn try_take_nib(&mut self) -> Result<u8> {
+        let nib = self.flav.try_take_nib()?;
+        self.sum.nib(nib);
+        Ok(nib)
+    }
+
+    #[inline]
+    fn try_peek_nib(&mut self) -> Result<u8> {
+        self.flav.try_peek_nib()
+    }
+
+    #[inline]
+    fn try_take_u8(&mut self) -> Result<u8> {
+        let byte = self.flav.try_take_u8()?;
+        self.sum.byte(byte);
+        Ok(byte)
+    }
+
+    fn try_take_bool(&mut self) -> Result<bool> {
+        let before = self.flav.mark();
+        let b = self.flav.try_take_bool()?;
+        self.plain &= took(before, self.flav.mark(), 1);
+        self.sum.nib(b as u8);
+        Ok(b)
+    }
+
+    fn try_take_variant(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+    ) -> Result<u32> {
+        let before = self.flav.mark();
+        let index = Vlu32N(self.flav.try_take_variant(name, variants)?);
+        self.plain &= took(before, self.flav.mark(), index.nibble_len());
+        index.nibbles().for_each(|nib| self.sum.nib(nib));
+        Ok(index.0)
+    }
+
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.align()?;
+        let data = self.flav.try_take_n(ct)?;
+        self.sum.run(data);
+        Ok(data)
+    }
+
+    fn mark(&self) -> Option<NibbleMark> {
+        let mark = self.flav.mark()?;
+        self.marks.save(mark, (self.sum.clone(), self.plain));
+        Some(mark)
+    }
+
+    fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+        let (sum, plain) = self.marks.restore(mark)?;
+        self.flav.reset_to(mark)?;
+        self.sum = sum;
+        self.plain = plain;
+        Ok(())
+    }
+
+    #[inline]
+    fn non_canonical_varint(&mut self) -> Result<()> {
+        self.flav.non_canonical_varint()
+    }
+
+    #[inline]
+    fn field_start(&mut self) -> Result<()> {
+        self.flav.field_start()
+    }
+
+    #[inline]
+    fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+        self.flav.value_start(ty)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.field_end()
+    }
+
+    fn finalize(mut self) -> Result<Self::Remainder> {
+        self.align()?;
+        let expected = self.sum.sum().to_le_bytes();
+        if self.flav.try_take_n(2)? != &expected[..] {
+            return Err(Error::DeserializeBadChecksum);
+        }
+        self.flav.finalize()
+    }
+}
+
+/// Decodes the output of the serialization `NibbleCrcCheckpoints` flavor, verifying each CRC
+/// checkpoint as soon as it is reached, see `ser_nibble_flavors::crc` for the format.
+///
+/// A mismatching CRC results in [`Error::DeserializeBadChecksum`](crate::Error::DeserializeBadChecksum).
+///
+/// The `use-crc` feature is required to use this module.
+#[cfg(feature = "use-crc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-crc")))]
+pub mod crc {
+    use crc::Digest;
+    use crc::Width;
+    use serde::Deserialize;
+
+    use super::{took, Marks, NibbleFlavor, NibbleMark, NibbleSlice};
+    use crate::de::nibble_deserializer::NibbleDeserializer;
+    use crate::ser::nibble_flavors::crc::{
+        StreamBytes, CRC_32, SLIP_END, SLIP_ESC, SLIP_ESC_END, SLIP_ESC_ESC,
+    };
+    use crate::ser::nibble_flavors::NibbleFieldType;
+    use crate::vlu32n::Vlu32N;
+    use crate::{Error, Result};
+    use paste::paste;
+
+    /// Verifies CRC checkpoints while decoding, see the [module level docs](self).
+    pub struct NibbleCrcCheckpoints<'de, F, W>
+    where
+        F: NibbleFlavor<'de>,
+        W: Width,
+    {
+        flav: F,
+        digest: Digest<'de, W>,
+        bytes: StreamBytes,
+        /// Whether the inner flavor took everything in its plain encoding so far
+        plain: bool,
+        marks: Marks<(Digest<'de, W>, StreamBytes, bool)>,
+    }
+
+    impl<'de, F, W> NibbleCrcCheckpoints<'de, F, W>
+    where
+        F: NibbleFlavor<'de>,
+        W: Width,
+    {
+        /// Create a new `NibbleCrcCheckpoints` modifier Flavor, `interval` has to match
+        /// the one used when serializing.
+        pub fn new(flav: F, digest: Digest<'de, W>, interval: usize) -> Self {
+            Self {
+                flav,
+                digest,
+                bytes: StreamBytes::new(interval),
+                plain: true,
+                marks: Marks::new(),
+            }
+        }
+    }
+
+    macro_rules! impl_flavor {
+        ($( $int:ty ),*) => {
+            $(
+                paste! {
+                    impl<'de, F> NibbleCrcCheckpoints<'de, F, $int>
+                    where
+                        F: NibbleFlavor<'de>,
+                    {
+                        fn check(&mut self, digest: Digest<'de, $int>) -> Result<()> {
+                            let crc = self.flav.try_take_n(core::mem::size_of::<$int>())?;
+                            if crc == &digest.finalize().to_le_bytes()[..] {
+                                Ok(())
+                            } else {
+                                Err(Error::DeserializeBadChecksum)
+                            }
+                        }
+
+                        fn checkpoint(&mut self) -> Result<()> {
+                            if self.bytes.checkpoint() {
+                                self.check(self.digest.clone())?;
+                            }
+                            Ok(())
+                        }
+
+                        fn record(&mut self, nib: u8) {
+                            if let Some(done) = self.bytes.nib(nib) {
+                                self.digest.update(&[done]);
+                            }
+                        }
+
+                        fn align(&mut self) -> Result<()> {
+                            // The padding nibble is read rather than assumed to be zero, so
+                            // that it is covered by the CRC as well. After booleans or
+                            // variants in another encoding it can't be located, then the
+                            // inner flavor skips it when aligning.
+                            if !self.bytes.is_aligned() {
+                                let pad = if self.plain {
+                                    self.flav.try_take_nib()?
+                                } else {
+                                    0
+                                };
+                                self.record(pad);
+                            }
+                            Ok(())
+                        }
+                    }
+
+                    impl<'de, F> NibbleFlavor<'de> for NibbleCrcCheckpoints<'de, F, $int>
+                    where
+                        F: NibbleFlavor<'de>,
+                    {
+                        type Remainder = F::Remainder;
+                        type Source = F::Source;
+
+                        fn try_take_nib(&mut self) -> Result<u8> {
+                            self.checkpoint()?;
+                            let nib = self.flav.try_take_nib()?;
+                            self.record(nib);
+                            Ok(nib)
+                        }
+
+                        fn try_peek_nib(&mut self) -> Result<u8> {
+                            self.checkpoint()?;
+                            self.flav.try_peek_nib()
+                        }
+
+                        fn try_take_u8(&mut self) -> Result<u8> {
+                            self.checkpoint()?;
+                            let byte = self.flav.try_take_u8()?;
+                            let done = self.bytes.byte(byte);
+                            self.digest.update(&[done]);
+                            Ok(byte)
+                        }
+
+                        fn try_take_bool(&mut self) -> Result<bool> {
+                            self.checkpoint()?;
+                            let before = self.flav.mark();
+                            let b = self.flav.try_take_bool()?;
+                            self.plain &= took(before, self.flav.mark(), 1);
+                            self.record(b as u8);
+                            Ok(b)
+                        }
+
+                        fn try_take_variant(
+                            &mut self,
+                            name: &'static str,
+                            variants: &'static [&'static str],
+                        ) -> Result<u32> {
+                            self.checkpoint()?;
+                            let before = self.flav.mark();
+                            let index = Vlu32N(self.flav.try_take_variant(name, variants)?);
+                            self.plain &= took(before, self.flav.mark(), index.nibble_len());
+                            index.nibbles().for_each(|nib| self.record(nib));
+                            Ok(index.0)
+                        }
+
+                        fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+                            self.checkpoint()?;
+                            self.align()?;
+                            let data = self.flav.try_take_n(ct)?;
+                            self.bytes.run(ct);
+                            self.digest.update(data);
+                            Ok(data)
+                        }
+
+                        fn mark(&self) -> Option<NibbleMark> {
+                            let mark = self.flav.mark()?;
+                            let state = (self.digest.clone(), self.bytes.clone(), self.plain);
+                            self.marks.save(mark, state);
+                            Some(mark)
+                        }
+
+                        fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+                            let (digest, bytes, plain) = self.marks.restore(mark)?;
+                            self.flav.reset_to(mark)?;
+                            self.digest = digest;
+                            self.bytes = bytes;
+                            self.plain = plain;
+                            Ok(())
+                        }
+
+                        #[inline]
+                        fn non_canonical_varint(&mut self) -> Result<()> {
+                            self.flav.non_canonical_varint()
+                        }
+
+                        #[inline]
+                        fn field_start(&mut self) -> Result<()> {
+                            self.flav.field_start()
+                        }
+
+                        #[inline]
+                        fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+                            self.flav.value_start(ty)
+                        }
+
+                        #[inline]
+                        fn field_end(&mut self) -> Result<()> {
+                            self.flav.field_end()
+                        }
+
+                        fn finalize(mut self) -> Result<Self::Remainder> {
+                            self.align()?;
+                            let digest = self.digest.clone();
+                            self.check(digest)?;
+                            self.flav.finalize()
+                        }
+                    }
+
+                    /// Deserialize a message of type `T` from a nibble byte slice with CRC
+                    /// checkpoints. The unused portion (if any) of the byte slice is not returned.
+                    pub fn [<from_nibbles_ $int>]<'a, T>(
+                        s: &'a [u8],
+                        digest: Digest<'a, $int>,
+                        interval: usize,
+                    ) -> Result<T>
+                    where
+                        T: Deserialize<'a>,
+                    {
+                        let flav = NibbleCrcCheckpoints::new(NibbleSlice::new(s), digest, interval);
+                        let mut deserializer = NibbleDeserializer::from_flavor(flav);
+                        let t = T::deserialize(&mut deserializer)?;
+                        deserializer.finalize()?;
+                        Ok(t)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_flavor![u8, u16, u32, u64, u128];
+
+    /// Verifies the CRC-32 appended by the serialization `NibbleCrc32` flavor when finalizing.
+    ///
+    /// A mismatching CRC results in [`Error::DeserializeBadChecksum`](crate::Error::DeserializeBadChecksum).
+    pub struct NibbleCrc32<'de, F>
+    where
+        F: NibbleFlavor<'de>,
+    {
+        flav: NibbleCrcCheckpoints<'de, F, u32>,
+    }
+
+    impl<'de, F> NibbleCrc32<'de, F>
+    where
+        F: NibbleFlavor<'de>,
+    {
+        /// Create a new `NibbleCrc32` modifier Flavor
+        pub fn new(flav: F) -> Self {
+            Self {
+                flav: NibbleCrcCheckpoints::new(flav, CRC_32.digest(), usize::MAX),
+            }
+        }
+    }
+
+    impl<'de, F> NibbleFlavor<'de> for NibbleCrc32<'de, F>
+    where
+        F: NibbleFlavor<'de>,
+    {
+        type Remainder = F::Remainder;
+        type Source = F::Source;
+
+        #[inline]
+        fn try_take_nib(&mut self) -> Result<u8> {
+            self.flav.try_take_nib()
+        }
+
+        #[inline]
+        fn try_peek_nib(&mut self) -> Result<u8> {
+            self.flav.try_peek_nib()
+        }
+
+        #[inline]
+        fn try_take_u8(&mut self) -> Result<u8> {
+            self.flav.try_take_u8()
+        }
+
+        #[inline]
+        fn try_take_bool(&mut self) -> Result<bool> {
+            self.flav.try_take_bool()
+        }
+
+        #[inline]
+        fn try_take_variant(
+            &mut self,
+            name: &'static str,
+            variants: &'static [&'static str],
+        ) -> Result<u32> {
+            self.flav.try_take_variant(name, variants)
+        }
+
+        #[inline]
+        fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+            self.flav.try_take_n(ct)
+        }
+
+        #[inline]
+        fn mark(&self) -> Option<NibbleMark> {
+            self.flav.mark()
+        }
+
+        #[inline]
+        fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+            self.flav.reset_to(mark)
+        }
+
+        #[inline]
+        fn non_canonical_varint(&mut self) -> Result<()> {
+            self.flav.non_canonical_varint()
+        }
+
+        #[inline]
+        fn field_start(&mut self) -> Result<()> {
+            self.flav.field_start()
+        }
+
+        #[inline]
+        fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+            self.flav.value_start(ty)
+        }
+
+        #[inline]
+        fn field_end(&mut self) -> Result<()> {
+            self.flav.field_end()
+        }
+
+        fn finalize(self) -> Result<Self::Remainder> {
+            self.flav.finalize()
+        }
+    }
+
+    /// Decodes a frame written by the serialization `NibbleSlip` flavor. The frame is unescaped
+    /// in place, at the start of its buffer, and its CRC is verified when finalizing.
+    ///
+    /// `buf` has to contain the whole frame including its `SLIP_END`, otherwise this fails
+    /// with [`Error::DeserializeUnexpectedEnd`]. Invalid escape sequences or unused data before
+    /// the CRC result in [`Error::DeserializeBadEncoding`], a mismatching CRC in
+    /// [`Error::DeserializeBadChecksum`]. Anything after the `SLIP_END`, such as the next
+    /// frames, is left untouched and returned as the remainder.
+    ///
+    /// ```rust
+    /// use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+    /// use postcard::de_nibble_flavors::crc::NibbleSlip;
+    /// use postcard::ser_nibble_flavors::{crc::NibbleSlip as SerSlip, NibbleSlice};
+    ///
+    /// let mut buf = [0u8; 32];
+    /// let used = serialize_with_nibble_flavor(&(true, 7u8), SerSlip::new(NibbleSlice::new(&mut buf))).unwrap().len();
+    /// let (value, rest) = from_nibbles_with_flavor::<(bool, u8), _>(NibbleSlip::new(&mut buf).unwrap()).unwrap();
+    /// assert_eq!(value, (true, 7));
+    /// assert_eq!(rest.len(), 32 - used);
+    /// ```
+    pub struct NibbleSlip<'de> {
+        flav: NibbleCrc32<'de, NibbleSlice<'de>>,
+        rest: &'de [u8],
+    }
+
+    impl<'de> NibbleSlip<'de> {
+        /// Create a new `NibbleSlip` Flavor from a buffer starting with a frame
+        pub fn new(buf: &'de mut [u8]) -> Result<Self> {
+            let end = buf
+                .iter()
+                .position(|b| *b == SLIP_END)
+                .ok_or(Error::DeserializeUnexpectedEnd)?;
+            let (frame, rest) = buf.split_at_mut(end);
+            let mut len = 0;
+            let mut i = 0;
+            while i < frame.len() {
+                let byte = match frame[i] {
+                    SLIP_ESC => {
+                        i += 1;
+                        match frame.get(i) {
+                            Some(&SLIP_ESC_END) => SLIP_END,
+                            Some(&SLIP_ESC_ESC) => SLIP_ESC,
+                            _ => return Err(Error::DeserializeBadEncoding),
+                        }
+                    }
+                    byte => byte,
+                };
+                // Never ahead of the byte being unescaped
+                frame[len] = byte;
+                len += 1;
+                i += 1;
+            }
+            Ok(Self {
+                flav: NibbleCrc32::new(NibbleSlice::new(&frame[..len])),
+                rest: &rest[1..],
+            })
+        }
+    }
+
+    impl<'de> NibbleFlavor<'de> for NibbleSlip<'de> {
+        type Remainder = &'de [u8];
+        type Source = &'de [u8];
+
+        #[inline]
+        fn try_take_nib(&mut self) -> Result<u8> {
+            self.flav.try_take_nib()
+        }
+
+        #[inline]
+        fn try_peek_nib(&mut self) -> Result<u8> {
+            self.flav.try_peek_nib()
+        }
+
+        #[inline]
+        fn try_take_u8(&mut self) -> Result<u8> {
+            self.flav.try_take_u8()
+        }
+
+        #[inline]
+        fn try_take_bool(&mut self) -> Result<bool> {
+            self.flav.try_take_bool()
+        }
+
+        #[inline]
+        fn try_take_variant(
+            &mut self,
+            name: &'static str,
+            variants: &'static [&'static str],
+        ) -> Result<u32> {
+            self.flav.try_take_variant(name, variants)
+        }
+
+        #[inline]
+        fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+            self.flav.try_take_n(ct)
+        }
+
+        #[inline]
+        fn mark(&self) -> Option<NibbleMark> {
+            self.flav.mark()
+        }
+
+        #[inline]
+        fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+            self.flav.reset_to(mark)
+        }
+
+        #[inline]
+        fn non_canonical_varint(&mut self) -> Result<()> {
+            self.flav.non_canonical_varint()
+        }
+
+        #[inline]
+        fn field_start(&mut self) -> Result<()> {
+            self.flav.field_start()
+        }
+
+        #[inline]
+        fn value_start(&mut self, ty: NibbleFieldType) -> Result<()> {
+            self.flav.value_start(ty)
+        }
+
+        #[inline]
+        fn field_end(&mut self) -> Result<()> {
+            self.flav.field_end()
+        }
+
+        fn finalize(self) -> Result<Self::Remainder> {
+            if !self.flav.finalize()?.is_empty() {
+                // The CRC has to end the frame
+                return Err(Error::DeserializeBadEncoding);
+            }
+            Ok(self.rest)
+        }
+    }
+}
+
+#[cfg(feature = "use-std")]
+mod io_reader {
+    use super::NibbleFlavor;
+    use crate::{Error, Result};
+    use std::io::Read;
+
+    /// Decodes a message straight from a [`std::io::Read`], such as a file or socket.
+    ///
+    /// Borrowed data, such as `&str` fields, has to outlive the flavor, so it is read into a
+    /// scratch buffer provided by the caller, which must be large enough for all byte runs of
+    /// the message together. Everything else is read from the reader as needed, byte by byte,
+    /// so wrapping it in a [`std::io::BufReader`] is a good idea.
+    ///
+    /// The input ending early, or any other I/O error, results in
+    /// [`Error::DeserializeUnexpectedEnd`], as does running out of scratch space.
+    ///
+    /// ```rust
+    /// use postcard::{from_nibbles_with_flavor, de_nibble_flavors::NibbleIOReader};
+    ///
+    /// let input: &[u8] = &[0x12, b'h', b'i', 0x50];
+    /// let mut scratch = [0u8; 8];
+    /// let flavor = NibbleIOReader::new(input, &mut scratch);
+    /// let ((flag, name), (rest, _)) = from_nibbles_with_flavor::<(bool, &str), _>(flavor).unwrap();
+    /// assert_eq!((flag, name), (true, "hi"));
+    /// // The next message is still in the reader
+    /// assert_eq!(rest, &[0x50]);
+    /// ```
+    ///
+    /// This type is only available when the (non-default) `use-std` feature is active
+    pub struct NibbleIOReader<'de, R>
+    where
+        R: Read,
+    {
+        reader: R,
+        scratch: &'de mut [u8],
+        /// The low nibble of a byte whose high nibble was consumed
+        pending: Option<u8>,
+    }
+
+    impl<'de, R> NibbleIOReader<'de, R>
+    where
+        R: Read,
+    {
+        /// Create a new `NibbleIOReader`, reading from `reader` and keeping borrowed data in
+        /// `scratch`
+        pub fn new(reader: R, scratch: &'de mut [u8]) -> Self {
+            Self {
+                reader,
+                scratch,
+                pending: None,
+            }
+        }
+
+        fn read_byte(&mut self) -> Result<u8> {
+            let mut byte = [0u8];
+            self.reader
+                .read_exact(&mut byte)
+                .map_err(|_| Error::DeserializeUnexpectedEnd)?;
+            Ok(byte[0])
+        }
+    }
+
+    impl<'de, R> NibbleFlavor<'de> for NibbleIOReader<'de, R>
+    where
+        R: Read + 'de,
+    {
+        type Remainder = (R, &'de mut [u8]);
+        type Source = R;
+
+        #[inline]
+        fn try_take_nib(&mut self) -> Result<u8> {
+            match self.pending.take() {
+                Some(low) => Ok(low),
+                None => {
+                    let byte = self.read_byte()?;
+                    self.pending = Some(byte & 0x0f);
+                    Ok(byte >> 4)
+                }
+            }
+        }
+
+        #[inline]
+        fn try_take_u8(&mut self) -> Result<u8> {
+            let byte = self.read_byte()?;
+            match self.pending.take() {
+                Some(low) => {
+                    self.pending = Some(byte & 0x0f);
+                    Ok((low << 4) | (byte >> 4))
+                }
+                None => Ok(byte),
+            }
+        }
+
+        #[inline]
+        fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+            // The rest of a partially read byte is padding
+            self.pending = None;
+            if ct > self.scratch.len() {
+                return Err(Error::DeserializeUnexpectedEnd);
+            }
+            let (data, rest) = core::mem::take(&mut self.scratch).split_at_mut(ct);
+            self.scratch = rest;
+            self.reader
+                .read_exact(data)
+                .map_err(|_| Error::DeserializeUnexpectedEnd)?;
+            Ok(data)
+        }
+
+        /// Returns the reader, positioned after the last byte of the message, and the unused
+        /// part of the scratch buffer
+        fn finalize(self) -> Result<Self::Remainder> {
+            Ok((self.reader, self.scratch))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod lenient {
+    extern crate alloc;
+    use super::{NibbleFlavor, NibbleMark, NibbleSlice};
+    use crate::Result;
+    use alloc::vec::Vec;
+
+    /// A non-fatal issue tolerated by the [`NibbleLenient`] flavor
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Warning {
+        /// A varint was encoded with more digits than needed for its value
+        NonCanonicalVarint,
+        /// More than a single padding nibble was left over after the message
+        TrailingData {
+            /// The number of unused nibbles
+            nibbles: usize,
+        },
+    }
+
+    /// A [`NibbleSlice`] that records non-fatal encoding issues as [`Warning`]s instead of
+    /// ignoring them, to help diagnose sloppy encoders.
+    ///
+    /// The warnings are returned along with the unused bytes when finalizing.
+    pub struct NibbleLenient<'de> {
+        slice: NibbleSlice<'de>,
+        warnings: Vec<Warning>,
+    }
+
+    impl<'de> NibbleLenient<'de> {
+        /// Create a new `NibbleLenient` from the given buffer
+        pub fn new(sli: &'de [u8]) -> Self {
+            Self {
+                slice: NibbleSlice::new(sli),
+                warnings: Vec::new(),
+            }
+        }
+    }
+
+    impl<'de> NibbleFlavor<'de> for NibbleLenient<'de> {
+        type Remainder = (&'de [u8], Vec<Warning>);
+        type Source = &'de [u8];
+
+        #[inline]
+        fn try_take_nib(&mut self) -> Result<u8> {
+            self.slice.try_take_nib()
+        }
+
+        #[inline]
+        fn try_peek_nib(&mut self) -> Result<u8> {
+            self.slice.try_peek_nib()
+        }
+
+        #[inline]
+        fn try_take_u8(&mut self) -> Result<u8> {
+            self.slice.try_take_u8()
+        }
+
+        #[inline]
+        fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+            self.slice.try_take_n(ct)
+        }
+
+        #[inline]
+        fn mark(&self) -> Option<NibbleMark> {
+            self.slice.mark()
+        }
+
+        #[inline]
+        fn reset_to(&mut self, mark: NibbleMark) -> Result<()> {
+            self.slice.reset_to(mark)
+        }
+
+        fn non_canonical_varint(&mut self) -> Result<()> {
+            self.warnings.push(Warning::NonCanonicalVarint);
+            Ok(())
+        }
+
+        fn finalize(mut self) -> Result<Self::Remainder> {
+            let nibbles = self.slice.nibbles_left();
+            if nibbles > 1 {
+                self.warnings.push(Warning::TrailingData { nibbles });
+            }
+            Ok((self.slice.finalize()?, self.warnings))
+        }
+    }
+}
@@ -0,0 +1,702 @@
+//! # Enum Dictionaries
+//!
+//! Every enum value starts with the index of its variant. In a long sequence of
+//! enums that mostly share one variant, e.g. a log of readings with the occasional
+//! error, this index is repeated for every element, and takes more than one nibble
+//! for enums with many variants.
+//!
+//! [`NibbleEnumDict`] writes the distinct variants of the sequence once, up front,
+//! and then every element as its position in that dictionary followed by the
+//! variant contents. If all elements share the same variant, the positions are
+//! left out entirely.
+
+extern crate alloc;
+
+use crate::Error;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, DeserializeSeed, Error as _, IntoDeserializer, SeqAccess, Visitor};
+use serde::ser::{self, Error as _, Impossible, SerializeTuple};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A `Vec` of enums, serialized as a dictionary of the variants used, followed by the elements.
+///
+/// On the wire the dictionary is a sequence of variant indices, in order of first use. It is
+/// followed by a sequence of the elements, each one its position in the dictionary, unless
+/// the dictionary has a single entry, followed by the contents of its variant. Indices and
+/// positions are written as `u16`s, i.e. nibble varints like the enum tags themselves.
+///
+/// Elements have to be enums with at most 65536 variants, anything else fails to serialize.
+///
+/// ```rust
+/// use postcard::enum_dict::NibbleEnumDict;
+/// # use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// enum Reading {
+///     Calibrating,
+///     Ok(u8),
+///     Error,
+/// }
+///
+/// let readings = NibbleEnumDict(vec![Reading::Ok(1), Reading::Ok(2), Reading::Ok(3)]);
+/// let ser = postcard::to_nibble_allocvec(&readings).unwrap();
+/// // One dictionary entry, so the elements are just their contents
+/// assert_eq!(ser.as_slice(), &[0x11, 0x31, 0x23]);
+/// assert_eq!(postcard::from_nibbles::<NibbleEnumDict<Reading>>(&ser).unwrap(), readings);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct NibbleEnumDict<T>(pub Vec<T>);
+
+impl<T> From<Vec<T>> for NibbleEnumDict<T> {
+    fn from(elements: Vec<T>) -> Self {
+        Self(elements)
+    }
+}
+
+impl<T> Serialize for NibbleEnumDict<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut dict = Vec::new();
+        let mut positions = Vec::with_capacity(self.0.len());
+        for element in &self.0 {
+            let variant = element
+                .serialize(VariantIndex)
+                .map_err(|_| S::Error::custom("NibbleEnumDict elements must be enums"))?;
+            let variant = u16::try_from(variant)
+                .map_err(|_| S::Error::custom("NibbleEnumDict variant index out of range"))?;
+            let position = match dict.iter().position(|v| *v == variant) {
+                Some(position) => position,
+                None => {
+                    dict.push(variant);
+                    dict.len() - 1
+                }
+            };
+            positions.push(position as u16);
+        }
+
+        let mut out = serializer.serialize_tuple(2)?;
+        out.serialize_element(&dict)?;
+        out.serialize_element(&Entries {
+            elements: &self.0,
+            positions: (dict.len() > 1).then_some(&positions[..]),
+        })?;
+        out.end()
+    }
+}
+
+/// The elements, each with its position in the dictionary if there is more than one entry
+struct Entries<'a, T> {
+    elements: &'a [T],
+    positions: Option<&'a [u16]>,
+}
+
+impl<'a, T> Serialize for Entries<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.positions {
+            Some(positions) => serializer.collect_seq(
+                positions
+                    .iter()
+                    .zip(self.elements)
+                    .map(|(position, element)| (position, Contents(element))),
+            ),
+            None => serializer.collect_seq(self.elements.iter().map(Contents)),
+        }
+    }
+}
+
+/// An enum value, serialized without its variant index
+struct Contents<'a, T>(&'a T);
+
+impl<'a, T> Serialize for Contents<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(ContentsSerializer(serializer))
+    }
+}
+
+/// Implements the `Serializer` methods for values other than enums, which all fail
+macro_rules! not_an_enum {
+    ($err:expr; $($method:ident($($ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<$ret, Self::Error> {
+                Err($err)
+            }
+        )*
+    };
+}
+
+/// Finds the variant index of an enum value, without serializing its contents
+struct VariantIndex;
+
+impl Serializer for VariantIndex {
+    type Ok = u32;
+    type Error = Error;
+    type SerializeSeq = Impossible<u32, Error>;
+    type SerializeTuple = Impossible<u32, Error>;
+    type SerializeTupleStruct = Impossible<u32, Error>;
+    type SerializeTupleVariant = FoundVariant;
+    type SerializeMap = Impossible<u32, Error>;
+    type SerializeStruct = Impossible<u32, Error>;
+    type SerializeStructVariant = FoundVariant;
+
+    not_an_enum! {
+        Error::WontImplement;
+        serialize_bool(bool) -> u32;
+        serialize_i8(i8) -> u32;
+        serialize_i16(i16) -> u32;
+        serialize_i32(i32) -> u32;
+        serialize_i64(i64) -> u32;
+        serialize_u8(u8) -> u32;
+        serialize_u16(u16) -> u32;
+        serialize_u32(u32) -> u32;
+        serialize_u64(u64) -> u32;
+        serialize_f32(f32) -> u32;
+        serialize_f64(f64) -> u32;
+        serialize_char(char) -> u32;
+        serialize_str(&str) -> u32;
+        serialize_bytes(&[u8]) -> u32;
+        serialize_none() -> u32;
+        serialize_unit() -> u32;
+        serialize_unit_struct(&'static str) -> u32;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<u32, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(Error::WontImplement)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<u32, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(Error::WontImplement)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<u32, Error> {
+        Ok(variant_index)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u32, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(variant_index)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<FoundVariant, Error> {
+        Ok(FoundVariant(variant_index))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<FoundVariant, Error> {
+        Ok(FoundVariant(variant_index))
+    }
+}
+
+/// Skips the fields of a tuple or struct variant
+struct FoundVariant(u32);
+
+impl ser::SerializeTupleVariant for FoundVariant {
+    type Ok = u32;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<u32, Error> {
+        Ok(self.0)
+    }
+}
+
+impl ser::SerializeStructVariant for FoundVariant {
+    type Ok = u32;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<u32, Error> {
+        Ok(self.0)
+    }
+}
+
+/// Serializes the contents of an enum variant like a value of the same shape
+struct ContentsSerializer<S>(S);
+
+impl<S> Serializer for ContentsSerializer<S>
+where
+    S: Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Impossible<S::Ok, S::Error>;
+    type SerializeTuple = Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+    type SerializeTupleVariant = TupleContents<S::SerializeTuple>;
+    type SerializeMap = Impossible<S::Ok, S::Error>;
+    type SerializeStruct = Impossible<S::Ok, S::Error>;
+    type SerializeStructVariant = StructContents<S::SerializeStruct>;
+
+    not_an_enum! {
+        S::Error::custom("NibbleEnumDict elements must be enums");
+        serialize_bool(bool) -> S::Ok;
+        serialize_i8(i8) -> S::Ok;
+        serialize_i16(i16) -> S::Ok;
+        serialize_i32(i32) -> S::Ok;
+        serialize_i64(i64) -> S::Ok;
+        serialize_u8(u8) -> S::Ok;
+        serialize_u16(u16) -> S::Ok;
+        serialize_u32(u32) -> S::Ok;
+        serialize_u64(u64) -> S::Ok;
+        serialize_f32(f32) -> S::Ok;
+        serialize_f64(f64) -> S::Ok;
+        serialize_char(char) -> S::Ok;
+        serialize_str(&str) -> S::Ok;
+        serialize_bytes(&[u8]) -> S::Ok;
+        serialize_none() -> S::Ok;
+        serialize_unit() -> S::Ok;
+        serialize_unit_struct(&'static str) -> S::Ok;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(S::Error::custom("NibbleEnumDict elements must be enums"))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(S::Error::custom("NibbleEnumDict elements must be enums"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self.0)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.0.serialize_tuple(len).map(TupleContents)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.0.serialize_struct(name, len).map(StructContents)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// The fields of a tuple variant, serialized as a tuple
+struct TupleContents<T>(T);
+
+impl<T> ser::SerializeTupleVariant for TupleContents<T>
+where
+    T: ser::SerializeTuple,
+{
+    type Ok = T::Ok;
+    type Error = T::Error;
+
+    fn serialize_field<V>(&mut self, value: &V) -> Result<(), T::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        self.0.serialize_element(value)
+    }
+
+    fn end(self) -> Result<T::Ok, T::Error> {
+        self.0.end()
+    }
+}
+
+/// The fields of a struct variant, serialized as a struct
+struct StructContents<T>(T);
+
+impl<T> ser::SerializeStructVariant for StructContents<T>
+where
+    T: ser::SerializeStruct,
+{
+    type Ok = T::Ok;
+    type Error = T::Error;
+
+    fn serialize_field<V>(&mut self, key: &'static str, value: &V) -> Result<(), T::Error>
+    where
+        V: Serialize + ?Sized,
+    {
+        self.0.serialize_field(key, value)
+    }
+
+    fn end(self) -> Result<T::Ok, T::Error> {
+        self.0.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for NibbleEnumDict<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, DictVisitor(PhantomData))
+    }
+}
+
+struct DictVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T> Visitor<'de> for DictVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = NibbleEnumDict<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a variant dictionary followed by the elements")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let dict: Vec<u16> = seq
+            .next_element()?
+            .ok_or_else(|| S::Error::invalid_length(0, &self))?;
+        let elements = seq
+            .next_element_seed(EntriesSeed {
+                dict: &dict,
+                _t: PhantomData,
+            })?
+            .ok_or_else(|| S::Error::invalid_length(1, &self))?;
+        Ok(NibbleEnumDict(elements))
+    }
+}
+
+/// Decodes the sequence of elements, looking up their variants in `dict`
+struct EntriesSeed<'a, T> {
+    dict: &'a [u16],
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<'a, 'de, T> DeserializeSeed<'de> for EntriesSeed<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Vec<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de, T> Visitor<'de> for EntriesSeed<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of enums")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        // The count comes from the input, so don't trust it for the allocation
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        match self.dict {
+            [variant] => {
+                let contents = || ContentsSeed {
+                    variant: (*variant).into(),
+                    _t: PhantomData,
+                };
+                while let Some(element) = seq.next_element_seed(contents())? {
+                    elements.push(element);
+                }
+            }
+            // Without any variants there are no elements, and reading a position fails
+            dict => {
+                let entry = || EntrySeed {
+                    dict,
+                    _t: PhantomData,
+                };
+                while let Some(element) = seq.next_element_seed(entry())? {
+                    elements.push(element);
+                }
+            }
+        }
+        Ok(elements)
+    }
+}
+
+/// Decodes the position of an element in the dictionary, then its contents
+struct EntrySeed<'a, T> {
+    dict: &'a [u16],
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<'a, 'de, T> DeserializeSeed<'de> for EntrySeed<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+impl<'a, 'de, T> Visitor<'de> for EntrySeed<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a position below {}", self.dict.len())
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let position: u16 = seq
+            .next_element()?
+            .ok_or_else(|| S::Error::invalid_length(0, &self))?;
+        let variant = *self.dict.get(usize::from(position)).ok_or_else(|| {
+            S::Error::invalid_value(de::Unexpected::Unsigned(position.into()), &self)
+        })?;
+        seq.next_element_seed(ContentsSeed {
+            variant: variant.into(),
+            _t: PhantomData,
+        })?
+        .ok_or_else(|| S::Error::invalid_length(1, &self))
+    }
+}
+
+/// Decodes the contents of an enum whose variant is already known
+struct ContentsSeed<T> {
+    variant: u32,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> DeserializeSeed<'de> for ContentsSeed<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(ContentsDeserializer {
+            de: deserializer,
+            variant: self.variant,
+        })
+    }
+}
+
+/// Hands out the known variant, and decodes its contents like a value of the same shape
+struct ContentsDeserializer<D> {
+    de: D,
+    variant: u32,
+}
+
+impl<'de, D> Deserializer<'de> for ContentsDeserializer<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(D::Error::custom("NibbleEnumDict elements must be enums"))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(ContentsAccess {
+            de: self.de,
+            variant: self.variant,
+            name,
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct ContentsAccess<D> {
+    de: D,
+    variant: u32,
+    name: &'static str,
+}
+
+impl<'de, D> de::EnumAccess<'de> for ContentsAccess<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), D::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, D> de::VariantAccess<'de> for ContentsAccess<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn unit_variant(self) -> Result<(), D::Error> {
+        <()>::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, D::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_struct(self.name, fields, visitor)
+    }
+}
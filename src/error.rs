@@ -1,4 +1,9 @@
-use core::fmt::{Display, Formatter};
+use core::convert::TryFrom;
+use core::fmt::{self, Display, Formatter, Write as _};
+use serde::de::{Expected, Unexpected};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 /// This is the error type used by Postcard
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -13,6 +18,8 @@ pub enum Error {
     SerializeBufferFull,
     /// The length of a sequence must be known
     SerializeSeqLengthUnknown,
+    /// The nibble budget of this serialization step is used up, continue with the next step
+    SerializeBudgetExhausted,
     /// Hit the end of buffer, expected more data
     DeserializeUnexpectedEnd,
     /// Found a varint that didn't terminate. Is the usize too big for this platform?
@@ -25,12 +32,41 @@ pub enum Error {
     DeserializeBadUtf8,
     /// Found an Option discriminant that wasn't 0 or 1
     DeserializeBadOption,
-    /// Found an enum discriminant that was > u32::max_value()
-    DeserializeBadEnum,
+    /// Found an enum discriminant that doesn't belong to any of the enum's variants
+    DeserializeBadEnum {
+        /// The decoded discriminant
+        index: u32,
+        /// The number of variants the enum has
+        count: usize,
+    },
     /// The original data was not well encoded
     DeserializeBadEncoding,
     /// vlu32n number contained more nibbles than maximum
     DeserializeBadVlu32N,
+    /// vlu64n number contained more nibbles or bits than maximum
+    DeserializeBadVlu64N,
+    /// vlu128n number contained more nibbles or bits than maximum
+    DeserializeBadVlu128N,
+    /// The message didn't use exactly the length declared in front of it
+    DeserializeLengthMismatch,
+    /// Found a parity protected nibble with more bit errors than can be corrected
+    DeserializeParityError,
+    /// A struct field was tagged with a different type than the one being deserialized
+    DeserializeFieldTypeMismatch,
+    /// The input continued after the message, beyond a zero padding nibble
+    DeserializeUnexpectedLength,
+    /// Found a character other than a hex digit in a hex string
+    DeserializeBadHex,
+    /// Decoding the message took more steps than allowed
+    DeserializeStepLimit,
+    /// The message nests sequences, maps, structs or enums deeper than allowed
+    DeserializeMaxDepthExceeded,
+    /// A checksum or CRC in the message doesn't match the data it covers
+    DeserializeBadChecksum,
+    /// The message has fewer struct fields than the type being deserialized
+    DeserializeMissingFields,
+    /// The message was written with another version of the nibble wire format
+    DeserializeWireVersionMismatch,
     /// Serde Serialization Error
     SerdeSerCustom,
     /// Serde Deserialization Error
@@ -54,6 +90,9 @@ impl Display for Error {
                 }
                 SerializeBufferFull => "The serialize buffer is full",
                 SerializeSeqLengthUnknown => "The length of a sequence must be known",
+                SerializeBudgetExhausted => {
+                    "The nibble budget of this serialization step is used up, continue with the next step"
+                }
                 DeserializeUnexpectedEnd => "Hit the end of buffer, expected more data",
                 DeserializeBadVarint => {
                     "Found a varint that didn't terminate. Is the usize too big for this platform?"
@@ -62,9 +101,43 @@ impl Display for Error {
                 DeserializeBadChar => "Found an invalid unicode char",
                 DeserializeBadUtf8 => "Tried to parse invalid utf-8",
                 DeserializeBadOption => "Found an Option discriminant that wasn't 0 or 1",
-                DeserializeBadEnum => "Found an enum discriminant that was > u32::max_value()",
+                DeserializeBadEnum { index, count } => {
+                    return write!(
+                        f,
+                        "Found enum discriminant {}, but the enum only has {} variants",
+                        index, count
+                    );
+                }
                 DeserializeBadEncoding => "The original data was not well encoded",
                 DeserializeBadVlu32N => "Tried to decode malformed vlu32n number",
+                DeserializeBadVlu64N => "Tried to decode malformed vlu64n number",
+                DeserializeBadVlu128N => "Tried to decode malformed vlu128n number",
+                DeserializeLengthMismatch => {
+                    "The message didn't use exactly the length declared in front of it"
+                }
+                DeserializeParityError => {
+                    "Found a parity protected nibble with more bit errors than can be corrected"
+                }
+                DeserializeFieldTypeMismatch => {
+                    "A struct field was tagged with a different type than the one being deserialized"
+                }
+                DeserializeUnexpectedLength => {
+                    "The input continued after the message, beyond a zero padding nibble"
+                }
+                DeserializeBadHex => "Found a character other than a hex digit in a hex string",
+                DeserializeStepLimit => "Decoding the message took more steps than allowed",
+                DeserializeMaxDepthExceeded => {
+                    "The message nests sequences, maps, structs or enums deeper than allowed"
+                }
+                DeserializeBadChecksum => {
+                    "A checksum or CRC in the message doesn't match the data it covers"
+                }
+                DeserializeMissingFields => {
+                    "The message has fewer struct fields than the type being deserialized"
+                }
+                DeserializeWireVersionMismatch => {
+                    "The message was written with another version of the nibble wire format"
+                }
                 SerdeSerCustom => "Serde Serialization Error",
                 SerdeDeCustom => "Serde Deserialization Error",
                 CollectStrError => "Error while processing `collect_str` during serialization",
@@ -93,6 +166,81 @@ impl serde::de::Error for Error {
     {
         Error::SerdeDeCustom
     }
+
+    /// The nibble deserializer checks enum discriminants itself, but a variant index can also
+    /// reach a derived enum from elsewhere, e.g. from the dictionary of a `NibbleEnumDict`.
+    /// The derived code then reports it as an invalid value, which is recognized here.
+    fn invalid_value(unexp: Unexpected, exp: &dyn Expected) -> Self {
+        match (unexp, variant_count(exp)) {
+            (Unexpected::Unsigned(index), Some(count)) => match u32::try_from(index) {
+                Ok(index) => Error::DeserializeBadEnum { index, count },
+                Err(_) => Error::SerdeDeCustom,
+            },
+            _ => Error::SerdeDeCustom,
+        }
+    }
+}
+
+/// The number of variants of an enum, if `exp` is what `serde_derive` expects of a variant
+/// index: "variant index 0 <= i < {count}"
+fn variant_count(exp: &dyn Expected) -> Option<usize> {
+    /// Just large enough for the message of an enum with `usize::MAX` variants
+    struct Text {
+        buf: [u8; 48],
+        len: usize,
+    }
+
+    impl fmt::Write for Text {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.buf
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut text = Text {
+        buf: [0; 48],
+        len: 0,
+    };
+    write!(text, "{}", exp).ok()?;
+    core::str::from_utf8(&text.buf[..text.len])
+        .ok()?
+        .strip_prefix("variant index 0 <= i < ")?
+        .parse()
+        .ok()
 }
 
 impl serde::ser::StdError for Error {}
+
+/// An [`Error`] along with the path to the value that failed to decode, as returned by
+/// [`from_nibbles_with_path()`](crate::from_nibbles_with_path).
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NibblePathError {
+    /// The error itself
+    pub error: Error,
+    /// The path to the failing value, such as `outer.items[3].value`. Struct fields are
+    /// separated by dots, elements of sequences, tuples and maps are given by their position
+    /// in brackets, and enum variants follow their enum after `::`. Empty if the message
+    /// failed as a whole.
+    pub path: alloc::string::String,
+}
+
+#[cfg(feature = "alloc")]
+impl Display for NibblePathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.error)
+        } else {
+            write!(f, "{} at `{}`", self.error, self.path)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl serde::ser::StdError for NibblePathError {}
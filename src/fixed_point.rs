@@ -0,0 +1,131 @@
+//! # Fixed Point Numbers
+//!
+//! Signal processing code often stores samples as Q-format fixed point numbers,
+//! where an integer represents a fraction in the range `[-1, 1)`. The wrappers in
+//! this module keep the raw integer on the wire at a fixed width, as its little
+//! endian bytes in the same aligned byte run as [`byte_array`](crate::byte_array),
+//! and provide conversions from and to `f32`.
+//!
+//! The run has a one nibble length in front, so a Q15 always takes 3 bytes and a
+//! Q31 5 bytes, whatever their value.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Round half away from zero, `f64::round` is not available in `core`
+fn round(v: f64) -> f64 {
+    if v >= 0.0 {
+        v + 0.5
+    } else {
+        v - 0.5
+    }
+}
+
+/// A Q15 fixed point number: 1 sign bit and 15 fractional bits, stored as an `i16`.
+///
+/// ```rust
+/// use postcard::fixed_point::NibbleQ15;
+///
+/// let q = NibbleQ15::from_f32(0.5);
+/// assert_eq!(q, NibbleQ15(0x4000));
+/// assert_eq!(q.to_f32(), 0.5);
+///
+/// let mut buf = [0u8; 4];
+/// assert_eq!(postcard::to_nibble_slice(&q, &mut buf).unwrap(), &[0x20, 0x00, 0x40]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct NibbleQ15(pub i16);
+
+/// A Q31 fixed point number: 1 sign bit and 31 fractional bits, stored as an `i32`.
+///
+/// ```rust
+/// use postcard::fixed_point::NibbleQ31;
+///
+/// let q = NibbleQ31::from_f32(-0.25);
+/// assert_eq!(q, NibbleQ31(-0x2000_0000));
+/// assert_eq!(q.to_f32(), -0.25);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct NibbleQ31(pub i32);
+
+macro_rules! impl_q {
+    ($($ty:ident: $int:ty, $frac_bits:literal;)*) => {
+        $(
+            impl $ty {
+                const SCALE: f64 = (1u64 << $frac_bits) as f64;
+
+                /// Convert from a float, rounding to the nearest representable value.
+                ///
+                /// Values outside of `[-1, 1)` saturate, and NaN converts to zero.
+                pub fn from_f32(v: f32) -> Self {
+                    // Float to int `as` casts saturate, and map NaN to 0
+                    Self(round(v as f64 * Self::SCALE) as $int)
+                }
+
+                /// Convert to a float
+                pub fn to_f32(self) -> f32 {
+                    (self.0 as f64 / Self::SCALE) as f32
+                }
+            }
+
+            impl Serialize for $ty {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    crate::byte_array::serialize(&self.0.to_le_bytes(), serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    crate::byte_array::deserialize(deserializer)
+                        .map(<$int>::from_le_bytes)
+                        .map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl_q! {
+    NibbleQ15: i16, 15;
+    NibbleQ31: i32, 31;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NibbleQ15, NibbleQ31};
+
+    const SAMPLES: [f32; 9] = [0.0, 0.5, -0.5, 0.1, -0.1, 1e-3, -1e-4, 0.999, -0.999];
+
+    #[test]
+    fn q15_accuracy() {
+        for v in SAMPLES {
+            let err = (NibbleQ15::from_f32(v).to_f32() - v).abs();
+            // Within half a step
+            assert!(err <= 0.5 / 32768.0, "{} off by {}", v, err);
+        }
+        assert_eq!(NibbleQ15::from_f32(-1.0), NibbleQ15(i16::MIN));
+        assert_eq!(NibbleQ15::from_f32(1.0), NibbleQ15(i16::MAX));
+        assert_eq!(NibbleQ15::from_f32(-7.5), NibbleQ15(i16::MIN));
+        assert_eq!(NibbleQ15::from_f32(f32::NAN), NibbleQ15(0));
+        assert_eq!(NibbleQ15(i16::MIN).to_f32(), -1.0);
+    }
+
+    #[test]
+    fn q31_accuracy() {
+        for v in SAMPLES {
+            let err = (NibbleQ31::from_f32(v).to_f32() - v).abs();
+            // Within half a step, or the f32 mantissa for larger values
+            let tolerance = (v.abs() * f32::EPSILON).max(0.5 / 2147483648.0);
+            assert!(err <= tolerance, "{} off by {}", v, err);
+        }
+        assert_eq!(NibbleQ31::from_f32(-1.0), NibbleQ31(i32::MIN));
+        assert_eq!(NibbleQ31::from_f32(1.0), NibbleQ31(i32::MAX));
+        assert_eq!(NibbleQ31::from_f32(f32::INFINITY), NibbleQ31(i32::MAX));
+        assert_eq!(NibbleQ31(1).to_f32(), 1.0 / 2147483648.0);
+    }
+}
@@ -4,10 +4,36 @@
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 
 pub mod accumulator;
+pub mod byte_array;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod byte_cow;
+pub mod byte_escape;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod byte_vec;
 mod de;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod enum_dict;
 mod error;
+pub mod fixed_point;
 pub mod fixint;
+pub mod max_nibble_size;
+pub mod nibble_decode;
+pub mod nibble_ordering;
+pub mod nibble_time;
+#[cfg(feature = "uuid")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "uuid")))]
+pub mod nibble_uuid;
 mod ser;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod soa_map;
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod type_descriptor;
+pub mod untagged;
 mod varint;
 
 // Still experimental! Don't make pub pub.
@@ -79,23 +105,81 @@ pub mod experimental {
     }
 }
 
+/// Version of the nibble wire format produced by this crate.
+///
+/// This is bumped whenever the nibble encoding changes in an incompatible way
+/// (varint scheme, enum tags, alignment rules, etc.), so that peers can detect
+/// a mismatch before trying to decode each other's messages.
+///
+/// The `NibbleTyped` flavors write it in the header in front of every message, and reject
+/// messages of another version. Other messages carry no version, applications can embed it
+/// themselves where they need it, such as in a handshake or in front of stored data:
+///
+/// ```rust
+/// use postcard::{from_nibbles, to_nibble_slice, NIBBLE_WIRE_VERSION};
+///
+/// let mut buf = [0u8; 8];
+/// let stored = to_nibble_slice(&(NIBBLE_WIRE_VERSION, 7u16), &mut buf).unwrap();
+///
+/// let (version, _): (u8, ()) = from_nibbles(stored).unwrap();
+/// if version == NIBBLE_WIRE_VERSION {
+///     let (_, value): (u8, u16) = from_nibbles(stored).unwrap();
+///     assert_eq!(value, 7);
+/// }
+/// ```
+pub const NIBBLE_WIRE_VERSION: u8 = 3;
+
+/// How deeply sequences, maps, structs, enums, `Option`s and newtype structs may be nested in a
+/// nibble message.
+///
+/// Deserializing a deeper message fails with [`Error::DeserializeMaxDepthExceeded`] instead
+/// of exhausting the stack. [`from_nibbles_with_depth()`] uses a different limit.
+pub const NIBBLE_DEFAULT_MAX_DEPTH: usize = 64;
+
 pub use de::deserializer::Deserializer;
 pub use de::flavors as de_flavors;
-pub use de::{from_bytes, from_bytes_cobs, from_nibbles, take_from_bytes, take_from_bytes_cobs};
+pub use de::nibble_flavors as de_nibble_flavors;
+pub use de::{
+    fold_nibble_map, from_bytes, from_bytes_cobs, from_nibbles, from_nibbles_cobs,
+    from_nibbles_field_types, from_nibbles_in_place, from_nibbles_limited, from_nibbles_located,
+    from_nibbles_measured, from_nibbles_min_size_front, from_nibbles_named_variants,
+    from_nibbles_packed, from_nibbles_prefix_versioned, from_nibbles_separated, from_nibbles_seq_n,
+    from_nibbles_strict, from_nibbles_with_depth, from_nibbles_with_flavor, from_nibbles_with_tags,
+    from_nibbles_with_trailer, from_nibbles_with_variant, from_nibbles_xor, nibble_field_offset,
+    peek_nibble_discriminant, read_nibble_frame, take_from_bytes, take_from_bytes_cobs,
+    take_from_nibbles, with_nibbles, NibbleSeqReader,
+};
+#[cfg(feature = "alloc")]
+pub use error::NibblePathError;
 pub use error::{Error, Result};
 pub use ser::flavors as ser_flavors;
-pub use ser::{serialize_with_flavor, serializer::Serializer, to_slice, to_slice_cobs};
+pub use ser::nibble_flavors as ser_nibble_flavors;
+pub use ser::{
+    nibble_size, nibble_size_bounds, nibble_size_bytes, serialize_with_flavor,
+    serialize_with_nibble_flavor, serializer::Serializer, to_nibble_iter, to_nibble_slice,
+    to_nibble_slice_reserve, to_slice, to_slice_cobs, transcode, NibbleIter,
+};
 
 #[cfg(feature = "heapless")]
-pub use ser::to_nibble_vec;
+pub use ser::{
+    to_nibble_vec, to_nibble_vec_canonical, to_nibble_vec_cobs, to_nibble_vec_field_types,
+    to_nibble_vec_named_variants, to_nibble_vec_packed, to_nibble_vec_prefix_versioned,
+    to_nibble_vec_separated, to_nibble_vec_with_tags, to_nibble_vec_xor,
+};
 #[cfg(feature = "heapless")]
 pub use ser::{to_vec, to_vec_cobs};
 
 #[cfg(feature = "use-std")]
-pub use ser::{to_stdvec, to_stdvec_cobs};
+pub use ser::{to_nibble_stdvec, to_stdvec, to_stdvec_cobs};
 
 #[cfg(feature = "alloc")]
-pub use ser::{to_allocvec, to_allocvec_cobs};
+pub use ser::{
+    to_allocvec, to_allocvec_cobs, to_nibble_allocvec, to_nibble_cow, to_nibble_frame,
+    to_nibble_hex, transcode_from_byte_postcard,
+};
+
+#[cfg(feature = "alloc")]
+pub use de::{frame_ranges, from_nibble_hex, from_nibbles_lenient, from_nibbles_with_path};
 
 #[cfg(feature = "use-crc")]
 pub use {
@@ -104,7 +188,7 @@ pub use {
 };
 
 #[cfg(all(feature = "use-crc", feature = "heapless"))]
-pub use ser::to_vec_crc32;
+pub use ser::{to_nibble_vec_with_crc, to_vec_crc32};
 
 #[cfg(all(feature = "use-crc", feature = "use-std"))]
 pub use ser::to_stdvec_crc32;
@@ -114,6 +198,13 @@ pub use ser::to_allocvec_crc32;
 
 #[cfg(test)]
 mod test {
+    #[test]
+    fn nibble_wire_version() {
+        // Changing this means old and new peers can no longer talk to each other,
+        // update it together with the encoding and the loopback tests.
+        assert_eq!(crate::NIBBLE_WIRE_VERSION, 3);
+    }
+
     #[test]
     fn varint_boundary_canon() {
         let x = u32::MAX;
@@ -0,0 +1,257 @@
+//! # Maximum Nibble Sizes
+//!
+//! Buffers for [`to_nibble_slice`](crate::to_nibble_slice) often live on the stack,
+//! so their size has to be known at compile time. [`MaxNibbleSize`] provides an
+//! upper bound for the nibble serialization of a type, the nibble counterpart of
+//! the byte based `MaxSize`.
+//!
+//! The bounds assume the plain nibble format, without any modifier flavor.
+
+use core::marker::PhantomData;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+
+use crate::vlu32n::Vlu32N;
+
+/// An upper bound for the size of the nibble serialization of a type.
+///
+/// Integers are bounded by the nibble varint width of their type, 3 bits per nibble, which
+/// also covers the types written as byte varints. Byte runs such as floats and strings count
+/// a padding nibble for their alignment.
+///
+/// ```rust
+/// use postcard::max_nibble_size::MaxNibbleSize;
+///
+/// type Msg = (bool, u32, Option<f32>);
+/// assert_eq!(Msg::MAX_NIBBLES, 1 + 11 + 1 + 9);
+///
+/// let mut buf = [0u8; Msg::MAX_NIBBLE_BYTES];
+/// let msg: Msg = (true, u32::MAX, Some(1.5));
+/// assert!(postcard::to_nibble_slice(&msg, &mut buf).is_ok());
+/// ```
+pub trait MaxNibbleSize {
+    /// The largest number of nibbles the serialization of this type can take
+    const MAX_NIBBLES: usize;
+
+    /// The largest number of bytes the serialization of this type can take, including the
+    /// padding of a partially used last byte
+    const MAX_NIBBLE_BYTES: usize = Self::MAX_NIBBLES.div_ceil(2);
+}
+
+/// The width of a nibble varint holding `bits` bits
+const fn int_nibbles(bits: u32) -> usize {
+    (bits as usize).div_ceil(3)
+}
+
+/// The width of a nibble varint length prefix for lengths up to `max_n`
+const fn len_nibbles(max_n: usize) -> usize {
+    if max_n > u32::MAX as usize {
+        // Not serializable anyway, the length prefix is at most a full `Vlu32N`
+        return Vlu32N(u32::MAX).nibble_len();
+    }
+    Vlu32N(max_n as u32).nibble_len()
+}
+
+const fn max(lhs: usize, rhs: usize) -> usize {
+    if lhs > rhs {
+        lhs
+    } else {
+        rhs
+    }
+}
+
+macro_rules! impl_ints {
+    ($($ty:ty: $bits:expr;)*) => {
+        $(
+            impl MaxNibbleSize for $ty {
+                const MAX_NIBBLES: usize = int_nibbles($bits);
+            }
+        )*
+    };
+}
+
+impl_ints! {
+    u8: u8::BITS;
+    u16: u16::BITS;
+    u32: u32::BITS;
+    u64: u64::BITS;
+    u128: u128::BITS;
+    i8: i8::BITS;
+    i16: i16::BITS;
+    i32: i32::BITS;
+    i64: i64::BITS;
+    i128: i128::BITS;
+    // Serialized as 64-bit integers, regardless of the platform
+    usize: u64::BITS;
+    isize: i64::BITS;
+}
+
+macro_rules! impl_non_zero {
+    ($($ty:ty: $int:ty;)*) => {
+        $(
+            impl MaxNibbleSize for $ty {
+                const MAX_NIBBLES: usize = <$int>::MAX_NIBBLES;
+            }
+        )*
+    };
+}
+
+impl_non_zero! {
+    NonZeroU8: u8;
+    NonZeroU16: u16;
+    NonZeroU32: u32;
+    NonZeroU64: u64;
+    NonZeroU128: u128;
+    NonZeroUsize: usize;
+    NonZeroI8: i8;
+    NonZeroI16: i16;
+    NonZeroI32: i32;
+    NonZeroI64: i64;
+    NonZeroI128: i128;
+    NonZeroIsize: isize;
+}
+
+impl MaxNibbleSize for bool {
+    const MAX_NIBBLES: usize = 1;
+}
+
+impl MaxNibbleSize for f32 {
+    const MAX_NIBBLES: usize = 1 + 2 * 4;
+}
+
+impl MaxNibbleSize for f64 {
+    const MAX_NIBBLES: usize = 1 + 2 * 8;
+}
+
+impl MaxNibbleSize for char {
+    // The length, then up to four aligned UTF-8 bytes
+    const MAX_NIBBLES: usize = len_nibbles(4) + 1 + 2 * 4;
+}
+
+impl MaxNibbleSize for () {
+    const MAX_NIBBLES: usize = 0;
+}
+
+impl<T> MaxNibbleSize for PhantomData<T> {
+    const MAX_NIBBLES: usize = 0;
+}
+
+impl<T: MaxNibbleSize> MaxNibbleSize for Option<T> {
+    const MAX_NIBBLES: usize = 1 + T::MAX_NIBBLES;
+}
+
+impl<T: MaxNibbleSize, E: MaxNibbleSize> MaxNibbleSize for Result<T, E> {
+    const MAX_NIBBLES: usize = 1 + max(T::MAX_NIBBLES, E::MAX_NIBBLES);
+}
+
+impl<T: MaxNibbleSize, const N: usize> MaxNibbleSize for [T; N] {
+    const MAX_NIBBLES: usize = T::MAX_NIBBLES * N;
+}
+
+impl<T: MaxNibbleSize> MaxNibbleSize for &'_ T {
+    const MAX_NIBBLES: usize = T::MAX_NIBBLES;
+}
+
+impl<T: MaxNibbleSize> MaxNibbleSize for &'_ mut T {
+    const MAX_NIBBLES: usize = T::MAX_NIBBLES;
+}
+
+macro_rules! impl_tuples {
+    ($(($($name:ident),+);)*) => {
+        $(
+            impl<$($name: MaxNibbleSize),+> MaxNibbleSize for ($($name,)+) {
+                const MAX_NIBBLES: usize = 0 $(+ $name::MAX_NIBBLES)+;
+            }
+        )*
+    };
+}
+
+impl_tuples! {
+    (A);
+    (A, B);
+    (A, B, C);
+    (A, B, C, D);
+    (A, B, C, D, E);
+    (A, B, C, D, E, F);
+    (A, B, C, D, E, F, G);
+    (A, B, C, D, E, F, G, H);
+}
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+impl<T: MaxNibbleSize, const N: usize> MaxNibbleSize for heapless::Vec<T, N> {
+    const MAX_NIBBLES: usize = len_nibbles(N) + <[T; N]>::MAX_NIBBLES;
+}
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+impl<const N: usize> MaxNibbleSize for heapless::String<N> {
+    // The length, then the aligned bytes
+    const MAX_NIBBLES: usize = len_nibbles(N) + 1 + 2 * N;
+}
+
+/// A rough estimate of the typical nibble serialization size of a type, for capacity planning.
+///
+/// Unlike [`MaxNibbleSize`], which bounds every value, and [`nibble_size()`](crate::nibble_size),
+/// which measures one, the estimate scales the upper bound by an assumed fill ratio: the share of
+/// the bound a representative value uses, with varints only as wide as their values and
+/// sequences partially filled. The estimate is only as good as that assumption, so it should
+/// not be used to size buffers a value must fit into.
+///
+/// ```rust
+/// use postcard::max_nibble_size::{MaxNibbleSize, NibbleSizeEstimate};
+///
+/// type Msg = (u32, [u16; 4]);
+/// assert_eq!(Msg::MAX_NIBBLES, 35);
+///
+/// // Values typically use about 40% of the bound
+/// let estimate = NibbleSizeEstimate::new(40);
+/// assert_eq!(estimate.nibbles::<Msg>(), 14);
+/// assert_eq!(estimate.bytes::<Msg>(), 7);
+/// // Storage for a queue of 100 messages
+/// assert_eq!(estimate.bytes_for::<Msg>(100), 700);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NibbleSizeEstimate {
+    fill_percent: u8,
+}
+
+impl NibbleSizeEstimate {
+    /// Create an estimate assuming values use `fill_percent` of their [`MaxNibbleSize`] bound.
+    /// Ratios above 100% are treated as 100%.
+    pub const fn new(fill_percent: u8) -> Self {
+        Self {
+            fill_percent: if fill_percent > 100 {
+                100
+            } else {
+                fill_percent
+            },
+        }
+    }
+
+    /// The assumed fill ratio in percent
+    pub const fn fill_percent(&self) -> u8 {
+        self.fill_percent
+    }
+
+    /// The estimated number of nibbles a typical value of `T` takes, rounded up
+    pub const fn nibbles<T: MaxNibbleSize>(&self) -> usize {
+        T::MAX_NIBBLES
+            .saturating_mul(self.fill_percent as usize)
+            .div_ceil(100)
+    }
+
+    /// The estimated number of bytes a typical value of `T` takes, including the padding of a
+    /// partially used last byte
+    pub const fn bytes<T: MaxNibbleSize>(&self) -> usize {
+        self.nibbles::<T>().div_ceil(2)
+    }
+
+    /// The estimated number of bytes to store `count` typical values of `T`, each serialized
+    /// into a message of its own
+    pub const fn bytes_for<T: MaxNibbleSize>(&self, count: usize) -> usize {
+        self.bytes::<T>().saturating_mul(count)
+    }
+}
@@ -0,0 +1,221 @@
+//! # Custom Nibble Decoding
+//!
+//! Some domain types have a wire layout that doesn't map well onto serde's
+//! data model, for example a value packed into the bits of a single varint.
+//! The [`NibbleDecode`] trait lets such a type read the nibble stream directly,
+//! bypassing serde for that one value, while the rest of the message is still
+//! decoded as usual with `from_nibbles` and friends.
+//!
+//! A type opts in by implementing `Deserialize` through [`deserialize`], or by
+//! being wrapped in [`NibbleDecoded`].
+//!
+//! ```rust
+//! use postcard::de_nibble_flavors::NibbleFlavor;
+//! use postcard::nibble_decode::NibbleDecode;
+//! use serde::{Deserialize, Deserializer};
+//!
+//! /// A port number, sent as two nibbles: bank and pin
+//! #[derive(Debug, PartialEq)]
+//! struct Pin(u8, u8);
+//!
+//! impl NibbleDecode for Pin {
+//!     fn decode<'de, F: NibbleFlavor<'de>>(flavor: &mut F) -> postcard::Result<Self> {
+//!         Ok(Pin(flavor.try_take_nib()?, flavor.try_take_nib()?))
+//!     }
+//! }
+//!
+//! impl<'de> Deserialize<'de> for Pin {
+//!     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+//!         postcard::nibble_decode::deserialize(deserializer)
+//!     }
+//! }
+//!
+//! let pins: (Pin, Pin) = postcard::from_nibbles(&[0x2A, 0x31]).unwrap();
+//! assert_eq!(pins, (Pin(2, 10), Pin(3, 1)));
+//! ```
+//!
+//! Only the nibble deserializer supports this, other deserializers report an
+//! error. Errors returned by [`NibbleDecode::decode`] are passed on unchanged
+//! when they come from the flavor, others are reported as
+//! [`Error::SerdeDeCustom`].
+
+use crate::de::nibble_flavors::NibbleFlavor;
+use crate::{Error, Result};
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{DeserializeSeed, Error as _, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+/// The name the nibble deserializer recognizes as a request for raw access
+pub(crate) const DECODE_NAME: &str = "postcard::NibbleDecode";
+
+/// A type that decodes itself directly from the nibble stream.
+pub trait NibbleDecode: Sized {
+    /// Decode a value, reading exactly what the matching serializer wrote.
+    fn decode<'de, F: NibbleFlavor<'de>>(flavor: &mut F) -> Result<Self>;
+}
+
+/// Deserialize a `T` with its [`NibbleDecode`] implementation, for use in a `Deserialize`
+/// impl or with `#[serde(deserialize_with = ...)]`.
+pub fn deserialize<'de, D, T>(deserializer: D) -> core::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: NibbleDecode,
+{
+    deserializer.deserialize_newtype_struct(DECODE_NAME, DecodeVisitor(PhantomData))
+}
+
+/// Wrapper that deserializes the inner value with its [`NibbleDecode`] implementation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct NibbleDecoded<T>(pub T);
+
+impl<'de, T> Deserialize<'de> for NibbleDecoded<T>
+where
+    T: NibbleDecode,
+{
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer).map(NibbleDecoded)
+    }
+}
+
+struct DecodeVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T> Visitor<'de> for DecodeVisitor<T>
+where
+    T: NibbleDecode,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("raw access to the nibble stream")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> core::result::Result<T, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // The nibble deserializer provides the stream as a sequence of raw reads
+        let mut flavor = SeqFlavor {
+            seq,
+            _pd: PhantomData,
+        };
+        T::decode(&mut flavor).map_err(A::Error::custom)
+    }
+}
+
+/// Presents the raw reads offered by the nibble deserializer as a flavor. As the sequence
+/// access only lives for `'s`, so does the flavor, borrowed data is shortened to match.
+struct SeqFlavor<'s, 'de, A: SeqAccess<'de>> {
+    seq: A,
+    _pd: PhantomData<&'s &'de ()>,
+}
+
+impl<'s, 'de, A: SeqAccess<'de>> SeqFlavor<'s, 'de, A> {
+    fn take<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value> {
+        match self.seq.next_element_seed(seed) {
+            Ok(Some(value)) => Ok(value),
+            // The actual error is kept by the nibble deserializer
+            _ => Err(Error::SerdeDeCustom),
+        }
+    }
+}
+
+impl<'s, 'de: 's, A: SeqAccess<'de> + 's> NibbleFlavor<'s> for SeqFlavor<'s, 'de, A> {
+    type Remainder = ();
+    type Source = A;
+
+    fn try_take_nib(&mut self) -> Result<u8> {
+        match self.take(RawRead::Nib)? {
+            RawValue::Byte(nib) => Ok(nib),
+            RawValue::Bytes(_) => Err(Error::SerdeDeCustom),
+        }
+    }
+
+    fn try_take_u8(&mut self) -> Result<u8> {
+        match self.take(RawRead::U8)? {
+            RawValue::Byte(byte) => Ok(byte),
+            RawValue::Bytes(_) => Err(Error::SerdeDeCustom),
+        }
+    }
+
+    fn try_take_n(&mut self, ct: usize) -> Result<&'s [u8]> {
+        match self.take(RawRead::N(ct))? {
+            RawValue::Bytes(bytes) => Ok(bytes),
+            RawValue::Byte(_) => Err(Error::SerdeDeCustom),
+        }
+    }
+
+    fn finalize(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A single raw read, performed by the nibble deserializer
+#[derive(Clone, Copy)]
+pub(crate) enum RawRead {
+    Nib,
+    U8,
+    N(usize),
+}
+
+// The kind of read is passed to the nibble deserializer as the length of a tuple
+impl RawRead {
+    fn to_len(self) -> usize {
+        match self {
+            RawRead::Nib => 0,
+            RawRead::U8 => 1,
+            RawRead::N(ct) => ct + 2,
+        }
+    }
+
+    pub(crate) fn from_len(len: usize) -> Self {
+        match len {
+            0 => RawRead::Nib,
+            1 => RawRead::U8,
+            ct => RawRead::N(ct - 2),
+        }
+    }
+}
+
+pub(crate) enum RawValue<'de> {
+    Byte(u8),
+    Bytes(&'de [u8]),
+}
+
+impl<'de> DeserializeSeed<'de> for RawRead {
+    type Value = RawValue<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(self.to_len(), RawVisitor)
+    }
+}
+
+struct RawVisitor;
+
+impl<'de> Visitor<'de> for RawVisitor {
+    type Value = RawValue<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("raw nibble data")
+    }
+
+    fn visit_u8<E>(self, v: u8) -> core::result::Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RawValue::Byte(v))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> core::result::Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RawValue::Bytes(v))
+    }
+}
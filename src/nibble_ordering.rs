@@ -0,0 +1,59 @@
+//! # Orderings
+//!
+//! `serde` has no implementation for [`core::cmp::Ordering`]. The
+//! [`nibble_ordering`](self) module, for use with `#[serde(with = ...)]`, encodes it
+//! as a unit enum with the variants `Less`, `Equal` and `Greater`, in that order.
+//!
+//! Like every enum with up to eight variants, including `Option` and `Result`, it
+//! takes a single nibble in the nibble format: the variant index is a nibble varint,
+//! holding three bits per nibble.
+
+use core::cmp::Ordering;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Ordering")]
+enum Repr {
+    Less,
+    Equal,
+    Greater,
+}
+
+/// Serialize the ordering as a unit variant.
+///
+/// ```rust
+/// # use core::cmp::Ordering;
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// pub struct Comparison {
+///     #[serde(with = "postcard::nibble_ordering")]
+///     result: Ordering,
+/// }
+///
+/// let mut buf = [0u8; 1];
+/// let used = postcard::to_nibble_slice(&Comparison { result: Ordering::Greater }, &mut buf).unwrap();
+/// assert_eq!(used, &[0x20]);
+/// ```
+pub fn serialize<S>(val: &Ordering, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match val {
+        Ordering::Less => Repr::Less,
+        Ordering::Equal => Repr::Equal,
+        Ordering::Greater => Repr::Greater,
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize the ordering from a unit variant.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Ordering, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Repr::deserialize(deserializer)? {
+        Repr::Less => Ordering::Less,
+        Repr::Equal => Ordering::Equal,
+        Repr::Greater => Ordering::Greater,
+    })
+}
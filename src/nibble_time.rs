@@ -0,0 +1,224 @@
+//! # Nibble Time Helpers
+//!
+//! Time-series data often consists of many timestamps that are close
+//! together. Serializing each of them as a full `Duration` wastes space,
+//! so the helpers in this module store the signed distance to a known
+//! base instead, which is usually a small varint on the wire.
+//!
+//! With the `chrono` feature, [`NibbleDateTime`] stores absolute points in
+//! time compactly as well.
+
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// The signed difference between two points in time, in nanoseconds.
+///
+/// The delta is serialized as a single signed (zigzag) varint, so values
+/// near the base take only a few bytes. The base itself is not part of the
+/// message: both sides need to agree on it, e.g. by sending it once at the
+/// start of a sequence.
+///
+/// ```rust
+/// # use core::time::Duration;
+/// use postcard::nibble_time::NibbleDurationDelta;
+///
+/// let base = Duration::from_secs(1_000);
+/// let delta = NibbleDurationDelta::between(base, Duration::from_millis(999_250)).unwrap();
+/// assert_eq!(delta.nanos(), -750_000_000);
+/// assert_eq!(delta.apply(base), Some(Duration::from_millis(999_250)));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NibbleDurationDelta(pub i64);
+
+impl NibbleDurationDelta {
+    /// Compute the delta from `base` to `current`.
+    ///
+    /// Returns `None` if the difference does not fit in an `i64` worth of
+    /// nanoseconds (roughly 292 years in either direction).
+    pub fn between(base: Duration, current: Duration) -> Option<Self> {
+        let delta = current.as_nanos() as i128 - base.as_nanos() as i128;
+        if delta < i64::MIN as i128 || delta > i64::MAX as i128 {
+            return None;
+        }
+        Some(Self(delta as i64))
+    }
+
+    /// The signed delta in nanoseconds
+    pub fn nanos(&self) -> i64 {
+        self.0
+    }
+
+    /// Reconstruct the point in time this delta was taken from, given the same `base`.
+    ///
+    /// Returns `None` if the result would be negative or overflow a `Duration`.
+    pub fn apply(&self, base: Duration) -> Option<Duration> {
+        let offset = Duration::from_nanos(self.0.unsigned_abs());
+        if self.0 >= 0 {
+            base.checked_add(offset)
+        } else {
+            base.checked_sub(offset)
+        }
+    }
+
+    /// Compute the delta from `base` to `current`.
+    ///
+    /// Returns `None` if the difference does not fit in an `i64` worth of nanoseconds.
+    #[cfg(feature = "use-std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "use-std")))]
+    pub fn between_instants(base: std::time::Instant, current: std::time::Instant) -> Option<Self> {
+        use core::convert::TryFrom;
+
+        let (offset, negative) = match current.checked_duration_since(base) {
+            Some(d) => (d, false),
+            None => (base.checked_duration_since(current)?, true),
+        };
+        let nanos = i64::try_from(offset.as_nanos()).ok()?;
+        Some(Self(if negative { -nanos } else { nanos }))
+    }
+
+    /// Reconstruct the `Instant` this delta was taken from, given the same `base`.
+    #[cfg(feature = "use-std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "use-std")))]
+    pub fn apply_instant(&self, base: std::time::Instant) -> Option<std::time::Instant> {
+        let offset = Duration::from_nanos(self.0.unsigned_abs());
+        if self.0 >= 0 {
+            base.checked_add(offset)
+        } else {
+            base.checked_sub(offset)
+        }
+    }
+}
+
+/// A `chrono::DateTime<Utc>`, serialized as a single signed varint of nanoseconds since the
+/// Unix epoch.
+///
+/// chrono's own serde implementation writes an RFC 3339 string, around 30 bytes, while
+/// present day timestamps take 11 bytes in this encoding, and dates close to the epoch less.
+/// The whole range of `DateTime<Utc>` is supported, including dates before the epoch.
+///
+/// A leap second, which chrono represents with more than a billion nanoseconds in the second,
+/// comes back as the first nanoseconds of the following second.
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use postcard::nibble_time::NibbleDateTime;
+///
+/// let date = NibbleDateTime(Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap());
+/// let mut buf = [0u8; 16];
+/// let ser = postcard::to_nibble_slice(&date, &mut buf).unwrap();
+/// // -1_000_000_000ns, zigzag encoded
+/// assert_eq!(ser.len(), 6);
+/// assert_eq!(postcard::from_nibbles::<NibbleDateTime>(&ser).unwrap(), date);
+/// ```
+#[cfg(feature = "chrono")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "chrono")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NibbleDateTime(pub chrono::DateTime<chrono::Utc>);
+
+#[cfg(feature = "chrono")]
+mod date_time {
+    use super::NibbleDateTime;
+    use chrono::{DateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+    impl From<DateTime<Utc>> for NibbleDateTime {
+        fn from(date: DateTime<Utc>) -> Self {
+            Self(date)
+        }
+    }
+
+    impl From<NibbleDateTime> for DateTime<Utc> {
+        fn from(date: NibbleDateTime) -> Self {
+            date.0
+        }
+    }
+
+    impl Serialize for NibbleDateTime {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            // The nanoseconds are always positive, also before the epoch
+            let nanos = self.0.timestamp() as i128 * NANOS_PER_SEC
+                + self.0.timestamp_subsec_nanos() as i128;
+            serializer.serialize_i128(nanos)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for NibbleDateTime {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let nanos = i128::deserialize(deserializer)?;
+            let secs = nanos.div_euclid(NANOS_PER_SEC);
+            let subsec = nanos.rem_euclid(NANOS_PER_SEC) as u32;
+            if secs < i64::MIN as i128 || secs > i64::MAX as i128 {
+                return Err(de::Error::custom("date out of range"));
+            }
+            DateTime::from_timestamp(secs as i64, subsec)
+                .map(Self)
+                .ok_or_else(|| de::Error::custom("date out of range"))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "use-std"))]
+mod tests {
+    use super::NibbleDurationDelta;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn instant_deltas() {
+        let base = Instant::now();
+        let later = base + Duration::from_millis(15);
+        let delta = NibbleDurationDelta::between_instants(base, later).unwrap();
+        assert_eq!(delta.nanos(), 15_000_000);
+        assert_eq!(delta.apply_instant(base), Some(later));
+
+        let delta = NibbleDurationDelta::between_instants(later, base).unwrap();
+        assert_eq!(delta.nanos(), -15_000_000);
+        assert_eq!(delta.apply_instant(later), Some(base));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_time_loopback() {
+        use super::NibbleDateTime;
+        use chrono::{DateTime, TimeZone, Utc};
+
+        let dates = [
+            DateTime::UNIX_EPOCH,
+            Utc.with_ymd_and_hms(2024, 2, 29, 12, 30, 5).unwrap(),
+            Utc.timestamp_opt(1_700_000_000, 123_456_789).unwrap(),
+            Utc.timestamp_opt(-1, 999_999_999).unwrap(),
+            Utc.with_ymd_and_hms(1900, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(-4000, 6, 15, 8, 0, 0).unwrap(),
+            DateTime::<Utc>::MIN_UTC,
+            DateTime::<Utc>::MAX_UTC,
+        ];
+        for date in dates {
+            let date = NibbleDateTime(date);
+            let ser = crate::to_nibble_stdvec(&date).unwrap();
+            assert_eq!(crate::from_nibbles::<NibbleDateTime>(&ser), Ok(date));
+            // The byte format works just the same
+            let ser = crate::to_stdvec(&date).unwrap();
+            assert_eq!(crate::from_bytes::<NibbleDateTime>(&ser), Ok(date));
+        }
+
+        // Present day dates are far shorter than chrono's own RFC 3339 strings
+        let ser = crate::to_nibble_stdvec(&NibbleDateTime(dates[2])).unwrap();
+        assert_eq!(ser.len(), 11);
+        // Just past the epoch, in both directions
+        let ser = crate::to_nibble_stdvec(&NibbleDateTime(dates[0])).unwrap();
+        assert_eq!(ser, [0x00]);
+        let ser = crate::to_nibble_stdvec(&NibbleDateTime(dates[3])).unwrap();
+        assert_eq!(ser, [0x10]);
+
+        // Nanoseconds beyond what a `DateTime<Utc>` can hold
+        let ser = crate::to_nibble_stdvec(&i128::MAX).unwrap();
+        assert!(crate::from_nibbles::<NibbleDateTime>(&ser).is_err());
+    }
+}
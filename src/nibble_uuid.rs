@@ -0,0 +1,94 @@
+//! # UUIDs
+//!
+//! [`NibbleUuid`] stores a `uuid::Uuid` as its 16 raw bytes, copied as a single
+//! aligned run like [`byte_array`](crate::byte_array), instead of the 36
+//! character hyphenated string.
+//!
+//! The `uuid` feature is required to use this module.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+/// A `uuid::Uuid`, serialized as a one byte length prefix followed by the 16 bytes as-is.
+///
+/// Unlike `Uuid`'s own serde support, this doesn't need the `serde` feature of `uuid`, and
+/// always uses the raw bytes.
+///
+/// ```rust
+/// use postcard::nibble_uuid::NibbleUuid;
+/// use uuid::Uuid;
+///
+/// let id = NibbleUuid(Uuid::from_u128(0x0123_4567_89AB_CDEF_0123_4567_89AB_CDEF));
+/// let mut buf = [0u8; 32];
+/// let ser = postcard::to_nibble_slice(&id, &mut buf).unwrap();
+/// assert_eq!(ser.len(), 17);
+/// assert_eq!(&ser[1..], id.0.as_bytes());
+/// assert_eq!(postcard::from_nibbles::<NibbleUuid>(ser).unwrap(), id);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct NibbleUuid(pub Uuid);
+
+impl From<Uuid> for NibbleUuid {
+    fn from(id: Uuid) -> Self {
+        Self(id)
+    }
+}
+
+impl From<NibbleUuid> for Uuid {
+    fn from(id: NibbleUuid) -> Self {
+        id.0
+    }
+}
+
+impl Serialize for NibbleUuid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        crate::byte_array::serialize(self.0.as_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NibbleUuid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        crate::byte_array::deserialize(deserializer).map(|bytes| Self(Uuid::from_bytes(bytes)))
+    }
+}
+
+#[cfg(all(test, feature = "heapless"))]
+mod tests {
+    use super::NibbleUuid;
+    use uuid::Uuid;
+
+    #[test]
+    fn uuid_loopback() {
+        let ids = [
+            Uuid::nil(),
+            Uuid::from_u128(0x6BA7_B810_9DAD_11D1_80B4_00C0_4FD4_30C8),
+            Uuid::from_u128(u128::MAX),
+        ];
+        for id in ids {
+            let ser: heapless::Vec<u8, 32> = crate::to_nibble_vec(&NibbleUuid(id)).unwrap();
+            // The length prefix, then 32 nibbles of raw bytes
+            assert_eq!(ser[0], 0xA0);
+            assert_eq!((ser.len() - 1) * 2, 32);
+            assert_eq!(&ser[1..], id.as_bytes());
+            assert_eq!(crate::from_nibbles::<NibbleUuid>(&ser), Ok(NibbleUuid(id)));
+        }
+
+        // Behind a nibble, the run still starts on the next byte
+        let ser: heapless::Vec<u8, 32> = crate::to_nibble_vec(&(true, NibbleUuid(ids[1]))).unwrap();
+        assert_eq!(&ser[..2], &[0x1A, 0x00]);
+        assert_eq!(&ser[2..], ids[1].as_bytes());
+        assert_eq!(
+            crate::from_nibbles::<(bool, NibbleUuid)>(&ser),
+            Ok((true, NibbleUuid(ids[1])))
+        );
+
+        // Truncated
+        assert!(crate::from_nibbles::<NibbleUuid>(&[0xA0, 0x00]).is_err());
+    }
+}
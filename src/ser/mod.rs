@@ -1,22 +1,37 @@
 use crate::error::{Error, Result};
 use crate::ser::flavors::{Cobs, Flavor, Slice};
-use crate::ser::nibble_flavors::NibbleFlavor;
+use crate::ser::nibble_flavors::{
+    NibbleBudgeted, NibbleChunk, NibbleFlavor, NibbleSlice, NIBBLE_CHUNK_LEN,
+};
 use serde::Serialize;
 
 #[cfg(feature = "heapless")]
 use crate::ser::flavors::HVec;
 #[cfg(feature = "heapless")]
-use crate::ser::nibble_flavors::NibbleHVec;
-
+use crate::ser::nibble_flavors::{
+    NibbleCanonical, NibbleCobs, NibbleFieldCount, NibbleFieldTypes, NibbleHVec,
+    NibbleNamedVariants, NibblePackedBools, NibbleSeparated, NibbleTagMap, NibbleTwoLevelTags,
+    NibbleXor,
+};
+
+#[cfg(any(feature = "heapless", feature = "alloc"))]
+use crate::vlu32n::Vlu32N;
 #[cfg(feature = "heapless")]
 use heapless::Vec;
 
 #[cfg(feature = "alloc")]
 use crate::ser::flavors::AllocVec;
+#[cfg(feature = "alloc")]
+use crate::ser::nibble_flavors::NibbleAllocVec;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use serde::de::DeserializeOwned;
+
 use crate::ser::nibble_serializer::NibbleSerializer;
 use crate::ser::serializer::Serializer;
 
@@ -96,6 +111,143 @@ where
     serialize_with_flavor::<T, Slice<'a>, &'a mut [u8]>(value, Slice::new(buf))
 }
 
+/// Serialize a `T` to the given slice in the nibble format.
+///
+/// When successful, this function returns the slice containing the serialized message. A
+/// message that ends in the middle of a byte includes that byte, with a zero low nibble.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_slice;
+/// let mut buf = [0u8; 32];
+///
+/// let used = to_nibble_slice(&(true, 7u8), &mut buf).unwrap();
+/// assert_eq!(used, &[0x17]);
+///
+/// let used = to_nibble_slice("Hi!", &mut buf).unwrap();
+/// assert_eq!(used, &[0x30, b'H', b'i', b'!']);
+/// ```
+pub fn to_nibble_slice<'a, T>(value: &T, buf: &'a mut [u8]) -> Result<&'a mut [u8]>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleSlice::new(buf))
+}
+
+/// Serialize a `T` to the given slice in the nibble format, keeping `reserve_bytes` free right
+/// after the message.
+///
+/// When successful, this function returns the slice containing the serialized message, and the
+/// reserved region of exactly `reserve_bytes` that directly follows it. This allows appending
+/// data that can only be computed from the finished message, such as a signature, in place.
+/// The message has to fit into the buffer without the reserved bytes, otherwise
+/// [`Error::SerializeBufferFull`] is returned.
+///
+/// ## Example
+///
+/// ```rust
+/// let mut buf = [0u8; 8];
+/// let (used, tail) = postcard::to_nibble_slice_reserve(&(1u8, 2u8), &mut buf, 2).unwrap();
+/// assert_eq!(used, &[0x12]);
+/// let sum = used.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+/// tail.copy_from_slice(&[sum, !sum]);
+/// assert_eq!(&buf[..3], &[0x12, 0x12, 0xED]);
+/// ```
+pub fn to_nibble_slice_reserve<'a, T>(
+    value: &T,
+    buf: &'a mut [u8],
+    reserve_bytes: usize,
+) -> Result<(&'a mut [u8], &'a mut [u8])>
+where
+    T: Serialize + ?Sized,
+{
+    let limit = buf
+        .len()
+        .checked_sub(reserve_bytes)
+        .ok_or(Error::SerializeBufferFull)?;
+    let used = serialize_with_nibble_flavor(value, NibbleSlice::new(&mut buf[..limit]))?.len();
+    let (used, rest) = buf.split_at_mut(used);
+    Ok((used, &mut rest[..reserve_bytes]))
+}
+
+/// Serialize a `T` in the nibble format as an iterator over the bytes of the message, for
+/// sinks that take one byte at a time, such as a UART, without a buffer for the whole message.
+///
+/// The message is produced in chunks of a few dozen bytes by a
+/// [`NibbleBudgeted`](crate::ser_nibble_flavors::NibbleBudgeted) flavor, which serializes
+/// `value` again for every chunk and skips what earlier chunks already produced. This keeps
+/// the memory use small and fixed, at the cost of time growing with the square of the message
+/// length. An error ends the iteration after it is returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_iter;
+///
+/// let bytes: Result<Vec<u8>, _> = to_nibble_iter(&(true, 7u8, "Hi!")).collect();
+/// assert_eq!(bytes.unwrap(), &[0x17, 0x30, b'H', b'i', b'!']);
+/// ```
+pub fn to_nibble_iter<T>(value: &T) -> NibbleIter<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    NibbleIter {
+        value,
+        flavor: Some(NibbleBudgeted::new(
+            NibbleChunk::new(),
+            NIBBLE_CHUNK_LEN - 1,
+        )),
+        chunk: [0; NIBBLE_CHUNK_LEN],
+        pos: 0,
+        len: 0,
+    }
+}
+
+/// An iterator over the bytes of a message in the nibble format, see [`to_nibble_iter()`].
+pub struct NibbleIter<'a, T>
+where
+    T: Serialize + ?Sized,
+{
+    value: &'a T,
+    /// `None` once the message is complete or failed
+    flavor: Option<NibbleBudgeted<NibbleChunk>>,
+    chunk: [u8; NIBBLE_CHUNK_LEN],
+    pos: usize,
+    len: usize,
+}
+
+impl<'a, T> Iterator for NibbleIter<'a, T>
+where
+    T: Serialize + ?Sized,
+{
+    type Item = Result<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos == self.len {
+            let flavor = self.flavor.as_mut()?;
+            let done = match flavor.step(self.value) {
+                Ok(()) => true,
+                Err(Error::SerializeBudgetExhausted) => false,
+                Err(e) => {
+                    self.flavor = None;
+                    return Some(Err(e));
+                }
+            };
+            let bytes = flavor.flavor_mut().take(done);
+            self.chunk[..bytes.len()].copy_from_slice(bytes);
+            self.pos = 0;
+            self.len = bytes.len();
+            if done {
+                self.flavor = None;
+            }
+        }
+        let byte = self.chunk[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
 /// Serialize a `T` to a `heapless::Vec<u8>`, with the `Vec` containing
 /// data in a serialized then COBS encoded format. The terminating sentinel
 /// `0x00` byte is included in the output `Vec`.
@@ -173,7 +325,205 @@ pub fn to_nibble_vec<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
 where
     T: Serialize + ?Sized,
 {
-    serialize_with_nibble_flavor::<T, NibbleHVec<B>, Vec<u8, B>>(value, NibbleHVec::default())
+    serialize_with_nibble_flavor(value, NibbleHVec::default())
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, framed with COBS and
+/// terminated with a `0x00`.
+///
+/// The frame contains no other zero bytes, so it is self-delimiting on a byte stream. See
+/// [`NibbleCobs`] for the details, and `from_nibbles_cobs()` to decode it.
+///
+/// [`NibbleCobs`]: crate::ser_nibble_flavors::NibbleCobs
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_vec_cobs;
+/// use heapless::Vec;
+///
+/// let ser: Vec<u8, 8> = to_nibble_vec_cobs(&(true, 0u8, 7u8)).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x03, 0x10, 0x70, 0x00]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_cobs<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleCobs::new(NibbleHVec::default()))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, such that equal values
+/// produce identical bytes, as long as they contain no unordered collections other than maps.
+///
+/// The output is regular nibble data and decodes with `from_nibbles()`, but map entries are
+/// written in a fixed order instead of iteration order, which makes the result suitable for
+/// hashing or content-addressed storage. See [`NibbleCanonical`] for the exact guarantees.
+/// Maps are staged in a scratch buffer of another `B` bytes.
+///
+/// Sets reach the serializer as plain sequences, so they can't be told apart from sequences
+/// whose order matters and are written in iteration order. Two equal `HashSet`s can produce
+/// different bytes, use a `BTreeSet` (or a sorted `Vec`) where the output needs to be stable.
+///
+/// [`NibbleCanonical`]: crate::ser_nibble_flavors::NibbleCanonical
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_canonical<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleCanonical::<_, B>::new(NibbleHVec::default()))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, with a separator nibble
+/// written after every struct field.
+///
+/// This is a debugging format, see [`NibbleSeparated`](crate::ser_nibble_flavors::NibbleSeparated).
+/// Use `from_nibbles_separated()` to decode it.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_separated<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleSeparated::new(NibbleHVec::default()))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, with a type tag nibble
+/// written in front of every struct field.
+///
+/// See [`NibbleFieldTypes`](crate::ser_nibble_flavors::NibbleFieldTypes) for the tags.
+/// Use `from_nibbles_field_types()` to decode it.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_field_types<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleFieldTypes::new(NibbleHVec::default()))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, with consecutive booleans
+/// packed into shared nibbles.
+///
+/// A struct with several flags in a row gets noticeably smaller, see
+/// [`NibblePackedBools`](crate::ser_nibble_flavors::NibblePackedBools).
+/// Use `from_nibbles_packed()` to decode it.
+///
+/// ```rust
+/// // Three bools and a small number, in one byte instead of two
+/// let packed: heapless::Vec<u8, 4> = postcard::to_nibble_vec_packed(&(true, false, true, 3u8)).unwrap();
+/// assert_eq!(packed.as_slice(), &[0x53]);
+/// let back: (bool, bool, bool, u8) = postcard::from_nibbles_packed(&packed).unwrap();
+/// assert_eq!(back, (true, false, true, 3));
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_packed<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibblePackedBools::new(NibbleHVec::default()))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, preceded by the number of
+/// fields of its outermost struct as a nibble varint.
+///
+/// When newer versions of a message only append fields, `from_nibbles_prefix_versioned()`
+/// decodes the fields an older version of the struct knows about, and checks that the message
+/// has all of them. The value is serialized twice, the first time to count its fields, see
+/// [`NibbleFieldCount`](crate::ser_nibble_flavors::NibbleFieldCount).
+///
+/// ```rust
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// struct Sample { id: u8, ok: bool }
+///
+/// let out: heapless::Vec<u8, 4> = postcard::to_nibble_vec_prefix_versioned(&Sample { id: 5, ok: true }).unwrap();
+/// assert_eq!(out.as_slice(), &[0x25, 0x10]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_prefix_versioned<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    let fields = serialize_with_nibble_flavor(value, NibbleFieldCount::default())?;
+    let mut flavor = NibbleHVec::default();
+    Vlu32N(fields).ser(&mut flavor)?;
+    serialize_with_nibble_flavor(value, flavor)
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, obfuscated by XORing every
+/// byte with the repeating `key`.
+///
+/// See [`NibbleXor`](crate::ser_nibble_flavors::NibbleXor), which is no encryption. Use
+/// `from_nibbles_xor()` with the same key to decode it.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_xor<T, const B: usize>(value: &T, key: &[u8]) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleXor::new(NibbleHVec::default(), key))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, with the discriminants of
+/// the enums selected by `map` written as two-level tags.
+///
+/// See [`NibbleTwoLevelTags`](crate::ser_nibble_flavors::NibbleTwoLevelTags).
+/// Use `from_nibbles_with_tags()` with the same map to decode it.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_with_tags<T, M, const B: usize>(value: &T, map: M) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+    M: NibbleTagMap,
+{
+    serialize_with_nibble_flavor(value, NibbleTwoLevelTags::new(NibbleHVec::default(), map))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, with enum variants written
+/// as their name instead of their index.
+///
+/// See [`NibbleNamedVariants`](crate::ser_nibble_flavors::NibbleNamedVariants).
+/// Use `from_nibbles_named_variants()` to decode it.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_nibble_vec_named_variants<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleNamedVariants::new(NibbleHVec::default()))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>` in the nibble format, and return the 32-bit CRC
+/// of the serialized bytes separately instead of appending it.
+///
+/// This is meant for transports that carry the CRC in a header field of their own. The CRC
+/// covers the returned bytes exactly, including the padding of a trailing half byte.
+///
+/// ```rust
+/// use crc::{Crc, CRC_32_ISCSI};
+///
+/// let crc = Crc::<u32>::new(&CRC_32_ISCSI);
+/// let (ser, sum) = postcard::to_nibble_vec_with_crc::<_, 8>(&(true, 0x12u8), crc.digest()).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x1A, 0x20]);
+/// assert_eq!(sum, crc.checksum(&ser));
+/// ```
+#[cfg(all(feature = "use-crc", feature = "heapless"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "use-crc", feature = "heapless"))))]
+pub fn to_nibble_vec_with_crc<T, const B: usize>(
+    value: &T,
+    mut digest: crc::Digest<'_, u32>,
+) -> Result<(Vec<u8, B>, u32)>
+where
+    T: Serialize + ?Sized,
+{
+    let ser: Vec<u8, B> = to_nibble_vec(value)?;
+    digest.update(&ser);
+    Ok((ser, digest.finalize()))
 }
 
 /// Serialize a `T` to a `std::vec::Vec<u8>`.
@@ -224,6 +574,30 @@ where
     to_allocvec_cobs(value)
 }
 
+/// Serialize a `T` to a `std::vec::Vec<u8>`, with the `Vec` containing
+/// data in a nibble serialized format.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_stdvec;
+///
+/// let ser: Vec<u8> = to_nibble_stdvec(&(true, 7u8)).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x17]);
+///
+/// let ser: Vec<u8> = to_nibble_stdvec("Hi!").unwrap();
+/// assert_eq!(ser.as_slice(), &[0x30, b'H', b'i', b'!']);
+/// ```
+#[cfg(feature = "use-std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-std")))]
+#[inline]
+pub fn to_nibble_stdvec<T>(value: &T) -> Result<std::vec::Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    to_nibble_allocvec(value)
+}
+
 /// Serialize a `T` to an `alloc::vec::Vec<u8>`.
 ///
 /// ## Example
@@ -273,6 +647,173 @@ where
     )
 }
 
+/// Serialize a `T` to an `alloc::vec::Vec<u8>`, with the `Vec` containing
+/// data in a nibble serialized format.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_allocvec;
+///
+/// let ser: Vec<u8> = to_nibble_allocvec(&(true, 7u8)).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x17]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn to_nibble_allocvec<T>(value: &T) -> Result<alloc::vec::Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, NibbleAllocVec::new())
+}
+
+/// Serialize a `T` to an `alloc::vec::Vec<u8>` as a length delimited frame, to send messages
+/// over a byte stream without COBS.
+///
+/// The frame starts with the length of the nibble encoded message in bytes, as a nibble varint
+/// padded to a whole byte, followed by the message. Use [`read_nibble_frame()`](crate::read_nibble_frame)
+/// to decode it.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_frame;
+///
+/// let frame = to_nibble_frame(&(true, 7u8, "Hi!")).unwrap();
+/// assert_eq!(frame.as_slice(), &[0x50, 0x17, 0x30, b'H', b'i', b'!']);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn to_nibble_frame<T>(value: &T) -> Result<alloc::vec::Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    use core::convert::TryFrom;
+
+    let message = to_nibble_allocvec(value)?;
+    let len = u32::try_from(message.len()).map_err(|_| Error::TooBigLen)?;
+    let mut flavor = NibbleAllocVec::new();
+    Vlu32N(len).ser(&mut flavor)?;
+    // Aligns the message to the next byte
+    flavor.try_extend(&message)?;
+    flavor.finalize()
+}
+
+/// Serialize a `T` in the nibble format, and render every nibble as a lowercase hex digit,
+/// e.g. for logging or to embed a message in a text config. A partially used last byte is
+/// rendered with its padding nibble.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_hex;
+///
+/// assert_eq!(to_nibble_hex(&(true, 7u8)).unwrap(), "17");
+/// assert_eq!(to_nibble_hex(&(true, 7u8, false)).unwrap(), "1700");
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn to_nibble_hex<T>(value: &T) -> Result<alloc::string::String>
+where
+    T: Serialize + ?Sized,
+{
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let bytes = to_nibble_allocvec(value)?;
+    let mut hex = alloc::string::String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        hex.push(DIGITS[(b >> 4) as usize] as char);
+        hex.push(DIGITS[(b & 0x0F) as usize] as char);
+    }
+    Ok(hex)
+}
+
+/// Serialize a `T` in the nibble format into `scratch` if it fits, or into an
+/// `alloc::vec::Vec<u8>` otherwise.
+///
+/// Small messages don't need an allocation this way, while larger ones are still serialized
+/// successfully. Only running out of space in `scratch` causes the fallback, which serializes
+/// the value a second time, other errors are returned as-is.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_nibble_cow;
+/// use std::borrow::Cow;
+///
+/// let mut scratch = [0u8; 4];
+/// let ser = to_nibble_cow(&(true, 7u8), &mut scratch).unwrap();
+/// assert!(matches!(ser, Cow::Borrowed(&[0x17])));
+///
+/// let ser = to_nibble_cow("Hello!", &mut scratch).unwrap();
+/// assert!(matches!(ser, Cow::Owned(_)));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn to_nibble_cow<'a, T>(value: &T, scratch: &'a mut [u8]) -> Result<Cow<'a, [u8]>>
+where
+    T: Serialize + ?Sized,
+{
+    match serialize_with_nibble_flavor(value, NibbleSlice::new(scratch)) {
+        Ok(used) => Ok(Cow::Borrowed(used)),
+        Err(Error::SerializeBufferFull) => to_nibble_allocvec(value).map(Cow::Owned),
+        Err(e) => Err(e),
+    }
+}
+
+/// Re-encode a `T` stored in the standard byte postcard format as nibbles, e.g. to migrate
+/// existing data.
+///
+/// The value is fully decoded from `bytes`, so it has to be a valid `T`. Any bytes left over
+/// after it are ignored, like with [`from_bytes`](crate::from_bytes).
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::transcode_from_byte_postcard;
+///
+/// let bytes = postcard::to_allocvec(&(true, 7u8)).unwrap();
+/// assert_eq!(bytes.as_slice(), &[0x01, 0x07]);
+/// let nibbles = transcode_from_byte_postcard::<(bool, u8)>(&bytes).unwrap();
+/// assert_eq!(nibbles.as_slice(), &[0x17]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn transcode_from_byte_postcard<T>(bytes: &[u8]) -> Result<alloc::vec::Vec<u8>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    transcode::<T, _, _>(crate::de_flavors::Slice::new(bytes), NibbleAllocVec::new())
+}
+
+/// Re-encode a `T` read in the standard byte postcard format from any deserialization
+/// [`Flavor`](crate::de_flavors::Flavor) as nibbles, written to any [`NibbleFlavor`].
+///
+/// Postcard messages don't describe themselves, so the value is fully decoded as a `T` in
+/// between, and has to be a valid `T`. Unlike [`transcode_from_byte_postcard()`], this works
+/// without the `alloc` feature and with types borrowing from the input.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{de_flavors::Slice, ser_nibble_flavors::NibbleSlice, transcode};
+///
+/// let mut buf = [0u8; 8];
+/// let bytes = [0x01, 0x03, b'H', b'i', b'!'];
+/// let nibbles = transcode::<(bool, &str), _, _>(Slice::new(&bytes), NibbleSlice::new(&mut buf));
+/// assert_eq!(nibbles.unwrap(), &[0x13, b'H', b'i', b'!']);
+/// ```
+pub fn transcode<'de, T, D, F>(input: D, output: F) -> Result<F::Output>
+where
+    T: Serialize + serde::Deserialize<'de>,
+    D: crate::de_flavors::Flavor<'de>,
+    F: NibbleFlavor,
+{
+    let mut deserializer = crate::Deserializer::from_flavor(input);
+    let value = T::deserialize(&mut deserializer)?;
+    serialize_with_nibble_flavor(&value, output)
+}
+
 /// Conveniently serialize a `T` to the given slice, with the resulting slice containing
 /// data followed by a 32-bit CRC. The CRC bytes are included in the output buffer.
 ///
@@ -446,12 +987,26 @@ where
         .map_err(|_| Error::SerializeBufferFull)
 }
 
-pub fn serialize_with_nibble_flavor<T, S, O>(value: &T, storage: S) -> Result<O>
+/// `serialize_with_nibble_flavor()` has the same use as `serialize_with_flavor()`, for the
+/// nibble format: it drives the nibble serializer against any [`NibbleFlavor`], either a
+/// storage flavor or a stack of modifier flavors on top of one.
+///
+/// * `T`: This is the type that is being serialized
+/// * `F`: This is the flavor that is used during serialization, its `Output` is returned
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::NibbleSlice};
+///
+/// let buffer = &mut [0u8; 32];
+/// let res = serialize_with_nibble_flavor(&(true, 7u8), NibbleSlice::new(buffer)).unwrap();
+/// assert_eq!(res, &[0x17]);
+/// ```
+pub fn serialize_with_nibble_flavor<T, F>(value: &T, flavor: F) -> Result<F::Output>
 where
     T: Serialize + ?Sized,
-    S: NibbleFlavor<Output = O>,
+    F: NibbleFlavor,
 {
-    let mut serializer = NibbleSerializer { output: storage };
+    let mut serializer = NibbleSerializer { output: flavor };
     value.serialize(&mut serializer)?;
     serializer
         .output
@@ -467,6 +1022,41 @@ where
     serialize_with_flavor::<T, flavors::Size, usize>(value, flavors::Size::default())
 }
 
+/// Compute the number of nibbles in the nibble serialization of `T`, see
+/// [`nibble_size_bytes`] for the size in bytes.
+///
+/// ```rust
+/// use postcard::{nibble_size, nibble_size_bytes};
+///
+/// let value = (false, 7u8, true);
+/// assert_eq!(nibble_size(&value).unwrap(), 3);
+/// assert_eq!(nibble_size_bytes(&value).unwrap(), 2);
+/// ```
+pub fn nibble_size<T>(value: &T) -> Result<usize>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, nibble_flavors::NibbleSize::default())
+}
+
+/// Compute the number of bytes in the nibble serialization of `T`, including the padding of
+/// a partially used last byte.
+pub fn nibble_size_bytes<T>(value: &T) -> Result<usize>
+where
+    T: Serialize + ?Sized,
+{
+    nibble_size(value).map(|nibbles| nibbles.div_ceil(2))
+}
+
+/// Compute the smallest and the largest nibble serialization of values shaped like `value`,
+/// see [`NibbleSizeBounds`](nibble_flavors::NibbleSizeBounds) for what counts as the shape.
+pub fn nibble_size_bounds<T>(value: &T) -> Result<(usize, usize)>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_nibble_flavor(value, nibble_flavors::NibbleSizeBounds::default())
+}
+
 #[cfg(feature = "heapless")]
 #[cfg(test)]
 mod test {
@@ -1,7 +1,11 @@
 //! # Nibble based Serialization Flavors
 //!
 use crate::error::{Error, Result};
+use crate::ser::nibble_serializer::NibbleSerializer;
+use crate::vlu32n::Vlu32N;
 use core::marker::PhantomData;
+use core::ops::DerefMut;
+use serde::Serialize;
 
 #[cfg(feature = "heapless")]
 pub use heapless_vec::*;
@@ -9,6 +13,15 @@ pub use heapless_vec::*;
 #[cfg(feature = "use-std")]
 pub use std_vec::*;
 
+#[cfg(feature = "use-std")]
+pub use layout::*;
+
+#[cfg(feature = "use-std")]
+pub use io_writer::*;
+
+#[cfg(feature = "bbqueue")]
+pub use bbq_writer::*;
+
 #[cfg(feature = "alloc")]
 pub use alloc_vec::*;
 
@@ -29,6 +42,11 @@ pub trait NibbleFlavor {
     /// The try_extend() trait method can be implemented when there is a more efficient way of processing
     /// multiple bytes at once, such as copying a slice to the output, rather than iterating over one byte
     /// at a time.
+    ///
+    /// The storage flavors of this crate override it to write the data starting at a byte
+    /// boundary, padding a partially written byte with a zero nibble first, so that extending
+    /// them with an empty slice aligns the output mid-stream, e.g. before handing byte data to a
+    /// DMA transfer. The default implementation pushes the bytes one at a time and doesn't align.
     #[inline]
     fn try_extend(&mut self, data: &[u8]) -> Result<()> {
         data.iter().try_for_each(|d| self.try_push_u8(*d))
@@ -40,10 +58,264 @@ pub trait NibbleFlavor {
     /// The try_push_nib() trait method can be used to push a single nibble to be modified and/or stored
     fn try_push_nib(&mut self, nib: u8) -> Result<()>;
 
+    /// The try_push_nibbles() trait method can be used to push several nibbles at once, each
+    /// given by the low 4 bits of an element. By default they are pushed one at a time, storage
+    /// flavors can pack them into bytes more efficiently.
+    #[inline]
+    fn try_push_nibbles(&mut self, nibs: &[u8]) -> Result<()> {
+        nibs.iter().try_for_each(|n| self.try_push_nib(*n))
+    }
+
+    /// The try_push_bool() trait method is used for booleans. By default they take up a whole
+    /// nibble, modifier flavors such as [`NibblePackedBools`] can store them more densely.
+    #[inline]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        self.try_push_nib(b as u8)
+    }
+
+    /// The try_push_variant() trait method is used for enum discriminants, `name` being the name
+    /// of the enum and `variant` the name of the variant. By default they are written as a nibble
+    /// varint, modifier flavors such as [`NibbleTwoLevelTags`] can encode them differently.
+    #[inline]
+    fn try_push_variant(
+        &mut self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Vlu32N(index).ser(self)
+    }
+
+    /// The field_start() trait method is called by the serializer before each struct field, with
+    /// the name of the field. Storage flavors can ignore it, modifier flavors such as
+    /// `NibbleLayoutRecorder` use it to annotate the output.
+    #[inline]
+    fn field_start(&mut self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    /// The field_end() trait method is called by the serializer after each struct field has been
+    /// serialized. Storage flavors can ignore it, it exists so that modifier flavors such as
+    /// [`NibbleSeparated`] can mark field boundaries.
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The map_start() trait method is called by the serializer before a map's length prefix.
+    /// Together with [`map_entry()`](Self::map_entry) and [`map_end()`](Self::map_end) it lets
+    /// flavors such as [`NibbleCanonical`] reorder map entries. Other flavors can ignore them.
+    #[inline]
+    fn map_start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The map_entry() trait method is called by the serializer before each map key.
+    #[inline]
+    fn map_entry(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The map_end() trait method is called by the serializer after the last map value.
+    #[inline]
+    fn map_end(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The serde_call() trait method is called by the serializer at the start of every
+    /// `serde::Serializer` method, allowing instrumentation flavors such as [`NibbleCallCount`]
+    /// to observe how a value is serialized. Other flavors can ignore it.
+    #[inline]
+    fn serde_call(&mut self, _call: SerdeCall) {}
+
     /// Finalize the serialization process
     fn finalize(self) -> Result<Self::Output>;
 }
 
+/// A borrowed flavor is a flavor too, so that values can be serialized into a flavor that is
+/// still used afterwards, e.g. after writing a header by hand. Finalizing the borrow does
+/// nothing, the owner finalizes the flavor itself once all data has been written.
+///
+/// ```rust
+/// use postcard::ser_nibble_flavors::{NibbleFlavor, NibbleSlice};
+/// use postcard::serialize_with_nibble_flavor;
+///
+/// let mut buf = [0u8; 4];
+/// let mut flavor = NibbleSlice::new(&mut buf);
+/// flavor.try_push_nib(0xA).unwrap();
+/// serialize_with_nibble_flavor(&(true, 5u8, false), &mut flavor).unwrap();
+/// assert_eq!(flavor.finalize().unwrap(), &[0xA1, 0x50]);
+/// ```
+impl<F> NibbleFlavor for &mut F
+where
+    F: NibbleFlavor,
+{
+    type Output = ();
+
+    #[inline(always)]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        (**self).try_extend(data)
+    }
+
+    #[inline(always)]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        (**self).try_push_u8(data)
+    }
+
+    #[inline(always)]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        (**self).try_push_nib(nib)
+    }
+
+    #[inline(always)]
+    fn try_push_nibbles(&mut self, nibs: &[u8]) -> Result<()> {
+        (**self).try_push_nibbles(nibs)
+    }
+
+    #[inline(always)]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        (**self).try_push_bool(b)
+    }
+
+    #[inline(always)]
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        (**self).try_push_variant(name, index, variant)
+    }
+
+    #[inline(always)]
+    fn field_start(&mut self, name: &'static str) -> Result<()> {
+        (**self).field_start(name)
+    }
+
+    #[inline(always)]
+    fn field_end(&mut self) -> Result<()> {
+        (**self).field_end()
+    }
+
+    #[inline(always)]
+    fn map_start(&mut self) -> Result<()> {
+        (**self).map_start()
+    }
+
+    #[inline(always)]
+    fn map_entry(&mut self) -> Result<()> {
+        (**self).map_entry()
+    }
+
+    #[inline(always)]
+    fn map_end(&mut self) -> Result<()> {
+        (**self).map_end()
+    }
+
+    #[inline(always)]
+    fn serde_call(&mut self, call: SerdeCall) {
+        (**self).serde_call(call)
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok(())
+    }
+}
+
+/// Implements the listed [`NibbleFlavor`] methods by handing them on unchanged to the inner
+/// flavor in the given field, so that modifier flavors only spell out what they change.
+macro_rules! forward_nibble_hooks {
+    ($inner:ident => $($hook:ident)*) => {
+        $(forward_nibble_hooks!(@hook $inner $hook);)*
+    };
+    (@hook $inner:ident try_extend) => {
+        #[inline]
+        fn try_extend(&mut self, data: &[u8]) -> $crate::error::Result<()> {
+            self.$inner.try_extend(data)
+        }
+    };
+    (@hook $inner:ident try_push_u8) => {
+        #[inline]
+        fn try_push_u8(&mut self, data: u8) -> $crate::error::Result<()> {
+            self.$inner.try_push_u8(data)
+        }
+    };
+    (@hook $inner:ident try_push_nib) => {
+        #[inline]
+        fn try_push_nib(&mut self, nib: u8) -> $crate::error::Result<()> {
+            self.$inner.try_push_nib(nib)
+        }
+    };
+    (@hook $inner:ident try_push_nibbles) => {
+        #[inline]
+        fn try_push_nibbles(&mut self, nibs: &[u8]) -> $crate::error::Result<()> {
+            self.$inner.try_push_nibbles(nibs)
+        }
+    };
+    (@hook $inner:ident try_push_bool) => {
+        #[inline]
+        fn try_push_bool(&mut self, b: bool) -> $crate::error::Result<()> {
+            self.$inner.try_push_bool(b)
+        }
+    };
+    (@hook $inner:ident try_push_variant) => {
+        #[inline]
+        fn try_push_variant(
+            &mut self,
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+        ) -> $crate::error::Result<()> {
+            self.$inner.try_push_variant(name, index, variant)
+        }
+    };
+    (@hook $inner:ident field_start) => {
+        #[inline]
+        fn field_start(&mut self, name: &'static str) -> $crate::error::Result<()> {
+            self.$inner.field_start(name)
+        }
+    };
+    (@hook $inner:ident field_end) => {
+        #[inline]
+        fn field_end(&mut self) -> $crate::error::Result<()> {
+            self.$inner.field_end()
+        }
+    };
+    (@hook $inner:ident map_start) => {
+        #[inline]
+        fn map_start(&mut self) -> $crate::error::Result<()> {
+            self.$inner.map_start()
+        }
+    };
+    (@hook $inner:ident map_entry) => {
+        #[inline]
+        fn map_entry(&mut self) -> $crate::error::Result<()> {
+            self.$inner.map_entry()
+        }
+    };
+    (@hook $inner:ident map_end) => {
+        #[inline]
+        fn map_end(&mut self) -> $crate::error::Result<()> {
+            self.$inner.map_end()
+        }
+    };
+    (@hook $inner:ident serde_call) => {
+        #[inline(always)]
+        fn serde_call(&mut self, call: $crate::ser::nibble_flavors::SerdeCall) {
+            self.$inner.serde_call(call)
+        }
+    };
+    (@hook $inner:ident finalize) => {
+        #[inline]
+        fn finalize(self) -> $crate::error::Result<Self::Output> {
+            self.$inner.finalize()
+        }
+    };
+}
+
 ////////////////////////////////////////
 // Slice
 ////////////////////////////////////////
@@ -59,17 +331,52 @@ pub struct NibbleSlice<'a> {
 }
 
 impl<'a> NibbleSlice<'a> {
-    // Create a new `Slice` flavor from a given backing buffer
-    // pub fn new(buf: &'a mut [u8]) -> Self {
-    //     let ptr = buf.as_mut_ptr();
-    //     NibbleSlice {
-    //         start: ptr,
-    //         cursor: ptr,
-    //         is_at_byte_boundary: true,
-    //         end: unsafe { ptr.add(buf.len()) },
-    //         _pl: PhantomData,
-    //     }
-    // }
+    /// Create a new `Slice` flavor from a given backing buffer
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        let ptr = buf.as_mut_ptr();
+        NibbleSlice {
+            start: ptr,
+            cursor: ptr,
+            is_at_byte_boundary: true,
+            end: unsafe { ptr.add(buf.len()) },
+            _pl: PhantomData,
+        }
+    }
+
+    /// Create a new `Slice` flavor that starts writing `nibble_offset` nibbles into the given
+    /// backing buffer, keeping the nibbles before it. At an odd offset, the high nibble of the
+    /// byte it falls into is preserved and the next write fills its low nibble, so that values
+    /// can share a byte with a tag written by hand. An offset of 0 is the same as [`new`].
+    ///
+    /// The preserved nibbles count as written, and are part of the output.
+    ///
+    /// ```rust
+    /// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::NibbleSlice};
+    ///
+    /// let mut buf = [0xA0, 0xFF, 0xFF];
+    /// let used = serialize_with_nibble_flavor(&5u8, NibbleSlice::new_at_nibble(&mut buf, 1)).unwrap();
+    /// assert_eq!(used, &[0xA5]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nibble_offset` is beyond the end of `buf`.
+    ///
+    /// [`new`]: NibbleSlice::new
+    pub fn new_at_nibble(buf: &'a mut [u8], nibble_offset: usize) -> Self {
+        assert!(
+            nibble_offset <= buf.len() * 2,
+            "nibble offset beyond the end of the buffer"
+        );
+        if nibble_offset & 1 == 1 {
+            // The low nibble is written with an OR, it must not hold stale data
+            buf[nibble_offset / 2] &= 0b1111_0000;
+        }
+        let mut flav = Self::new(buf);
+        flav.cursor = unsafe { flav.start.add(nibble_offset / 2) };
+        flav.is_at_byte_boundary = nibble_offset & 1 == 0;
+        flav
+    }
 
     fn align(&mut self) -> Result<()> {
         if !self.is_at_byte_boundary {
@@ -78,6 +385,22 @@ impl<'a> NibbleSlice<'a> {
         Ok(())
     }
 
+    /// The number of nibbles written so far
+    pub fn nibbles_written(&self) -> usize {
+        let bytes_done = (self.cursor as usize) - (self.start as usize);
+        if self.is_at_byte_boundary {
+            bytes_done * 2
+        } else {
+            // The cursor stays on a partially written byte until its low nibble is written
+            bytes_done * 2 + 1
+        }
+    }
+
+    /// The number of bytes written so far, including a partially written last byte
+    pub fn bytes_written(&self) -> usize {
+        self.nibbles_written().div_ceil(2)
+    }
+
     fn nibbles_left(&self) -> usize {
         let bytes_remain = (self.end as usize) - (self.cursor as usize);
         if self.is_at_byte_boundary {
@@ -120,16 +443,17 @@ impl<'a> NibbleFlavor for NibbleSlice<'a> {
             unsafe {
                 let mut b = self.cursor.read();
                 if self.is_at_byte_boundary {
-                    b &= 0b0000_1111;
-                    b |= nib << 4;
+                    // The buffer may hold stale data, the low nibble is zero padding until written
+                    b = nib << 4;
                     self.is_at_byte_boundary = false;
+                    self.cursor.write(b);
                 } else {
                     b &= 0b1111_0000;
                     b |= nib & 0b0000_1111;
                     self.is_at_byte_boundary = true;
+                    self.cursor.write(b);
                     self.cursor = self.cursor.add(1);
                 }
-                self.cursor.write(b);
             }
             Ok(())
         }
@@ -150,12 +474,225 @@ impl<'a> NibbleFlavor for NibbleSlice<'a> {
     }
 
     fn finalize(self) -> Result<Self::Output> {
-        let used = (self.cursor as usize) - (self.start as usize);
+        let mut used = (self.cursor as usize) - (self.start as usize);
+        if !self.is_at_byte_boundary {
+            // Include the byte holding the last nibble
+            used += 1;
+        }
         let sli = unsafe { core::slice::from_raw_parts_mut(self.start, used) };
         Ok(sli)
     }
 }
 
+////////////////////////////////////////
+// Double buffer
+////////////////////////////////////////
+
+/// The `NibbleDoubleBuffer` flavor is a storage flavor over two plain `[u8]` slices, for
+/// ping-pong style transmission (e.g. DMA). The first buffer is filled completely before
+/// output continues in the second one, so a message may span both buffers. Concatenating
+/// the two resolved sub-slices gives the same bytes as serializing into a single buffer.
+///
+/// Output only switches to the second buffer at a byte boundary, so no byte is split
+/// between the buffers: a byte whose high nibble ends the first buffer gets its low nibble
+/// there as well.
+pub struct NibbleDoubleBuffer<'a> {
+    first: &'a mut [u8],
+    second: &'a mut [u8],
+    first_used: usize,
+    second_used: usize,
+    in_second: bool,
+    is_at_byte_boundary: bool,
+}
+
+impl<'a> NibbleDoubleBuffer<'a> {
+    /// Create a new `NibbleDoubleBuffer` flavor, filling `first` before `second`
+    pub fn new(first: &'a mut [u8], second: &'a mut [u8]) -> Self {
+        Self {
+            first,
+            second,
+            first_used: 0,
+            second_used: 0,
+            in_second: false,
+            is_at_byte_boundary: true,
+        }
+    }
+
+    /// The active buffer and the number of bytes used in it, switching to the second
+    /// buffer when the first is full and a new byte needs to be started.
+    fn active(&mut self) -> (&mut [u8], &mut usize) {
+        if !self.in_second && self.is_at_byte_boundary && self.first_used == self.first.len() {
+            self.in_second = true;
+        }
+        if self.in_second {
+            (&mut *self.second, &mut self.second_used)
+        } else {
+            (&mut *self.first, &mut self.first_used)
+        }
+    }
+}
+
+impl<'a> NibbleFlavor for NibbleDoubleBuffer<'a> {
+    type Output = (&'a mut [u8], &'a mut [u8]);
+
+    #[inline(always)]
+    fn try_push_u8(&mut self, byte: u8) -> Result<()> {
+        if self.is_at_byte_boundary {
+            let (buf, used) = self.active();
+            let slot = buf.get_mut(*used).ok_or(Error::SerializeBufferFull)?;
+            *slot = byte;
+            *used += 1;
+            Ok(())
+        } else {
+            self.try_push_nib(byte >> 4)?;
+            self.try_push_nib(byte & 0b0000_1111)
+        }
+    }
+
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        let at_boundary = self.is_at_byte_boundary;
+        let (buf, used) = self.active();
+        if at_boundary {
+            let slot = buf.get_mut(*used).ok_or(Error::SerializeBufferFull)?;
+            *slot = nib << 4;
+            *used += 1;
+        } else {
+            buf[*used - 1] |= nib & 0b0000_1111;
+        }
+        self.is_at_byte_boundary = !at_boundary;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_extend(&mut self, mut bytes: &[u8]) -> Result<()> {
+        if !self.is_at_byte_boundary {
+            self.try_push_nib(0)?;
+        }
+        let first_left = self.first.len() - self.first_used;
+        let second_left = self.second.len() - self.second_used;
+        let total_left = if self.in_second {
+            second_left
+        } else {
+            first_left + second_left
+        };
+        if bytes.len() > total_left {
+            return Err(Error::SerializeBufferFull);
+        }
+        if !self.in_second {
+            let (now, rest) = bytes.split_at(first_left.min(bytes.len()));
+            self.first[self.first_used..][..now.len()].copy_from_slice(now);
+            self.first_used += now.len();
+            bytes = rest;
+        }
+        if !bytes.is_empty() {
+            self.in_second = true;
+            self.second[self.second_used..][..bytes.len()].copy_from_slice(bytes);
+            self.second_used += bytes.len();
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok((
+            &mut self.first[..self.first_used],
+            &mut self.second[..self.second_used],
+        ))
+    }
+}
+
+#[cfg(feature = "bbqueue")]
+mod bbq_writer {
+    use super::NibbleFlavor;
+    use crate::{Error, Result};
+    use bbqueue::{GrantW, Producer};
+
+    /// The `NibbleBbqWriter` flavor is a storage flavor, packing the serialized nibbles directly
+    /// into a region granted by a [`bbqueue::Producer`]. The region is granted up front, as the
+    /// size of the message isn't known until it is serialized.
+    ///
+    /// On success the used bytes, including a trailing byte that holds only one nibble, are
+    /// committed to the queue, and their number is returned. If serialization fails the grant
+    /// is dropped without committing anything.
+    ///
+    /// ```rust
+    /// use bbqueue::BBBuffer;
+    /// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::NibbleBbqWriter};
+    ///
+    /// let bb: BBBuffer<16> = BBBuffer::new();
+    /// let (mut prod, mut cons) = bb.try_split().unwrap();
+    ///
+    /// let flavor = NibbleBbqWriter::new(&mut prod, 16).unwrap();
+    /// assert_eq!(serialize_with_nibble_flavor(&(true, 5u8), flavor).unwrap(), 1);
+    ///
+    /// let grant = cons.read().unwrap();
+    /// assert_eq!(&grant[..], &[0x15]);
+    /// grant.release(1);
+    /// ```
+    ///
+    /// This type is only available when the (non-default) `bbqueue` feature is active
+    pub struct NibbleBbqWriter<'a, const N: usize> {
+        grant: GrantW<'a, N>,
+        nibbles: usize,
+    }
+
+    impl<'a, const N: usize> NibbleBbqWriter<'a, N> {
+        /// Create a new `NibbleBbqWriter` flavor, granting up to `max` bytes from `producer`.
+        ///
+        /// Returns [`Error::SerializeBufferFull`] if the queue can't grant any space.
+        pub fn new(producer: &mut Producer<'a, N>, max: usize) -> Result<Self> {
+            let grant = producer
+                .grant_max_remaining(max)
+                .map_err(|_| Error::SerializeBufferFull)?;
+            Ok(Self { grant, nibbles: 0 })
+        }
+    }
+
+    impl<'a, const N: usize> NibbleFlavor for NibbleBbqWriter<'a, N> {
+        type Output = usize;
+
+        #[inline]
+        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            let start = self.nibbles.div_ceil(2);
+            let end = start + data.len();
+            self.grant
+                .get_mut(start..end)
+                .ok_or(Error::SerializeBufferFull)?
+                .copy_from_slice(data);
+            self.nibbles = end * 2;
+            Ok(())
+        }
+
+        #[inline]
+        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+            self.try_push_nib(data >> 4)?;
+            self.try_push_nib(data & 0b0000_1111)
+        }
+
+        #[inline]
+        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+            let high = self.nibbles & 1 == 0;
+            let b = self
+                .grant
+                .get_mut(self.nibbles / 2)
+                .ok_or(Error::SerializeBufferFull)?;
+            if high {
+                // The grant may hold stale data, the low nibble is zero padding until written
+                *b = nib << 4;
+            } else {
+                *b |= nib & 0b0000_1111;
+            }
+            self.nibbles += 1;
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output> {
+            let used = self.nibbles.div_ceil(2);
+            self.grant.commit(used);
+            Ok(used)
+        }
+    }
+}
+
 #[cfg(feature = "heapless")]
 mod heapless_vec {
     use super::NibbleFlavor;
@@ -190,6 +727,61 @@ mod heapless_vec {
         //     Self::default()
         // }
 
+        /// Continue writing `nibble_offset` nibbles into `vec`, keeping the nibbles before it
+        /// and dropping the ones after. At an odd offset, the high nibble of the last byte is
+        /// preserved and the next write fills its low nibble. An offset of 0 starts from an
+        /// empty vec, like `NibbleHVec::default()`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `nibble_offset` is beyond the end of `vec`.
+        pub fn new_at_nibble(mut vec: Vec<u8, B>, nibble_offset: usize) -> Self {
+            assert!(
+                nibble_offset <= vec.len() * 2,
+                "nibble offset beyond the end of the buffer"
+            );
+            vec.truncate(nibble_offset.div_ceil(2));
+            let is_at_byte_boundary = nibble_offset & 1 == 0;
+            if let (false, Some(b)) = (is_at_byte_boundary, vec.last_mut()) {
+                *b &= 0b1111_0000;
+            }
+            Self {
+                vec,
+                is_at_byte_boundary,
+            }
+        }
+
+        /// The number of nibbles written so far
+        pub fn nibbles_written(&self) -> usize {
+            if self.is_at_byte_boundary {
+                self.vec.len() * 2
+            } else {
+                self.vec.len() * 2 - 1
+            }
+        }
+
+        /// The number of bytes written so far, including a partially written last byte
+        pub fn bytes_written(&self) -> usize {
+            self.vec.len()
+        }
+
+        /// The number of bytes in the buffer, including a partially written last byte
+        pub fn len(&self) -> usize {
+            self.vec.len()
+        }
+
+        /// Returns `true` if nothing was written yet
+        pub fn is_empty(&self) -> bool {
+            self.vec.is_empty()
+        }
+
+        /// Empty the buffer, so that it can be reused for the next message. Afterwards the
+        /// flavor is equivalent to a fresh `NibbleHVec::default()`.
+        pub fn clear(&mut self) {
+            self.vec.clear();
+            self.is_at_byte_boundary = true;
+        }
+
         fn align(&mut self) -> Result<()> {
             if !self.is_at_byte_boundary {
                 self.try_push_nib(0)?;
@@ -239,6 +831,23 @@ mod heapless_vec {
             }
         }
 
+        fn try_push_nibbles(&mut self, mut nibs: &[u8]) -> Result<()> {
+            if let (false, Some((first, rest))) = (self.is_at_byte_boundary, nibs.split_first()) {
+                self.try_push_nib(*first)?;
+                nibs = rest;
+            }
+            let mut pairs = nibs.chunks_exact(2);
+            for pair in &mut pairs {
+                self.vec
+                    .push((pair[0] << 4) | (pair[1] & 0b0000_1111))
+                    .map_err(|_| Error::SerializeBufferFull)?;
+            }
+            if let Some(last) = pairs.remainder().first() {
+                self.try_push_nib(*last)?;
+            }
+            Ok(())
+        }
+
         fn finalize(self) -> Result<Vec<u8, B>> {
             Ok(self.vec)
         }
@@ -247,89 +856,3347 @@ mod heapless_vec {
 
 #[cfg(feature = "use-std")]
 mod std_vec {
-    /// The `StdVec` flavor is a wrapper type around a `std::vec::Vec`.
+    /// The `NibbleStdVec` flavor is a wrapper type around a `std::vec::Vec`.
     ///
     /// This type is only available when the (non-default) `use-std` feature is active
-    pub type StdVec = super::alloc_vec::AllocVec;
+    pub type NibbleStdVec = super::alloc_vec::NibbleAllocVec;
 }
 
-#[cfg(feature = "alloc")]
-mod alloc_vec {
-    extern crate alloc;
-    use super::Flavor;
-    use super::Index;
-    use super::IndexMut;
+#[cfg(feature = "use-std")]
+mod layout {
+    use super::NibbleFlavor;
+    use crate::vlu32n::Vlu32N;
     use crate::Result;
-    use alloc::vec::Vec;
+    use std::fmt::Write;
+    use std::string::String;
+    use std::vec::Vec;
 
-    /// The `AllocVec` flavor is a wrapper type around an [alloc::vec::Vec].
+    /// The `NibbleLayoutRecorder` flavor records which nibbles every struct field was written
+    /// to, as a human readable layout. Committed next to golden files, the layout shows in
+    /// review exactly which fields moved when the wire format changes.
     ///
-    /// This type is only available when the (non-default) `alloc` feature is active
-    #[derive(Default)]
-    pub struct AllocVec {
-        /// The vec to be used for serialization
-        vec: Vec<u8>,
+    /// Every field gets a line with its path and the half-open range of nibbles it occupies,
+    /// in the order the fields start. Fields of nested structs are joined with dots:
+    ///
+    /// ```text
+    /// id 0..2
+    /// pos 2..5
+    /// pos.x 2..4
+    /// pos.y 4..5
+    /// ```
+    ///
+    /// Only struct fields are recorded, elements of tuples and sequences are part of the
+    /// enclosing field. Booleans and enum variants are passed on to the inner flavor, and
+    /// take the nibbles of their plain encoding in the layout, whatever that flavor makes of
+    /// them. This type is only available when the (non-default) `use-std` feature is active.
+    pub struct NibbleLayoutRecorder<F>
+    where
+        F: NibbleFlavor,
+    {
+        flav: F,
+        nibbles: usize,
+        /// Path and nibble range of every field, the end is filled in when the field ends
+        fields: Vec<(String, usize, usize)>,
+        /// Indices into `fields` of the fields currently being written
+        open: Vec<usize>,
     }
 
-    impl AllocVec {
-        /// Create a new, currently empty, [alloc::vec::Vec] to be used for storing serialized
-        /// output data.
-        pub fn new() -> Self {
-            Self::default()
+    impl<F> NibbleLayoutRecorder<F>
+    where
+        F: NibbleFlavor,
+    {
+        /// Create a new `NibbleLayoutRecorder` modifier Flavor
+        pub fn new(flav: F) -> Self {
+            Self {
+                flav,
+                nibbles: 0,
+                fields: Vec::new(),
+                open: Vec::new(),
+            }
         }
     }
 
-    impl Flavor for AllocVec {
-        type Output = Vec<u8>;
+    impl<F> NibbleFlavor for NibbleLayoutRecorder<F>
+    where
+        F: NibbleFlavor,
+    {
+        type Output = (F::Output, String);
+
+        forward_nibble_hooks! {
+            flav => map_start map_entry map_end serde_call
+        }
+
+        #[inline]
+        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            // Storage flavors align to a whole byte first
+            self.nibbles += (self.nibbles & 1) + data.len() * 2;
+            self.flav.try_extend(data)
+        }
+
+        #[inline]
+        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+            self.nibbles += 2;
+            self.flav.try_push_u8(data)
+        }
+
+        #[inline]
+        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+            self.nibbles += 1;
+            self.flav.try_push_nib(nib)
+        }
+
+        #[inline]
+        fn try_push_bool(&mut self, b: bool) -> Result<()> {
+            self.nibbles += 1;
+            self.flav.try_push_bool(b)
+        }
+
+        #[inline]
+        fn try_push_variant(
+            &mut self,
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+        ) -> Result<()> {
+            self.nibbles += Vlu32N(index).nibble_len();
+            self.flav.try_push_variant(name, index, variant)
+        }
+
+        fn field_start(&mut self, name: &'static str) -> Result<()> {
+            let path = match self.open.last() {
+                Some(&parent) => format!("{}.{}", self.fields[parent].0, name),
+                None => String::from(name),
+            };
+            self.open.push(self.fields.len());
+            self.fields.push((path, self.nibbles, self.nibbles));
+            self.flav.field_start(name)
+        }
+
+        fn field_end(&mut self) -> Result<()> {
+            if let Some(i) = self.open.pop() {
+                self.fields[i].2 = self.nibbles;
+            }
+            self.flav.field_end()
+        }
+
+        fn finalize(self) -> Result<Self::Output> {
+            let mut layout = String::new();
+            for (path, start, end) in &self.fields {
+                // Writing to a String can't fail
+                let _ = writeln!(layout, "{} {}..{}", path, start, end);
+            }
+            Ok((self.flav.finalize()?, layout))
+        }
+    }
+}
+
+#[cfg(feature = "use-std")]
+mod io_writer {
+    use super::NibbleFlavor;
+    use crate::{Error, Result};
+    use std::io::Write;
+
+    /// The `NibbleIOWriter` flavor writes the serialized output straight to a
+    /// [`std::io::Write`], such as a file or socket, without buffering the whole message.
+    ///
+    /// Complete bytes are written as soon as they are known, only a pending high nibble is
+    /// held back until its byte is complete. Any I/O error is reported as
+    /// [`Error::SerializeBufferFull`].
+    ///
+    /// ```rust
+    /// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::NibbleIOWriter};
+    ///
+    /// let out: Vec<u8> = serialize_with_nibble_flavor(&(true, 5u8), NibbleIOWriter::new(Vec::new())).unwrap();
+    /// assert_eq!(out, &[0x15]);
+    /// ```
+    ///
+    /// This type is only available when the (non-default) `use-std` feature is active
+    pub struct NibbleIOWriter<W>
+    where
+        W: Write,
+    {
+        writer: W,
+        /// The high nibble of a byte that is not complete yet
+        pending: Option<u8>,
+    }
+
+    impl<W> NibbleIOWriter<W>
+    where
+        W: Write,
+    {
+        /// Create a new `NibbleIOWriter`, writing to `writer`
+        pub fn new(writer: W) -> Self {
+            Self {
+                writer,
+                pending: None,
+            }
+        }
+
+        /// Pad a partially written byte with a zero nibble, write it and flush the writer,
+        /// without finalizing. Afterwards the output is at a byte boundary, e.g. for bulk byte
+        /// data written to the writer directly. The deserializer skips the padding with
+        /// `try_take_n(0)`.
+        pub fn align_and_flush(&mut self) -> Result<()> {
+            if let Some(high) = self.pending.take() {
+                self.write(&[high << 4])?;
+            }
+            self.writer.flush().map_err(|_| Error::SerializeBufferFull)
+        }
+
+        fn write(&mut self, data: &[u8]) -> Result<()> {
+            self.writer
+                .write_all(data)
+                .map_err(|_| Error::SerializeBufferFull)
+        }
+    }
+
+    impl<W> NibbleFlavor for NibbleIOWriter<W>
+    where
+        W: Write,
+    {
+        type Output = W;
+
+        #[inline]
+        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            // Aligned data completes a partial byte with a zero low nibble
+            if let Some(high) = self.pending.take() {
+                self.write(&[high << 4])?;
+            }
+            self.write(data)
+        }
+
+        #[inline]
+        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+            match self.pending.take() {
+                Some(high) => {
+                    self.pending = Some(data & 0b0000_1111);
+                    self.write(&[(high << 4) | (data >> 4)])
+                }
+                None => self.write(&[data]),
+            }
+        }
+
+        #[inline]
+        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+            match self.pending.take() {
+                Some(high) => self.write(&[(high << 4) | (nib & 0b0000_1111)]),
+                None => {
+                    self.pending = Some(nib & 0b0000_1111);
+                    Ok(())
+                }
+            }
+        }
+
+        fn finalize(mut self) -> Result<Self::Output> {
+            self.align_and_flush()?;
+            Ok(self.writer)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_vec {
+    extern crate alloc;
+    use super::NibbleFlavor;
+    use crate::Result;
+    use alloc::vec::Vec;
+
+    /// The `NibbleAllocVec` flavor is a wrapper type around an [alloc::vec::Vec].
+    ///
+    /// This type is only available when the (non-default) `alloc` feature is active
+    pub struct NibbleAllocVec {
+        /// The vec to be used for serialization
+        vec: Vec<u8>,
+        is_at_byte_boundary: bool,
+    }
+
+    impl Default for NibbleAllocVec {
+        fn default() -> Self {
+            Self {
+                vec: Vec::new(),
+                is_at_byte_boundary: true,
+            }
+        }
+    }
+
+    impl NibbleAllocVec {
+        /// Create a new, currently empty, [alloc::vec::Vec] to be used for storing serialized
+        /// output data.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    impl NibbleFlavor for NibbleAllocVec {
+        type Output = Vec<u8>;
+
+        #[inline(always)]
+        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            // The low nibble of a partially written byte is already zero
+            self.is_at_byte_boundary = true;
+            self.vec.extend_from_slice(data);
+            Ok(())
+        }
+
+        #[inline(always)]
+        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+            if self.is_at_byte_boundary {
+                self.vec.push(data);
+                Ok(())
+            } else {
+                self.try_push_nib(data >> 4)?;
+                self.try_push_nib(data & 0b0000_1111)
+            }
+        }
+
+        #[inline]
+        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+            match self.vec.last_mut() {
+                Some(b) if !self.is_at_byte_boundary => *b |= nib & 0b0000_1111,
+                _ => self.vec.push(nib << 4),
+            }
+            self.is_at_byte_boundary = !self.is_at_byte_boundary;
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output> {
+            Ok(self.vec)
+        }
+    }
+
+    /// The `NibbleAllocVecRef` flavor appends to a borrowed [alloc::vec::Vec], so that several
+    /// messages can be written into one buffer without moving it in and out of a flavor.
+    ///
+    /// [`finalize()`](NibbleFlavor::finalize) returns the number of bytes appended. A message
+    /// ending mid-byte leaves the low nibble of the last byte zero, so the next
+    /// [`new()`](NibbleAllocVecRef::new) starts on a fresh byte and the messages can be read back
+    /// with a [`NibbleSeqReader`](crate::NibbleSeqReader).
+    ///
+    /// This type is only available when the (non-default) `alloc` feature is active
+    pub struct NibbleAllocVecRef<'a> {
+        vec: &'a mut Vec<u8>,
+        is_at_byte_boundary: bool,
+        start_len: usize,
+    }
+
+    impl<'a> NibbleAllocVecRef<'a> {
+        /// Append to `vec`, starting at the next whole byte.
+        pub fn new(vec: &'a mut Vec<u8>) -> Self {
+            Self::new_at_boundary(vec, true)
+        }
+
+        /// Append to `vec`, continuing from a previous write that ended mid-byte if
+        /// `is_at_byte_boundary` is false. In that case the first nibble goes into the low half of
+        /// the last byte, which is cleared first. An empty `vec` is always at a byte boundary.
+        pub fn new_at_boundary(vec: &'a mut Vec<u8>, is_at_byte_boundary: bool) -> Self {
+            let is_at_byte_boundary = match vec.last_mut() {
+                Some(b) if !is_at_byte_boundary => {
+                    *b &= 0b1111_0000;
+                    false
+                }
+                _ => true,
+            };
+            let start_len = vec.len();
+            Self {
+                vec,
+                is_at_byte_boundary,
+                start_len,
+            }
+        }
+
+        /// Whether the next nibble starts a new byte. Pass this to
+        /// [`new_at_boundary()`](NibbleAllocVecRef::new_at_boundary) to continue writing
+        /// without padding.
+        pub fn is_at_byte_boundary(&self) -> bool {
+            self.is_at_byte_boundary
+        }
+    }
+
+    impl<'a> NibbleFlavor for NibbleAllocVecRef<'a> {
+        type Output = usize;
 
         #[inline(always)]
         fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            // The low nibble of a partially written byte is already zero
+            self.is_at_byte_boundary = true;
             self.vec.extend_from_slice(data);
             Ok(())
         }
 
-        #[inline(always)]
-        fn try_push(&mut self, data: u8) -> Result<()> {
-            self.vec.push(data);
-            Ok(())
+        #[inline(always)]
+        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+            if self.is_at_byte_boundary {
+                self.vec.push(data);
+                Ok(())
+            } else {
+                self.try_push_nib(data >> 4)?;
+                self.try_push_nib(data & 0b0000_1111)
+            }
+        }
+
+        #[inline]
+        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+            match self.vec.last_mut() {
+                Some(b) if !self.is_at_byte_boundary => *b |= nib & 0b0000_1111,
+                _ => self.vec.push(nib << 4),
+            }
+            self.is_at_byte_boundary = !self.is_at_byte_boundary;
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output> {
+            Ok(self.vec.len() - self.start_len)
+        }
+    }
+}
+
+////////////////////////////////////////
+// Bit stream
+////////////////////////////////////////
+
+/// The `BitFlavor` is a storage flavor writing a little-endian bit stream into a plain `[u8]`
+/// slice, for protocols with fields that are not a whole number of nibbles wide, such as 3 or 7
+/// bits. Fields written with [`push_bits()`](BitFlavor::push_bits) are packed LSB-first: the
+/// first bit of the stream is the least significant bit of the first byte.
+///
+/// Serialized values can be mixed in, nibbles being written as 4-bit fields. A message of
+/// nibble-sized values therefore comes out as the nibble format with the two halves of every
+/// byte swapped. Byte runs, such as strings and byte slices, start at the next byte and are
+/// copied unchanged, so that the deserialization `BitFlavor` can borrow them.
+///
+/// ```rust
+/// use postcard::ser_nibble_flavors::BitFlavor;
+/// use postcard::serialize_with_nibble_flavor;
+///
+/// let mut buf = [0u8; 4];
+/// let mut bits = BitFlavor::new(&mut buf);
+/// bits.push_bits(0b101, 3).unwrap();
+/// bits.push_bits(0x7F, 7).unwrap();
+/// let used = serialize_with_nibble_flavor(&true, bits).unwrap();
+/// assert_eq!(used, &[0b1111_1101, 0b0000_0111]);
+/// ```
+pub struct BitFlavor<'a> {
+    buf: &'a mut [u8],
+    bits: usize,
+}
+
+impl<'a> BitFlavor<'a> {
+    /// Create a new `BitFlavor` from a given backing buffer
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, bits: 0 }
+    }
+
+    /// Write the low `nbits` bits of `value`, least significant bit first
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbits` is larger than 64.
+    pub fn push_bits(&mut self, value: u64, nbits: u32) -> Result<()> {
+        assert!(nbits <= 64, "cannot push more than 64 bits at once");
+        if self.buf.len() * 8 - self.bits < nbits as usize {
+            return Err(Error::SerializeBufferFull);
+        }
+        let mut value = value;
+        let mut left = nbits as usize;
+        while left > 0 {
+            let offset = self.bits & 7;
+            let take = left.min(8 - offset);
+            let part = (value as u8) & (0xFF >> (8 - take));
+            let byte = &mut self.buf[self.bits / 8];
+            // The buffer may hold stale data, bits above the ones written are zero padding
+            *byte = if offset == 0 {
+                part
+            } else {
+                *byte | (part << offset)
+            };
+            value = value.checked_shr(take as u32).unwrap_or(0);
+            self.bits += take;
+            left -= take;
+        }
+        Ok(())
+    }
+
+    /// The number of bits written so far
+    pub fn bits_written(&self) -> usize {
+        self.bits
+    }
+
+    fn align(&mut self) -> Result<()> {
+        let pad = self.bits.wrapping_neg() & 7;
+        self.push_bits(0, pad as u32)
+    }
+}
+
+impl<'a> NibbleFlavor for BitFlavor<'a> {
+    type Output = &'a mut [u8];
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        // Most significant nibble first, as in the nibble format
+        self.try_push_nib(data >> 4)?;
+        self.try_push_nib(data & 0b0000_1111)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.push_bits(nib as u64, 4)
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.align()?;
+        let start = self.bits / 8;
+        let end = start + data.len();
+        if end > self.buf.len() {
+            return Err(Error::SerializeBufferFull);
+        }
+        self.buf[start..end].copy_from_slice(data);
+        self.bits += data.len() * 8;
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        let used = self.bits.div_ceil(8);
+        Ok(&mut self.buf[..used])
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Modification Flavors
+////////////////////////////////////////////////////////////////////////////////
+
+////////////////////////////////////////
+// Minimum size, front padded
+////////////////////////////////////////
+
+/// Storage outputs that can be grown at the front, as required by [`NibbleMinSizeFront`].
+pub trait PadFront {
+    /// Prepend zero bytes until the output is at least `min_len` bytes long
+    fn pad_front_to(&mut self, min_len: usize) -> Result<()>;
+}
+
+#[cfg(feature = "heapless")]
+impl<const B: usize> PadFront for heapless::Vec<u8, B> {
+    fn pad_front_to(&mut self, min_len: usize) -> Result<()> {
+        let len = self.len();
+        if len < min_len {
+            self.resize_default(min_len)
+                .map_err(|_| Error::SerializeBufferFull)?;
+            self.rotate_right(min_len - len);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PadFront for alloc::vec::Vec<u8> {
+    fn pad_front_to(&mut self, min_len: usize) -> Result<()> {
+        let len = self.len();
+        if len < min_len {
+            self.resize(min_len, 0);
+            self.rotate_right(min_len - len);
+        }
+        Ok(())
+    }
+}
+
+/// The `NibbleMinSizeFront` flavor pads the serialized output with leading zero bytes until
+/// it is at least `min_len` bytes long, so that the payload is right-aligned in the frame.
+/// Outputs that are already long enough are left unchanged.
+///
+/// The amount of padding is only known once serialization is complete, so the padding is
+/// inserted in `finalize` by growing the storage output. This means the inner flavor must
+/// produce a growable buffer, such as [`NibbleHVec`] or `NibbleAllocVec`.
+///
+/// Use `postcard::from_nibbles_min_size_front()` to skip the padding when decoding.
+pub struct NibbleMinSizeFront<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    min_len: usize,
+}
+
+impl<F> NibbleMinSizeFront<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleMinSizeFront` modifier Flavor
+    pub fn new(flav: F, min_len: usize) -> Self {
+        Self { flav, min_len }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleMinSizeFront<F>
+where
+    F: NibbleFlavor,
+    F::Output: PadFront,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav =>
+            try_extend try_push_u8 try_push_nib try_push_bool try_push_variant field_start field_end
+            map_start map_entry map_end serde_call
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        let mut out = self.flav.finalize()?;
+        out.pad_front_to(self.min_len)?;
+        Ok(out)
+    }
+}
+
+/// The nibble written by [`NibbleSeparated`] after every struct field
+pub const FIELD_SEPARATOR: u8 = 0xF;
+
+/// The `NibbleSeparated` flavor is a debugging aid that writes a [`FIELD_SEPARATOR`] nibble
+/// after every struct field, making field boundaries easy to spot when inspecting frames by hand.
+///
+/// The result is not compatible with the regular nibble format, it must be decoded with
+/// `postcard::from_nibbles_separated()` (or the `de_nibble_flavors::NibbleSeparated` flavor),
+/// which checks that every separator is in place.
+pub struct NibbleSeparated<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+}
+
+impl<F> NibbleSeparated<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleSeparated` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self { flav }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleSeparated<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav =>
+            try_extend try_push_u8 try_push_nib try_push_bool try_push_variant field_start map_start
+            map_entry map_end serde_call finalize
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        self.flav.try_push_nib(FIELD_SEPARATOR)?;
+        self.flav.field_end()
+    }
+}
+
+/// The `serde::Serializer` methods reported to [`NibbleFlavor::serde_call`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SerdeCall {
+    /// `serialize_bool`
+    Bool,
+    /// `serialize_i8`
+    I8,
+    /// `serialize_i16`
+    I16,
+    /// `serialize_i32`
+    I32,
+    /// `serialize_i64`
+    I64,
+    /// `serialize_i128`
+    I128,
+    /// `serialize_u8`
+    U8,
+    /// `serialize_u16`
+    U16,
+    /// `serialize_u32`
+    U32,
+    /// `serialize_u64`
+    U64,
+    /// `serialize_u128`
+    U128,
+    /// `serialize_f32`
+    F32,
+    /// `serialize_f64`
+    F64,
+    /// `serialize_char`
+    Char,
+    /// `serialize_str`
+    Str,
+    /// `serialize_bytes`
+    Bytes,
+    /// `serialize_none`
+    None,
+    /// `serialize_some`
+    Some,
+    /// `serialize_unit`
+    Unit,
+    /// `serialize_unit_struct`
+    UnitStruct,
+    /// `serialize_unit_variant`
+    UnitVariant,
+    /// `serialize_newtype_struct`
+    NewtypeStruct,
+    /// `serialize_newtype_variant`
+    NewtypeVariant,
+    /// `serialize_seq`
+    Seq,
+    /// `serialize_tuple`
+    Tuple,
+    /// `serialize_tuple_struct`
+    TupleStruct,
+    /// `serialize_tuple_variant`
+    TupleVariant,
+    /// `serialize_map`
+    Map,
+    /// `serialize_struct`
+    Struct,
+    /// `serialize_struct_variant`
+    StructVariant,
+    /// `collect_str`
+    CollectStr,
+}
+
+impl SerdeCall {
+    /// The number of distinct serializer methods
+    pub const COUNT: usize = 31;
+}
+
+/// The kind of value held by a struct field, as tagged by the [`NibbleFieldTypes`] flavor.
+///
+/// The tags follow serde's data model rather than Rust types, so integers of different widths
+/// share a tag, as do strings and chars serialized with `collect_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NibbleFieldType {
+    /// A `bool`
+    Bool,
+    /// An unsigned integer
+    Unsigned,
+    /// A signed integer
+    Signed,
+    /// An `f32` or `f64`
+    Float,
+    /// A `char`
+    Char,
+    /// A string
+    Str,
+    /// A byte slice
+    Bytes,
+    /// An `Option`
+    Option,
+    /// A unit or unit struct
+    Unit,
+    /// A newtype struct
+    Newtype,
+    /// An enum, of any variant kind
+    Enum,
+    /// A sequence, tuple or tuple struct
+    Seq,
+    /// A map
+    Map,
+    /// A struct
+    Struct,
+}
+
+impl NibbleFieldType {
+    /// The tag nibble of this type
+    pub fn nibble(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<SerdeCall> for NibbleFieldType {
+    fn from(call: SerdeCall) -> Self {
+        match call {
+            SerdeCall::Bool => Self::Bool,
+            SerdeCall::U8 | SerdeCall::U16 | SerdeCall::U32 | SerdeCall::U64 | SerdeCall::U128 => {
+                Self::Unsigned
+            }
+            SerdeCall::I8 | SerdeCall::I16 | SerdeCall::I32 | SerdeCall::I64 | SerdeCall::I128 => {
+                Self::Signed
+            }
+            SerdeCall::F32 | SerdeCall::F64 => Self::Float,
+            SerdeCall::Char => Self::Char,
+            SerdeCall::Str | SerdeCall::CollectStr => Self::Str,
+            SerdeCall::Bytes => Self::Bytes,
+            SerdeCall::None | SerdeCall::Some => Self::Option,
+            SerdeCall::Unit | SerdeCall::UnitStruct => Self::Unit,
+            SerdeCall::NewtypeStruct => Self::Newtype,
+            SerdeCall::UnitVariant
+            | SerdeCall::NewtypeVariant
+            | SerdeCall::TupleVariant
+            | SerdeCall::StructVariant => Self::Enum,
+            SerdeCall::Seq | SerdeCall::Tuple | SerdeCall::TupleStruct => Self::Seq,
+            SerdeCall::Map => Self::Map,
+            SerdeCall::Struct => Self::Struct,
+        }
+    }
+}
+
+/// Per-method tallies gathered by [`NibbleCallCount`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerdeCallCounts {
+    counts: [usize; SerdeCall::COUNT],
+}
+
+impl SerdeCallCounts {
+    /// The number of times the given serializer method was called
+    pub fn get(&self, call: SerdeCall) -> usize {
+        self.counts[call as usize]
+    }
+
+    /// The total number of serializer method calls
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// The `NibbleCallCount` flavor is an instrumentation flavor, which tallies every
+/// `serde::Serializer` method invoked while serializing a value, and passes all data
+/// through to the inner flavor unchanged.
+///
+/// This is useful to find out how much per-type overhead a value brings along. Calls
+/// the serializer makes to itself are not counted, only the ones made by `Serialize` impls,
+/// including the self-described entries of maps with an unknown length.
+pub struct NibbleCallCount<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    counts: SerdeCallCounts,
+}
+
+impl<F> NibbleCallCount<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleCallCount` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            counts: SerdeCallCounts {
+                counts: [0; SerdeCall::COUNT],
+            },
+        }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleCallCount<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = (F::Output, SerdeCallCounts);
+
+    forward_nibble_hooks! {
+        flav =>
+            try_extend try_push_u8 try_push_nib try_push_bool try_push_variant field_start field_end
+            map_start map_entry map_end
+    }
+
+    #[inline]
+    fn serde_call(&mut self, call: SerdeCall) {
+        self.counts.counts[call as usize] += 1;
+        self.flav.serde_call(call)
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok((self.flav.finalize()?, self.counts))
+    }
+}
+
+/// The `NibbleCanonical` flavor makes the output of equal values byte-identical, no matter how
+/// they were constructed, by writing the entries of every map in a fixed order.
+///
+/// Entries are sorted by their encoding rather than by key value, so the order is stable but
+/// not necessarily ascending. Everything else is already deterministic: integers always use
+/// the shortest varint, and padding nibbles are always zero. Floats are encoded bit-exact
+/// (so `0.0` and `-0.0` differ), and sets are encoded as sequences, keeping their iteration order.
+///
+/// While a map is open, everything written to it is recorded in a scratch buffer of `L` bytes,
+/// which needs roughly two to four times the serialized size of the largest top-level map.
+/// Running out of scratch space, or nesting maps more than 16 deep, results in
+/// [`Error::SerializeBufferFull`]. So does using more than 32 different enum variants within
+/// one top-level map, which are recorded by name to hand them on to the inner flavor, e.g.
+/// [`NibbleNamedVariants`], once the map is written out.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub struct NibbleCanonical<F, const L: usize>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// Position independent record of everything written to the open maps
+    log: heapless::Vec<u8, L>,
+    /// Where each open map starts in `log`
+    maps: heapless::Vec<usize, 16>,
+    /// The enum and variant names of the variants in `log`, which only holds their position
+    variants: heapless::Vec<(&'static str, &'static str), 32>,
+}
+
+// Operations recorded in the `NibbleCanonical` log
+#[cfg(feature = "heapless")]
+mod canonical_op {
+    /// Low nibble is the pushed nibble
+    pub const NIB: u8 = 0x00;
+    /// Followed by the pushed byte
+    pub const U8: u8 = 0x10;
+    /// Followed by the length as a little endian u32, and the data
+    pub const EXTEND: u8 = 0x20;
+    pub const FIELD_END: u8 = 0x30;
+    /// Start of a map entry that was not written out yet
+    pub const ENTRY: u8 = 0x40;
+    /// Start of a map entry that was already written out
+    pub const ENTRY_DONE: u8 = 0x41;
+    /// Low bit is the pushed bool
+    pub const BOOL: u8 = 0x50;
+    /// Followed by the variant index as `NIB` operations of its varint, which sort like the
+    /// plain encoding, and the position of its names in `NibbleCanonical::variants`
+    pub const VARIANT: u8 = 0x60;
+
+    /// The length of the operation starting at `log[i]`
+    pub fn len(log: &[u8], i: usize) -> usize {
+        match log[i] & 0xF0 {
+            U8 => 2,
+            EXTEND => {
+                let mut len = [0u8; 4];
+                len.copy_from_slice(&log[i + 1..i + 5]);
+                5 + u32::from_le_bytes(len) as usize
+            }
+            VARIANT => {
+                // The last nibble of the varint has no continuation bit
+                let nibs = log[i + 1..]
+                    .iter()
+                    .take_while(|op| **op & 0x08 != 0)
+                    .count();
+                nibs + 3
+            }
+            _ => 1,
+        }
+    }
+
+    /// The index of the first entry marker at or after `i`, or `end`
+    pub fn next_entry(log: &[u8], mut i: usize, end: usize) -> usize {
+        while i < end && log[i] != ENTRY && log[i] != ENTRY_DONE {
+            i += len(log, i);
+        }
+        i
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<F, const L: usize> NibbleCanonical<F, L>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleCanonical` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            log: heapless::Vec::new(),
+            maps: heapless::Vec::new(),
+            variants: heapless::Vec::new(),
+        }
+    }
+
+    fn logging(&self) -> bool {
+        !self.maps.is_empty()
+    }
+
+    fn log(&mut self, data: &[u8]) -> Result<()> {
+        self.log
+            .extend_from_slice(data)
+            .map_err(|_| Error::SerializeBufferFull)
+    }
+
+    /// Write out the recorded operations in `log[from..to]`, either to the log of the
+    /// enclosing map, or to the inner flavor
+    fn replay(&mut self, from: usize, to: usize) -> Result<()> {
+        let mut i = from;
+        while i < to {
+            let len = canonical_op::len(&self.log, i);
+            if self.logging() {
+                for k in i..i + len {
+                    let b = self.log[k];
+                    self.log.push(b).map_err(|_| Error::SerializeBufferFull)?;
+                }
+            } else {
+                match self.log[i] & 0xF0 {
+                    canonical_op::NIB => self.flav.try_push_nib(self.log[i] & 0x0F)?,
+                    canonical_op::U8 => self.flav.try_push_u8(self.log[i + 1])?,
+                    canonical_op::EXTEND => self.flav.try_extend(&self.log[i + 5..i + len])?,
+                    canonical_op::FIELD_END => self.flav.field_end()?,
+                    canonical_op::BOOL => self.flav.try_push_bool(self.log[i] & 1 == 1)?,
+                    canonical_op::VARIANT => {
+                        let index = self.log[i + 1..i + len - 1]
+                            .iter()
+                            .fold(0u32, |index, nib| (index << 3) | (nib & 0b111) as u32);
+                        let (name, variant) = self.variants[self.log[i + len - 1] as usize];
+                        self.flav.try_push_variant(name, index, variant)?
+                    }
+                    _ => {}
+                }
+            }
+            i += len;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<F, const L: usize> NibbleFlavor for NibbleCanonical<F, L>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => serde_call finalize
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        use core::convert::TryFrom;
+
+        if self.logging() {
+            let len = u32::try_from(data.len()).map_err(|_| Error::TooBigLen)?;
+            self.log(&[canonical_op::EXTEND])?;
+            self.log(&len.to_le_bytes())?;
+            self.log(data)
+        } else {
+            self.flav.try_extend(data)
+        }
+    }
+
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        if self.logging() {
+            self.log(&[canonical_op::U8, data])
+        } else {
+            self.flav.try_push_u8(data)
+        }
+    }
+
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        if self.logging() {
+            self.log(&[canonical_op::NIB | (nib & 0x0F)])
+        } else {
+            self.flav.try_push_nib(nib)
+        }
+    }
+
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        if self.logging() {
+            self.log(&[canonical_op::BOOL | b as u8])
+        } else {
+            self.flav.try_push_bool(b)
+        }
+    }
+
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        if !self.logging() {
+            return self.flav.try_push_variant(name, index, variant);
+        }
+        let names = (name, variant);
+        let pos = match self.variants.iter().position(|v| *v == names) {
+            Some(pos) => pos,
+            None => {
+                self.variants
+                    .push(names)
+                    .map_err(|_| Error::SerializeBufferFull)?;
+                self.variants.len() - 1
+            }
+        };
+        self.log(&[canonical_op::VARIANT])?;
+        for nib in Vlu32N(index).nibbles() {
+            self.log(&[canonical_op::NIB | nib])?;
+        }
+        self.log(&[pos as u8])
+    }
+
+    fn field_start(&mut self, name: &'static str) -> Result<()> {
+        // Field names are not recorded, fields inside of maps are not reported
+        if self.logging() {
+            Ok(())
+        } else {
+            self.flav.field_start(name)
+        }
+    }
+
+    fn field_end(&mut self) -> Result<()> {
+        if self.logging() {
+            self.log(&[canonical_op::FIELD_END])
+        } else {
+            self.flav.field_end()
+        }
+    }
+
+    fn map_start(&mut self) -> Result<()> {
+        self.maps
+            .push(self.log.len())
+            .map_err(|_| Error::SerializeBufferFull)
+    }
+
+    fn map_entry(&mut self) -> Result<()> {
+        self.log(&[canonical_op::ENTRY])
+    }
+
+    fn map_end(&mut self) -> Result<()> {
+        let start = self.maps.pop().ok_or(Error::SerializeBufferFull)?;
+        let end = self.log.len();
+
+        // The length prefix, then the entries, smallest encoding first
+        let first = canonical_op::next_entry(&self.log, start, end);
+        self.replay(start, first)?;
+        loop {
+            let mut smallest: Option<(usize, usize)> = None;
+            let mut i = first;
+            while i < end {
+                let next = canonical_op::next_entry(&self.log, i + 1, end);
+                if self.log[i] == canonical_op::ENTRY {
+                    let is_smaller = match smallest {
+                        Some((s, e)) => self.log[i + 1..next] < self.log[s + 1..e],
+                        None => true,
+                    };
+                    if is_smaller {
+                        smallest = Some((i, next));
+                    }
+                }
+                i = next;
+            }
+            match smallest {
+                Some((i, next)) => {
+                    self.log[i] = canonical_op::ENTRY_DONE;
+                    self.replay(i + 1, next)?;
+                }
+                None => break,
+            }
+        }
+
+        // Drop the recorded map, keeping anything replayed into the enclosing map's log
+        self.log.copy_within(end.., start);
+        let len = self.log.len() - (end - start);
+        self.log.truncate(len);
+        if !self.logging() {
+            self.variants.clear();
+        }
+        Ok(())
+    }
+}
+
+/// The `NibblePackedBools` flavor stores consecutive booleans as single bits, up to four of them
+/// sharing one nibble, instead of using a whole nibble for each one. Anything else written after
+/// a boolean starts at the next nibble, the unused bits are zero.
+///
+/// Neither struct fields nor map entries end a run of booleans. Other modifier flavors that
+/// write at field boundaries, such as [`NibbleSeparated`], must wrap this one rather than the
+/// other way around, and so must `NibbleCanonical`, which reorders map entries.
+///
+/// The bit of the first boolean is the least significant one. The result is not compatible with
+/// the regular nibble format, it must be decoded with `postcard::from_nibbles_packed()` (or the
+/// `de_nibble_flavors::NibblePackedBools` flavor).
+pub struct NibblePackedBools<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    bits: u8,
+    count: u8,
+}
+
+impl<F> NibblePackedBools<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibblePackedBools` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            bits: 0,
+            count: 0,
+        }
+    }
+
+    /// Write out the partially filled nibble of booleans, if any
+    fn flush(&mut self) -> Result<()> {
+        if self.count != 0 {
+            self.flav.try_push_nib(self.bits)?;
+            self.bits = 0;
+            self.count = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<F> NibbleFlavor for NibblePackedBools<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start map_start map_entry map_end serde_call
+    }
+
+    #[inline]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.flush()?;
+        self.flav.try_extend(data)
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.flush()?;
+        self.flav.try_push_u8(data)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.flush()?;
+        self.flav.try_push_nib(nib)
+    }
+
+    #[inline]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        self.bits |= (b as u8) << self.count;
+        self.count += 1;
+        if self.count == 4 {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.flush()?;
+        self.flav.try_push_variant(name, index, variant)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        // Bools of consecutive fields share nibbles, so nothing is written out here
+        self.flav.field_end()
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        self.flush()?;
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// Two-level enum tags
+////////////////////////////////////////
+
+/// A user provided mapping between the variants of some enums and two-level tags, made of a
+/// category nibble and a subtype nibble. Used by [`NibbleTwoLevelTags`] when serializing, and
+/// the `de_nibble_flavors::NibbleTwoLevelTags` flavor when deserializing.
+///
+/// Enums are identified by the name passed to serde, which for derived impls is the name of
+/// the type. Both values of a tag must be in `0..=15`.
+pub trait NibbleTagMap {
+    /// Whether the enum `name` uses two-level tags, other enums keep their varint tags
+    fn is_mapped(&self, name: &'static str) -> bool;
+
+    /// The category and subtype of variant `index` of the mapped enum `name`, or `None` if
+    /// the variant has no tag
+    fn tag(&self, name: &'static str, index: u32) -> Option<(u8, u8)>;
+
+    /// The index of the variant of the mapped enum `name` with the given tag, or `None` if
+    /// no variant has this tag
+    fn index(&self, name: &'static str, category: u8, subtype: u8) -> Option<u32>;
+}
+
+/// The `NibbleTwoLevelTags` flavor writes the discriminants of the enums selected by a
+/// [`NibbleTagMap`] as a fixed-width, two-level tag: a category nibble followed by a subtype
+/// nibble. This fits up to 256 variants in a single byte, and lets a router dispatch on the
+/// category without knowing the subtypes.
+///
+/// Serializing a variant that has no tag, or a tag that does not fit in two nibbles, results
+/// in [`Error::SerdeSerCustom`]. Use this as the outermost modifier flavor, as it writes the
+/// tags through the regular nibble methods of the inner flavor.
+pub struct NibbleTwoLevelTags<F, M>
+where
+    F: NibbleFlavor,
+    M: NibbleTagMap,
+{
+    flav: F,
+    map: M,
+}
+
+impl<F, M> NibbleTwoLevelTags<F, M>
+where
+    F: NibbleFlavor,
+    M: NibbleTagMap,
+{
+    /// Create a new `NibbleTwoLevelTags` modifier Flavor, using `map` for the tags
+    pub fn new(flav: F, map: M) -> Self {
+        Self { flav, map }
+    }
+}
+
+impl<F, M> NibbleFlavor for NibbleTwoLevelTags<F, M>
+where
+    F: NibbleFlavor,
+    M: NibbleTagMap,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav =>
+            try_extend try_push_u8 try_push_nib try_push_bool field_start field_end map_start
+            map_entry map_end serde_call finalize
+    }
+
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        if !self.map.is_mapped(name) {
+            return self.flav.try_push_variant(name, index, variant);
+        }
+        match self.map.tag(name, index) {
+            Some((category, subtype)) if category < 16 && subtype < 16 => {
+                self.flav.try_push_nib(category)?;
+                self.flav.try_push_nib(subtype)
+            }
+            _ => Err(Error::SerdeSerCustom),
+        }
+    }
+}
+
+////////////////////////////////////////
+// Named enum variants
+////////////////////////////////////////
+
+/// The `NibbleNamedVariants` flavor writes enum discriminants as the name of the variant
+/// instead of its index, like serde's externally tagged representation in self-describing
+/// formats: a length prefix followed by the name as an aligned run of bytes, exactly like a
+/// `&str`. The payload of the variant follows as usual.
+///
+/// This costs a lot more space than an index, but lets variants be reordered or added in the
+/// middle without breaking existing messages, and makes them readable in a hex dump. Use this
+/// as the outermost modifier flavor, as it writes the names through the regular methods of the
+/// inner flavor.
+pub struct NibbleNamedVariants<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+}
+
+impl<F> NibbleNamedVariants<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleNamedVariants` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self { flav }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleNamedVariants<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav =>
+            try_extend try_push_u8 try_push_nib try_push_bool field_start field_end map_start
+            map_entry map_end serde_call finalize
+    }
+
+    fn try_push_variant(
+        &mut self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        if variant.len() > u32::MAX as usize {
+            return Err(Error::TooBigLen);
+        }
+        Vlu32N(variant.len() as u32).ser(&mut self.flav)?;
+        self.flav.try_extend(variant.as_bytes())
+    }
+}
+
+////////////////////////////////////////
+// Type ids
+////////////////////////////////////////
+
+/// The `NibbleTyped` flavor prefixes the message with a header of the
+/// [`NIBBLE_WIRE_VERSION`](crate::NIBBLE_WIRE_VERSION) and a type id, both as nibble varints,
+/// so that a generic consumer of a stream of different message types can tell which one it
+/// got, and that it is able to decode it.
+///
+/// The ids are up to the application, the de counterpart `NibbleTyped` hands them to a
+/// [`NibbleTypeRegistry`](crate::de_nibble_flavors::NibbleTypeRegistry) that picks the type
+/// to decode.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleHVec, NibbleTyped}};
+///
+/// let flavor = NibbleTyped::new(NibbleHVec::<8>::default(), 3);
+/// let ser = serialize_with_nibble_flavor(&(true, 5u8), flavor).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x33, 0x15]);
+/// ```
+pub struct NibbleTyped<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// The id, until the header was written in front of the first output
+    pending: Option<u32>,
+}
+
+impl<F> NibbleTyped<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleTyped` modifier Flavor, writing the wire version and `type_id`
+    /// before the message
+    pub fn new(flav: F, type_id: u32) -> Self {
+        Self {
+            flav,
+            pending: Some(type_id),
+        }
+    }
+
+    fn type_id(&mut self) -> Result<()> {
+        match self.pending.take() {
+            Some(id) => {
+                Vlu32N(crate::NIBBLE_WIRE_VERSION as u32).ser(&mut self.flav)?;
+                Vlu32N(id).ser(&mut self.flav)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleTyped<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end map_entry map_end serde_call
+    }
+
+    #[inline]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.type_id()?;
+        self.flav.try_extend(data)
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.type_id()?;
+        self.flav.try_push_u8(data)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.type_id()?;
+        self.flav.try_push_nib(nib)
+    }
+
+    #[inline]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        self.type_id()?;
+        self.flav.try_push_bool(b)
+    }
+
+    #[inline]
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.type_id()?;
+        self.flav.try_push_variant(name, index, variant)
+    }
+
+    #[inline]
+    fn map_start(&mut self) -> Result<()> {
+        // Before the map, so that staging flavors don't move the header into it
+        self.type_id()?;
+        self.flav.map_start()
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        // Messages without any data, such as `()`, still get their header
+        self.type_id()?;
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// Field type tags
+////////////////////////////////////////
+
+/// The `NibbleFieldTypes` flavor writes a type tag nibble in front of every struct field, see
+/// [`NibbleFieldType`] for the tags. The de counterpart `NibbleFieldTypes` compares them to the
+/// types being deserialized, so that a message decoded with the wrong schema fails with
+/// [`Error::DeserializeFieldTypeMismatch`] at the first differing field, instead of silently
+/// producing garbage.
+///
+/// Only the fields themselves are tagged, the contents of options, sequences, etc. are not.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleHVec, NibbleFieldTypes}};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Reading {
+///     valid: bool,
+///     value: u8,
+/// }
+///
+/// let flavor = NibbleFieldTypes::new(NibbleHVec::<8>::default());
+/// let ser = serialize_with_nibble_flavor(&Reading { valid: true, value: 5 }, flavor).unwrap();
+/// // Bool tag, `true`, unsigned tag, 5
+/// assert_eq!(ser.as_slice(), &[0x01, 0x15]);
+/// ```
+pub struct NibbleFieldTypes<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// A field was started, the next serde call tells its type
+    awaiting: bool,
+    /// The tag of the current field, until it was written in front of its data
+    pending: Option<NibbleFieldType>,
+}
+
+impl<F> NibbleFieldTypes<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleFieldTypes` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            awaiting: false,
+            pending: None,
+        }
+    }
+
+    fn tag(&mut self) -> Result<()> {
+        match self.pending.take() {
+            Some(ty) => self.flav.try_push_nib(ty.nibble()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleFieldTypes<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => map_entry map_end finalize
+    }
+
+    #[inline]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.tag()?;
+        self.flav.try_extend(data)
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.tag()?;
+        self.flav.try_push_u8(data)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.tag()?;
+        self.flav.try_push_nib(nib)
+    }
+
+    #[inline]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        self.tag()?;
+        self.flav.try_push_bool(b)
+    }
+
+    #[inline]
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.tag()?;
+        self.flav.try_push_variant(name, index, variant)
+    }
+
+    #[inline]
+    fn field_start(&mut self, name: &'static str) -> Result<()> {
+        // The tag of an enclosing struct field, which has no data of its own before this one
+        self.tag()?;
+        self.awaiting = true;
+        self.flav.field_start(name)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        // Fields without any data, such as `()`, still get their tag
+        self.tag()?;
+        self.flav.field_end()
+    }
+
+    #[inline]
+    fn map_start(&mut self) -> Result<()> {
+        self.tag()?;
+        self.flav.map_start()
+    }
+
+    #[inline]
+    fn serde_call(&mut self, call: SerdeCall) {
+        // Only the outermost call of the field, e.g. `Some` rather than the contained value
+        if self.awaiting {
+            self.awaiting = false;
+            self.pending = Some(call.into());
+        }
+        self.flav.serde_call(call)
+    }
+}
+
+////////////////////////////////////////
+// Budget
+////////////////////////////////////////
+
+/// The `NibbleBudgeted` flavor spreads serializing a message over several steps, writing at
+/// most `budget` nibbles per step, so that a cooperative scheduler gets to run other tasks in
+/// between.
+///
+/// A serde call can't be suspended, so instead every [`step()`](Self::step) serializes the
+/// whole value again, skipping what earlier steps already passed on to the wrapped flavor,
+/// and stops with [`Error::SerializeBudgetExhausted`] once the budget is used up. Each step
+/// has to be given the same value. Byte runs, such as strings, are split across steps, all
+/// other writes are done in one piece, so the budget has to be at least two nibbles.
+///
+/// Booleans and enum variants are passed on to the wrapped flavor as such and count against
+/// the budget with the size of their plain encoding, so a modifier flavor that packs or
+/// names them can be wrapped as well.
+///
+/// The skipped part of the value is still walked every step, so the total work grows with
+/// the number of steps. Pick a budget that makes a step take about as long as you can afford.
+///
+/// ```rust
+/// use postcard::ser_nibble_flavors::{NibbleBudgeted, NibbleFlavor, NibbleHVec};
+/// use postcard::Error;
+///
+/// let value = (7u8, "a somewhat longer string");
+/// let mut flavor = NibbleBudgeted::new(NibbleHVec::<32>::default(), 16);
+/// let mut steps = 1;
+/// while flavor.step(&value) == Err(Error::SerializeBudgetExhausted) {
+///     // Let other tasks run
+///     steps += 1;
+/// }
+/// assert_eq!(steps, 4);
+/// let ser = flavor.finalize().unwrap();
+/// assert_eq!(postcard::from_nibbles::<(u8, &str)>(&ser).unwrap(), value);
+/// ```
+pub struct NibbleBudgeted<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    budget: usize,
+    /// Nibbles written in the current step
+    used: usize,
+    /// Calls made by the current step so far
+    call: usize,
+    /// Calls completed by earlier steps, which are skipped
+    done: usize,
+    /// Bytes of the next call, a byte run, that were already written
+    partial: usize,
+    /// The current step ran out of budget
+    exhausted: bool,
+}
+
+impl<F> NibbleBudgeted<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleBudgeted` modifier Flavor, writing at most `budget` nibbles per
+    /// step. The budget has to be at least 2.
+    pub fn new(flav: F, budget: usize) -> Self {
+        Self {
+            flav,
+            budget: budget.max(2),
+            used: 0,
+            call: 0,
+            done: 0,
+            partial: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Serialize `value`, continuing where the previous step ran out of budget.
+    ///
+    /// Returns [`Error::SerializeBudgetExhausted`] if there is more to write, in which case
+    /// `step()` has to be called again with the same value. Once it returns `Ok`, the
+    /// message is complete and the flavor can be finalized.
+    pub fn step<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.used = 0;
+        self.call = 0;
+        self.exhausted = false;
+        let mut serializer = NibbleSerializer { output: &mut *self };
+        let res = value.serialize(&mut serializer);
+        match res {
+            // The serializer reports some flavor errors as a full buffer
+            Err(_) if self.exhausted => Err(Error::SerializeBudgetExhausted),
+            res => res,
+        }
+    }
+
+    /// The number of nibbles written by the current step
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// The wrapped flavor, to take out what the steps so far have written
+    pub(crate) fn flavor_mut(&mut self) -> &mut F {
+        &mut self.flav
+    }
+
+    /// Count a call, returns `true` if it was completed by an earlier step
+    fn replay(&mut self) -> bool {
+        self.call += 1;
+        self.call <= self.done
+    }
+
+    /// Make room for `nibbles` nibbles in the budget of this step
+    fn spend(&mut self, nibbles: usize) -> Result<()> {
+        if self.used + nibbles > self.budget {
+            self.exhausted = true;
+            return Err(Error::SerializeBudgetExhausted);
+        }
+        self.used += nibbles;
+        self.done += 1;
+        Ok(())
+    }
+}
+
+impl<F> NibbleFlavor for NibbleBudgeted<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => finalize
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        let rest = &data[self.partial..];
+        let fit = rest.len().min((self.budget - self.used) / 2);
+        // An empty run still aligns the output
+        if fit > 0 || data.is_empty() {
+            self.flav.try_extend(&rest[..fit])?;
+        }
+        self.used += fit * 2;
+        self.partial += fit;
+        if self.partial < data.len() {
+            self.exhausted = true;
+            return Err(Error::SerializeBudgetExhausted);
+        }
+        self.partial = 0;
+        self.done += 1;
+        Ok(())
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(2)?;
+        self.flav.try_push_u8(data)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(1)?;
+        self.flav.try_push_nib(nib)
+    }
+
+    #[inline]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(1)?;
+        self.flav.try_push_bool(b)
+    }
+
+    #[inline]
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(Vlu32N(index).nibble_len())?;
+        self.flav.try_push_variant(name, index, variant)
+    }
+
+    #[inline]
+    fn field_start(&mut self, name: &'static str) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(0)?;
+        self.flav.field_start(name)
+    }
+
+    #[inline]
+    fn field_end(&mut self) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(0)?;
+        self.flav.field_end()
+    }
+
+    #[inline]
+    fn map_start(&mut self) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(0)?;
+        self.flav.map_start()
+    }
+
+    #[inline]
+    fn map_entry(&mut self) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(0)?;
+        self.flav.map_entry()
+    }
+
+    #[inline]
+    fn map_end(&mut self) -> Result<()> {
+        if self.replay() {
+            return Ok(());
+        }
+        self.spend(0)?;
+        self.flav.map_end()
+    }
+
+    #[inline]
+    fn serde_call(&mut self, call: SerdeCall) {
+        if !self.replay() {
+            self.done += 1;
+            self.flav.serde_call(call)
+        }
+    }
+}
+
+/// The number of bytes a [`NibbleChunk`] holds
+pub(crate) const NIBBLE_CHUNK_LEN: usize = 32;
+
+/// A storage flavor holding the whole bytes written by one step of a [`NibbleBudgeted`] flavor
+/// with a budget of [`NIBBLE_CHUNK_LEN`] - 1 nibbles, until they are taken out. A started
+/// byte is kept back, to be completed by the next step.
+///
+/// Every nibble can be followed by a padding nibble aligning a byte run, so together with a
+/// started byte carried over, one step writes at most `NIBBLE_CHUNK_LEN` bytes.
+pub(crate) struct NibbleChunk {
+    buf: [u8; NIBBLE_CHUNK_LEN],
+    len: usize,
+    /// The high nibble of a started byte
+    pending: Option<u8>,
+}
+
+impl NibbleChunk {
+    pub(crate) fn new() -> Self {
+        Self {
+            buf: [0; NIBBLE_CHUNK_LEN],
+            len: 0,
+            pending: None,
+        }
+    }
+
+    /// The whole bytes written since the last call, with a started byte zero padded if `flush`
+    /// is set, as the message is complete.
+    pub(crate) fn take(&mut self, flush: bool) -> &[u8] {
+        if flush {
+            if let Some(high) = self.pending.take() {
+                self.buf[self.len] = high << 4;
+                self.len += 1;
+            }
+        }
+        let len = core::mem::replace(&mut self.len, 0);
+        &self.buf[..len]
+    }
+
+    fn push(&mut self, byte: u8) -> Result<()> {
+        let slot = self
+            .buf
+            .get_mut(self.len)
+            .ok_or(Error::SerializeBufferFull)?;
+        *slot = byte;
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl NibbleFlavor for NibbleChunk {
+    type Output = ();
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        if let Some(high) = self.pending.take() {
+            self.push(high << 4)?;
+        }
+        data.iter().try_for_each(|&b| self.push(b))
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        match self.pending {
+            None => self.push(data),
+            Some(_) => {
+                self.try_push_nib(data >> 4)?;
+                self.try_push_nib(data & 0b0000_1111)
+            }
+        }
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        match self.pending.take() {
+            Some(high) => self.push((high << 4) | (nib & 0b0000_1111)),
+            None => {
+                self.pending = Some(nib & 0b0000_1111);
+                Ok(())
+            }
+        }
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok(())
+    }
+}
+
+////////////////////////////////////////
+// COBS
+////////////////////////////////////////
+
+/// The `NibbleCobs` flavor frames the nibble message with [Consistent Overhead Byte Stuffing],
+/// for byte streams where `0x00` delimits frames, such as a UART.
+///
+/// The byte aligned nibble output is COBS encoded, exactly like the byte based `Cobs` flavor
+/// would encode the same bytes, and terminated with a `0x00`. The frame contains no other zero
+/// bytes, so it is self-delimiting. Bytes are passed on to the inner flavor as soon as a block
+/// is complete, which takes up to 254 bytes of buffer in the flavor itself.
+///
+/// The inner flavor only ever sees encoded blocks, so booleans, enum variants and map entries
+/// are not handed to it. Modifiers that change how those are written, such as
+/// `NibbleCanonical` or [`NibblePackedBools`], go around `NibbleCobs` instead, which then
+/// frames their output.
+///
+/// [Consistent Overhead Byte Stuffing]: https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing
+pub struct NibbleCobs<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// The high nibble of a partially written byte
+    pending: Option<u8>,
+    /// The non-zero bytes of the current block
+    block: [u8; 254],
+    len: usize,
+}
+
+impl<F> NibbleCobs<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleCobs` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            pending: None,
+            block: [0; 254],
+            len: 0,
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<()> {
+        if byte == 0 {
+            return self.flush();
+        }
+        self.block[self.len] = byte;
+        self.len += 1;
+        if self.len == self.block.len() {
+            // A full block, which isn't followed by an implicit zero
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write the current block, preceded by its code byte
+    fn flush(&mut self) -> Result<()> {
+        self.flav.try_push_u8(self.len as u8 + 1)?;
+        self.flav.try_extend(&self.block[..self.len])?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl<F> NibbleFlavor for NibbleCobs<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end serde_call
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        if let Some(hi) = self.pending.take() {
+            self.push_byte(hi)?;
+        }
+        data.iter().try_for_each(|b| self.push_byte(*b))
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        match self.pending.take() {
+            Some(hi) => {
+                self.pending = Some(data << 4);
+                self.push_byte(hi | (data >> 4))
+            }
+            None => self.push_byte(data),
+        }
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        match self.pending.take() {
+            Some(hi) => self.push_byte(hi | (nib & 0x0F)),
+            None => {
+                self.pending = Some(nib << 4);
+                Ok(())
+            }
+        }
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        if let Some(hi) = self.pending.take() {
+            self.push_byte(hi)?;
+        }
+        self.flush()?;
+        self.flav.try_push_u8(0)?;
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// Authenticated encryption
+////////////////////////////////////////
+
+/// An AEAD (authenticated encryption with associated data) cipher, as used by the
+/// [`NibbleAead`] flavors. Implement this with a thin adapter around a cipher crate, which
+/// also takes care of the nonce.
+///
+/// The methods mirror the detached in-place methods of the `aead` crate.
+pub trait NibbleAeadCipher {
+    /// The authentication tag produced when encrypting
+    type Tag: AsRef<[u8]>;
+
+    /// The length of [`Tag`](Self::Tag) in bytes
+    const TAG_LEN: usize;
+
+    /// Encrypt `buffer` in place, authenticating it together with `aad`
+    fn encrypt_in_place(&mut self, aad: &[u8], buffer: &mut [u8]) -> Result<Self::Tag>;
+
+    /// Check `tag` against `aad` and the encrypted `buffer`, and decrypt `buffer` in place.
+    ///
+    /// Should return [`Error::DeserializeBadEncoding`] if the data was not authentic.
+    fn decrypt_in_place(&mut self, aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<()>;
+}
+
+/// Storage outputs that can be grown at the back, as required by [`NibbleAead`].
+pub trait PushBack: DerefMut<Target = [u8]> {
+    /// Append `data` to the output
+    fn push_back(&mut self, data: &[u8]) -> Result<()>;
+}
+
+#[cfg(feature = "heapless")]
+impl<const B: usize> PushBack for heapless::Vec<u8, B> {
+    fn push_back(&mut self, data: &[u8]) -> Result<()> {
+        self.extend_from_slice(data)
+            .map_err(|_| Error::SerializeBufferFull)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PushBack for alloc::vec::Vec<u8> {
+    fn push_back(&mut self, data: &[u8]) -> Result<()> {
+        self.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+/// The `NibbleAead` flavor encrypts the serialized message with an AEAD cipher, and appends
+/// the authentication tag. The associated data `aad`, such as a routing header that has to
+/// stay readable, is authenticated together with the message but not encrypted, and is not
+/// part of the output.
+///
+/// The message is serialized into the inner flavor as usual, and encrypted in place in
+/// [`finalize()`](NibbleFlavor::finalize), so the inner flavor has to be a storage flavor that
+/// can grow by the size of the tag, such as [`NibbleHVec`]. Use the
+/// `de_nibble_flavors::NibbleAead` flavor with the same associated data to decode it.
+pub struct NibbleAead<'a, F, A>
+where
+    F: NibbleFlavor,
+    F::Output: PushBack,
+    A: NibbleAeadCipher,
+{
+    flav: F,
+    aad: &'a [u8],
+    cipher: A,
+}
+
+impl<'a, F, A> NibbleAead<'a, F, A>
+where
+    F: NibbleFlavor,
+    F::Output: PushBack,
+    A: NibbleAeadCipher,
+{
+    /// Create a new `NibbleAead` modifier Flavor, authenticating `aad` along with the message
+    pub fn new(flav: F, aad: &'a [u8], cipher: A) -> Self {
+        Self { flav, aad, cipher }
+    }
+}
+
+impl<'a, F, A> NibbleFlavor for NibbleAead<'a, F, A>
+where
+    F: NibbleFlavor,
+    F::Output: PushBack,
+    A: NibbleAeadCipher,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav =>
+            try_extend try_push_u8 try_push_nib try_push_bool try_push_variant field_start field_end
+            map_start map_entry map_end serde_call
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        let mut out = self.flav.finalize()?;
+        let tag = self.cipher.encrypt_in_place(self.aad, &mut out)?;
+        out.push_back(tag.as_ref())?;
+        Ok(out)
+    }
+}
+
+////////////////////////////////////////
+// Parity
+////////////////////////////////////////
+
+/// Protect the nibble `data` with an extended Hamming (8, 4) code: returns the nibble followed
+/// by its check nibble, as a byte. Any two codewords differ in at least four bits.
+pub(crate) fn parity_encode(data: u8) -> u8 {
+    let bit = |n: u8| (data >> n) & 1;
+    let p0 = bit(0) ^ bit(1) ^ bit(3);
+    let p1 = bit(0) ^ bit(2) ^ bit(3);
+    let p2 = bit(1) ^ bit(2) ^ bit(3);
+    let check = p0 | (p1 << 1) | (p2 << 2);
+    // The overall parity, making double bit errors detectable
+    let p3 = ((data & 0x0F).count_ones() + check.count_ones()) as u8 & 1;
+    ((data & 0x0F) << 4) | check | (p3 << 3)
+}
+
+/// Decode a byte written by [`parity_encode`], returning the nibble and whether a bit error
+/// was corrected, or `None` if there are more bit errors than that.
+pub(crate) fn parity_decode(byte: u8) -> Option<(u8, bool)> {
+    (0..16).find_map(|data| match (parity_encode(data) ^ byte).count_ones() {
+        0 => Some((data, false)),
+        1 => Some((data, true)),
+        _ => None,
+    })
+}
+
+/// The `NibbleParity` flavor protects every nibble of the message with a check nibble, for
+/// extremely noisy channels: single bit errors in a nibble and its check nibble are
+/// corrected by the de counterpart `NibbleParity`, double bit errors are detected.
+///
+/// Every nibble becomes a whole byte, with the nibble itself in the high half, so the output
+/// is twice as large as the plain message.
+///
+/// Booleans, enum variants and map entries reach this flavor as plain nibbles, and the inner
+/// flavor only receives the protected bytes. To pack booleans or sort maps, wrap
+/// `NibbleParity` in [`NibblePackedBools`] or `NibbleCanonical`, their output is protected
+/// like any other message then.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleParity, NibbleSlice}};
+///
+/// let mut buf = [0u8; 8];
+/// let used = serialize_with_nibble_flavor(&(true, 7u8), NibbleParity::new(NibbleSlice::new(&mut buf))).unwrap();
+/// assert_eq!(used, &[0x1B, 0x78]);
+/// ```
+pub struct NibbleParity<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// The position in the plain message
+    is_at_byte_boundary: bool,
+}
+
+impl<F> NibbleParity<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleParity` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            is_at_byte_boundary: true,
+        }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleParity<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end serde_call finalize
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        if !self.is_at_byte_boundary {
+            self.try_push_nib(0)?;
+        }
+        data.iter().try_for_each(|b| self.try_push_u8(*b))
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.try_push_nib(data >> 4)?;
+        self.try_push_nib(data & 0x0F)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.is_at_byte_boundary = !self.is_at_byte_boundary;
+        self.flav.try_push_u8(parity_encode(nib))
+    }
+}
+
+////////////////////////////////////////
+// Alignment
+////////////////////////////////////////
+
+/// The `NibbleAligned` flavor pads the message with zero nibbles, so that every byte run, such
+/// as a string or a float, starts at a multiple of `align` bytes, and the output is a multiple
+/// of `align` bytes long. With an `align` of 2 or 4 the runs can be handed to DMA engines that
+/// need 16 or 32 bit aligned buffers. [`align_to()`](Self::align_to) pads to any boundary
+/// mid-stream.
+///
+/// Positions are counted from the start of the message, so this flavor should wrap the storage
+/// flavor directly. The deserialization `NibbleAligned` flavor, created with the same `align`,
+/// skips the padding.
+///
+/// Encoding modifiers like [`NibblePackedBools`], [`NibbleNamedVariants`] or `NibbleCanonical`
+/// go on the outside as well. This flavor writes booleans and variants in the plain encoding
+/// itself and pads whatever it is given, so it never hands them on to the storage flavor.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleAligned, NibbleSlice}};
+///
+/// let mut buf = [0u8; 16];
+/// let used = serialize_with_nibble_flavor(&(7u8, "hi"), NibbleAligned::new(NibbleSlice::new(&mut buf), 4)).unwrap();
+/// assert_eq!(used, &[0x72, 0x00, 0x00, 0x00, b'h', b'i', 0x00, 0x00]);
+/// ```
+pub struct NibbleAligned<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// The boundary for byte runs and the end of the message, in nibbles
+    boundary: usize,
+    /// The number of nibbles written so far
+    nibbles: usize,
+}
+
+impl<F> NibbleAligned<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleAligned` modifier Flavor, aligning to `align` bytes. An `align` of
+    /// zero is treated as one, which is the alignment of the plain format.
+    pub fn new(flav: F, align: usize) -> Self {
+        Self {
+            flav,
+            boundary: align.max(1) * 2,
+            nibbles: 0,
+        }
+    }
+
+    /// Pad with zero nibbles up to the next multiple of `bytes` bytes, counted from the start
+    /// of the message
+    pub fn align_to(&mut self, bytes: usize) -> Result<()> {
+        let boundary = bytes.max(1) * 2;
+        while !self.nibbles.is_multiple_of(boundary) {
+            self.try_push_nib(0)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F> NibbleFlavor for NibbleAligned<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end serde_call
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.align_to(self.boundary / 2)?;
+        self.nibbles += data.len() * 2;
+        self.flav.try_extend(data)
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.nibbles += 2;
+        self.flav.try_push_u8(data)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.nibbles += 1;
+        self.flav.try_push_nib(nib)
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        self.align_to(self.boundary / 2)?;
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// XOR obfuscation
+////////////////////////////////////////
+
+/// The `NibbleXor` flavor obfuscates the message by XORing every byte with a repeating key,
+/// the byte at position `i` with `key[i % key.len()]`. This keeps casual observers of a link
+/// from reading the data, but is no encryption: anyone knowing a plain message and its
+/// obfuscated bytes can recover the key. Use [`NibbleAead`] to protect data.
+///
+/// Nibbles are XORed with the matching half of their key byte, so the output is exactly the
+/// plain message XORed with the key, including a padding nibble completing the last byte. An
+/// empty key leaves the message unchanged. The deserialization `NibbleXor` flavor, or
+/// `from_nibbles_xor()`, restores it.
+///
+/// Only XORed data reaches the inner flavor, booleans and enum variants included, which are
+/// obfuscated in their plain encoding. Put [`NibblePackedBools`] or another encoding modifier
+/// around `NibbleXor` to change that encoding.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleXor, NibbleSlice}};
+///
+/// let mut buf = [0u8; 4];
+/// let used = serialize_with_nibble_flavor(&(true, 7u8, "a"), NibbleXor::new(NibbleSlice::new(&mut buf), &[0xFF])).unwrap();
+/// assert_eq!(used, &[0x17 ^ 0xFF, 0x10 ^ 0xFF, b'a' ^ 0xFF]);
+/// ```
+pub struct NibbleXor<'k, F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    key: &'k [u8],
+    /// The number of nibbles written so far
+    nibbles: usize,
+}
+
+impl<'k, F> NibbleXor<'k, F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleXor` modifier Flavor, obfuscating with `key`
+    pub fn new(flav: F, key: &'k [u8]) -> Self {
+        Self {
+            flav,
+            key,
+            nibbles: 0,
+        }
+    }
+
+    /// The key byte for the byte at the current position
+    fn key_byte(&self) -> u8 {
+        match self.key.len() {
+            0 => 0,
+            len => self.key[(self.nibbles / 2) % len],
+        }
+    }
+}
+
+impl<'k, F> NibbleFlavor for NibbleXor<'k, F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end serde_call
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        // The padding nibble is obfuscated as well, so align here rather than in the inner
+        // flavor
+        if self.nibbles & 1 != 0 {
+            self.try_push_nib(0)?;
+        }
+        data.iter().try_for_each(|b| self.try_push_u8(*b))
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        if self.nibbles & 1 != 0 {
+            self.try_push_nib(data >> 4)?;
+            return self.try_push_nib(data & 0x0F);
+        }
+        let key = self.key_byte();
+        self.nibbles += 2;
+        self.flav.try_push_u8(data ^ key)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        let key = self.key_byte();
+        let key = if self.nibbles & 1 == 0 {
+            key >> 4
+        } else {
+            key & 0x0F
+        };
+        self.nibbles += 1;
+        self.flav.try_push_nib((nib ^ key) & 0x0F)
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        if self.nibbles & 1 != 0 {
+            self.try_push_nib(0)?;
+        }
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// String interning
+////////////////////////////////////////
+
+/// The `NibbleStringIntern` flavor writes every distinct string only once, for messages that
+/// repeat the same strings many times, such as names or units.
+///
+/// The strings form a manifest, in the order they first appear in the message. Every string is
+/// preceded by a nibble varint: 0 for a string that is not in the manifest yet, followed by the
+/// string as usual, which then becomes the next manifest entry. A repeated string is written as
+/// the number of its manifest entry, counting from 1, and nothing else. The
+/// deserialization `NibbleStringIntern` flavor builds the same manifest while decoding.
+///
+/// Strings written with `collect_str` are always added as new entries. Byte slices are not
+/// interned. The manifest is searched linearly, so the flavor suits messages with a modest
+/// number of distinct strings.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleStringIntern, NibbleSlice}};
+///
+/// let mut buf = [0u8; 16];
+/// let data = ["degC", "degC", "degC"];
+/// let used = serialize_with_nibble_flavor(&data, NibbleStringIntern::new(NibbleSlice::new(&mut buf))).unwrap();
+/// // A new string of length 4, then twice the first manifest entry
+/// assert_eq!(used, &[0x04, b'd', b'e', b'g', b'C', 0x11]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub struct NibbleStringIntern<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    manifest: alloc::vec::Vec<alloc::vec::Vec<u8>>,
+    state: InternState,
+}
+
+/// Where the `NibbleStringIntern` flavor is within a string
+#[cfg(feature = "alloc")]
+enum InternState {
+    /// Outside of strings
+    Idle,
+    /// Within the length of a string, which is written once the string is known
+    Length,
+    /// At the start of a string written by `collect_str`, before its tag
+    CollectStart,
+    /// Within a string written by `collect_str`, which is appended to the last manifest entry
+    Collect,
+}
+
+#[cfg(feature = "alloc")]
+impl<F> NibbleStringIntern<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleStringIntern` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            manifest: alloc::vec::Vec::new(),
+            state: InternState::Idle,
+        }
+    }
+
+    /// The number of distinct strings written so far
+    pub fn interned(&self) -> usize {
+        self.manifest.len()
+    }
+
+    fn collect_tag(&mut self) -> Result<()> {
+        if let InternState::CollectStart = self.state {
+            self.state = InternState::Collect;
+            Vlu32N(0).ser(&mut self.flav)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<F> NibbleFlavor for NibbleStringIntern<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav =>
+            try_push_bool try_push_variant field_start field_end map_start map_entry map_end
+            finalize
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.collect_tag()?;
+        match self.state {
+            InternState::Idle => self.flav.try_extend(data),
+            InternState::Length => {
+                self.state = InternState::Idle;
+                match self.manifest.iter().position(|s| s[..] == *data) {
+                    Some(i) => Vlu32N(i as u32 + 1).ser(&mut self.flav),
+                    None => {
+                        Vlu32N(0).ser(&mut self.flav)?;
+                        Vlu32N(data.len() as u32).ser(&mut self.flav)?;
+                        self.flav.try_extend(data)?;
+                        self.manifest.push(data.to_vec());
+                        Ok(())
+                    }
+                }
+            }
+            InternState::CollectStart | InternState::Collect => {
+                if let Some(s) = self.manifest.last_mut() {
+                    s.extend_from_slice(data);
+                }
+                self.flav.try_extend(data)
+            }
+        }
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.collect_tag()?;
+        match self.state {
+            // The length is known from the string itself
+            InternState::Length => Ok(()),
+            _ => self.flav.try_push_u8(data),
+        }
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.collect_tag()?;
+        match self.state {
+            InternState::Length => Ok(()),
+            _ => self.flav.try_push_nib(nib),
+        }
+    }
+
+    fn serde_call(&mut self, call: SerdeCall) {
+        // Every value starts with a call, which also ends a collected string
+        self.state = match call {
+            SerdeCall::Str => InternState::Length,
+            SerdeCall::CollectStr => {
+                self.manifest.push(alloc::vec::Vec::new());
+                InternState::CollectStart
+            }
+            _ => InternState::Idle,
+        };
+        self.flav.serde_call(call)
+    }
+}
+
+////////////////////////////////////////
+// Line safe text
+////////////////////////////////////////
+
+/// The character encoding the nibble `0` in the [`NibbleLineSafe`] format, the nibble `n` is
+/// encoded as `LINE_SAFE_BASE + n`, i.e. one of `a` to `p`.
+pub const LINE_SAFE_BASE: u8 = b'a';
+
+/// The `NibbleLineSafe` flavor writes the message as a line of text, for line based protocols
+/// where frames are delimited by `\n`, such as a serial console.
+///
+/// Every nibble becomes one lowercase letter from `a` to `p`, see [`LINE_SAFE_BASE`], and the
+/// frame ends with a `\n`. The frame contains no control characters, whitespace or other line
+/// breaks, so it passes through terminals and line buffered readers unchanged. The output is
+/// twice as large as the plain message, the same as hex, but decoding needs no lookup table
+/// and a partially received line is never mistaken for a frame.
+///
+/// The inner flavor is given letters, so booleans, enum variants and map entries stop here
+/// and are written as plain nibbles. Modifiers that encode them differently, e.g.
+/// [`NibbleNamedVariants`], belong outside of `NibbleLineSafe`, which then turns their
+/// output into text.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleLineSafe, NibbleSlice}};
+///
+/// let mut buf = [0u8; 8];
+/// let used = serialize_with_nibble_flavor(&(true, 7u8), NibbleLineSafe::new(NibbleSlice::new(&mut buf))).unwrap();
+/// assert_eq!(used, b"bh\n");
+/// ```
+pub struct NibbleLineSafe<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    /// The position in the plain message
+    is_at_byte_boundary: bool,
+}
+
+impl<F> NibbleLineSafe<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleLineSafe` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            is_at_byte_boundary: true,
+        }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleLineSafe<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end serde_call
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        if !self.is_at_byte_boundary {
+            self.try_push_nib(0)?;
+        }
+        data.iter().try_for_each(|b| self.try_push_u8(*b))
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.try_push_nib(data >> 4)?;
+        self.try_push_nib(data & 0x0F)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.is_at_byte_boundary = !self.is_at_byte_boundary;
+        self.flav.try_push_u8(LINE_SAFE_BASE + (nib & 0x0F))
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        self.flav.try_push_u8(b'\n')?;
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// Fletcher-16
+////////////////////////////////////////
+
+/// A running Fletcher-16 checksum over the bytes of a nibble stream, shared with the
+/// deserialization flavor, which mirrors every step
+#[derive(Clone)]
+pub(crate) struct Fletcher16 {
+    /// The high nibble of a partially written byte
+    pending: Option<u8>,
+    sum1: u16,
+    sum2: u16,
+}
+
+impl Fletcher16 {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: None,
+            sum1: 0,
+            sum2: 0,
+        }
+    }
+
+    fn update(&mut self, byte: u8) {
+        self.sum1 = (self.sum1 + byte as u16) % 255;
+        self.sum2 = (self.sum2 + self.sum1) % 255;
+    }
+
+    /// Record a nibble
+    pub(crate) fn nib(&mut self, nib: u8) {
+        match self.pending.take() {
+            Some(hi) => self.update(hi | (nib & 0x0F)),
+            None => self.pending = Some(nib << 4),
+        }
+    }
+
+    /// Record a byte
+    pub(crate) fn byte(&mut self, byte: u8) {
+        match self.pending.take() {
+            Some(hi) => {
+                self.pending = Some(byte << 4);
+                self.update(hi | (byte >> 4));
+            }
+            None => self.update(byte),
+        }
+    }
+
+    /// Whether the stream is at a byte boundary
+    pub(crate) fn is_aligned(&self) -> bool {
+        self.pending.is_none()
+    }
+
+    /// Record a run of aligned bytes, after the padding nibble, if any
+    pub(crate) fn run(&mut self, data: &[u8]) {
+        if let Some(hi) = self.pending.take() {
+            self.update(hi);
+        }
+        data.iter().for_each(|b| self.update(*b));
+    }
+
+    /// The checksum of all complete bytes, `sum2` in the high byte
+    pub(crate) fn sum(&self) -> u16 {
+        (self.sum2 << 8) | self.sum1
+    }
+}
+
+/// The `NibbleFletcher16` flavor appends a [Fletcher-16] checksum to the message, a lighter
+/// alternative to `crc::NibbleCrc32` for small microcontrollers. It needs neither tables nor
+/// the `use-crc` feature.
+///
+/// The framing is the same as for the CRC flavors: the checksum covers the padded byte stream,
+/// the bytes of the nibble output as they end up in the buffer, including the zero padding
+/// nibbles before byte runs and at the end of the message. The output is aligned, then the
+/// checksum follows in little endian order, i.e. the simple sum first. The deserialization
+/// `NibbleFletcher16` flavor verifies it.
+///
+/// Booleans and enum variants are passed on to the wrapped flavor, which may encode them
+/// differently, like [`NibblePackedBools`] does. The checksum covers them in their plain
+/// encoding regardless, so both sides still agree on it, but it no longer matches the bytes
+/// in the buffer then. Map entries are covered in the order they are serialized, so
+/// [`NibbleCanonical`] has to wrap this flavor rather than the other way round.
+///
+/// ```rust
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::{NibbleFletcher16, NibbleSlice}};
+///
+/// let mut buf = [0u8; 8];
+/// let used = serialize_with_nibble_flavor(&(true, 7u8, false), NibbleFletcher16::new(NibbleSlice::new(&mut buf))).unwrap();
+/// // 0x17 + 0x00, then 0x17 + 0x17
+/// assert_eq!(used, &[0x17, 0x00, 0x17, 0x2E]);
+/// ```
+///
+/// [Fletcher-16]: https://en.wikipedia.org/wiki/Fletcher%27s_checksum
+pub struct NibbleFletcher16<F>
+where
+    F: NibbleFlavor,
+{
+    flav: F,
+    sum: Fletcher16,
+}
+
+impl<F> NibbleFletcher16<F>
+where
+    F: NibbleFlavor,
+{
+    /// Create a new `NibbleFletcher16` modifier Flavor
+    pub fn new(flav: F) -> Self {
+        Self {
+            flav,
+            sum: Fletcher16::new(),
+        }
+    }
+}
+
+impl<F> NibbleFlavor for NibbleFletcher16<F>
+where
+    F: NibbleFlavor,
+{
+    type Output = F::Output;
+
+    forward_nibble_hooks! {
+        flav => field_start field_end map_start map_entry map_end serde_call
+    }
+
+    #[inline]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.sum.run(data);
+        self.flav.try_extend(data)
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, data: u8) -> Result<()> {
+        self.sum.byte(data);
+        self.flav.try_push_u8(data)
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+        self.sum.nib(nib);
+        self.flav.try_push_nib(nib)
+    }
+
+    #[inline]
+    fn try_push_bool(&mut self, b: bool) -> Result<()> {
+        self.sum.nib(b as u8);
+        self.flav.try_push_bool(b)
+    }
+
+    #[inline]
+    fn try_push_variant(
+        &mut self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        Vlu32N(index).nibbles().for_each(|nib| self.sum.nib(nib));
+        self.flav.try_push_variant(name, index, variant)
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        self.sum.run(&[]);
+        self.flav.try_extend(&self.sum.sum().to_le_bytes())?;
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// CRC checkpoints
+////////////////////////////////////////
+
+/// Flavors that interleave CRCs with the nibble data, so that corruption in a large frame is
+/// detected while decoding, instead of only after the whole frame was consumed.
+///
+/// ## Format
+///
+/// The CRC is computed over the bytes of the nibble stream, with the padding nibbles (always
+/// zero) that align byte runs. Once at least `interval` bytes were written since the frame
+/// start or the previous checkpoint, a checkpoint is placed before the next value that starts
+/// at a byte boundary: the running CRC of all data bytes so far, in little endian order. The
+/// checkpoints themselves are not part of the CRC. After the last value, the stream is aligned
+/// and the final CRC is appended.
+///
+/// As checkpoints sit between values, their exact positions depend on the data, but both
+/// sides agree on them without any markers. Values are never split by a checkpoint, so a large
+/// string or byte slice can extend a chunk beyond `interval`.
+///
+/// Booleans and enum variants are handed to the wrapped flavor, which may encode them
+/// differently, e.g. `NibblePackedBools` shares nibbles between booleans. The CRC covers
+/// their plain encoding regardless, which the deserialization flavors rebuild from the decoded
+/// values, so both sides agree on it, but it no longer matches the bytes in the buffer then.
+/// Map entries are covered in the order they are serialized, so `NibbleCanonical` has to wrap
+/// these flavors rather than the other way round.
+///
+/// The `use-crc` feature is required to use this module.
+#[cfg(feature = "use-crc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-crc")))]
+pub mod crc {
+    use crc::Width;
+    use crc::{Crc, Digest, CRC_32_ISO_HDLC};
+    use serde::Serialize;
+
+    use super::{NibbleFlavor, NibbleSlice};
+    use crate::ser::serialize_with_nibble_flavor;
+    use crate::vlu32n::Vlu32N;
+    use crate::Result;
+    use paste::paste;
+
+    /// Tracks how the nibble stream maps onto whole bytes for the CRC, and where
+    /// checkpoints go. Shared with the deserialization flavor, which mirrors every step.
+    #[derive(Clone)]
+    pub(crate) struct StreamBytes {
+        /// The high nibble of a partially written byte
+        pending: Option<u8>,
+        /// Bytes completed since the frame start or last checkpoint
+        since: usize,
+        interval: usize,
+    }
+
+    impl StreamBytes {
+        pub(crate) fn new(interval: usize) -> Self {
+            Self {
+                pending: None,
+                since: 0,
+                interval,
+            }
+        }
+
+        /// Whether a checkpoint goes here, which also restarts the interval if so
+        pub(crate) fn checkpoint(&mut self) -> bool {
+            let due = self.pending.is_none() && self.since != 0 && self.since >= self.interval;
+            if due {
+                self.since = 0;
+            }
+            due
+        }
+
+        /// Record a nibble, returning the byte it completed, if any
+        pub(crate) fn nib(&mut self, nib: u8) -> Option<u8> {
+            match self.pending.take() {
+                Some(hi) => {
+                    self.since += 1;
+                    Some(hi | (nib & 0x0F))
+                }
+                None => {
+                    self.pending = Some(nib << 4);
+                    None
+                }
+            }
+        }
+
+        /// Record a byte, returning the byte it completed
+        pub(crate) fn byte(&mut self, byte: u8) -> u8 {
+            self.since += 1;
+            match self.pending.take() {
+                Some(hi) => {
+                    self.pending = Some(byte << 4);
+                    hi | (byte >> 4)
+                }
+                None => byte,
+            }
+        }
+
+        /// Whether the stream is at a byte boundary
+        pub(crate) fn is_aligned(&self) -> bool {
+            self.pending.is_none()
         }
 
-        fn finalize(self) -> Result<Self::Output> {
-            Ok(self.vec)
+        /// Record the alignment before a byte run, returning the padded byte, if any
+        pub(crate) fn align(&mut self) -> Option<u8> {
+            let hi = self.pending.take()?;
+            self.since += 1;
+            Some(hi)
+        }
+
+        /// Record a run of aligned bytes
+        pub(crate) fn run(&mut self, len: usize) {
+            self.since += len;
         }
     }
 
-    impl Index<usize> for AllocVec {
-        type Output = u8;
+    /// Interleaves CRC checkpoints with the data, see the [module level docs](self).
+    pub struct NibbleCrcCheckpoints<'a, F, W>
+    where
+        F: NibbleFlavor,
+        W: Width,
+    {
+        flav: F,
+        digest: Digest<'a, W>,
+        bytes: StreamBytes,
+    }
 
-        #[inline]
-        fn index(&self, idx: usize) -> &u8 {
-            &self.vec[idx]
+    impl<'a, F, W> NibbleCrcCheckpoints<'a, F, W>
+    where
+        F: NibbleFlavor,
+        W: Width,
+    {
+        /// Create a new `NibbleCrcCheckpoints` modifier Flavor, with a checkpoint after
+        /// roughly every `interval` bytes.
+        pub fn new(flav: F, digest: Digest<'a, W>, interval: usize) -> Self {
+            Self {
+                flav,
+                digest,
+                bytes: StreamBytes::new(interval),
+            }
+        }
+    }
+
+    macro_rules! impl_flavor {
+        ($( $int:ty ),*) => {
+            $(
+                paste! {
+                    impl<'a, F> NibbleCrcCheckpoints<'a, F, $int>
+                    where
+                        F: NibbleFlavor,
+                    {
+                        fn checkpoint(&mut self) -> Result<()> {
+                            if self.bytes.checkpoint() {
+                                let crc = self.digest.clone().finalize();
+                                self.flav.try_extend(&crc.to_le_bytes())?;
+                            }
+                            Ok(())
+                        }
+
+                        fn record(&mut self, nib: u8) {
+                            if let Some(done) = self.bytes.nib(nib) {
+                                self.digest.update(&[done]);
+                            }
+                        }
+                    }
+
+                    impl<'a, F> NibbleFlavor for NibbleCrcCheckpoints<'a, F, $int>
+                    where
+                        F: NibbleFlavor,
+                    {
+                        type Output = F::Output;
+
+                        forward_nibble_hooks! {
+                            flav => field_start field_end map_start map_entry map_end serde_call
+                        }
+
+                        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+                            self.checkpoint()?;
+                            if let Some(padded) = self.bytes.align() {
+                                self.digest.update(&[padded]);
+                            }
+                            self.bytes.run(data.len());
+                            self.digest.update(data);
+                            self.flav.try_extend(data)
+                        }
+
+                        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+                            self.checkpoint()?;
+                            let done = self.bytes.byte(data);
+                            self.digest.update(&[done]);
+                            self.flav.try_push_u8(data)
+                        }
+
+                        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+                            self.checkpoint()?;
+                            self.record(nib);
+                            self.flav.try_push_nib(nib)
+                        }
+
+                        fn try_push_bool(&mut self, b: bool) -> Result<()> {
+                            self.checkpoint()?;
+                            self.record(b as u8);
+                            self.flav.try_push_bool(b)
+                        }
+
+                        fn try_push_variant(
+                            &mut self,
+                            name: &'static str,
+                            index: u32,
+                            variant: &'static str,
+                        ) -> Result<()> {
+                            self.checkpoint()?;
+                            Vlu32N(index).nibbles().for_each(|nib| self.record(nib));
+                            self.flav.try_push_variant(name, index, variant)
+                        }
+
+                        fn finalize(mut self) -> Result<Self::Output> {
+                            if let Some(padded) = self.bytes.align() {
+                                self.digest.update(&[padded]);
+                            }
+                            let crc = self.digest.finalize();
+                            self.flav.try_extend(&crc.to_le_bytes())?;
+                            self.flav.finalize()
+                        }
+                    }
+
+                    /// Serialize a `T` to the given slice in the nibble format, with CRC
+                    /// checkpoints after roughly every `interval` bytes and a final CRC.
+                    ///
+                    /// When successful, this function returns the slice containing the
+                    /// serialized message.
+                    pub fn [<to_nibble_slice_ $int>]<'a, T>(
+                        value: &T,
+                        buf: &'a mut [u8],
+                        digest: Digest<'a, $int>,
+                        interval: usize,
+                    ) -> Result<&'a mut [u8]>
+                    where
+                        T: Serialize + ?Sized,
+                    {
+                        serialize_with_nibble_flavor(
+                            value,
+                            NibbleCrcCheckpoints::new(NibbleSlice::new(buf), digest, interval),
+                        )
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_flavor![u8, u16, u32, u64, u128];
+
+    /// The CRC used by [`NibbleCrc32`]: CRC-32/ISO-HDLC, the common CRC-32 of zlib and Ethernet
+    pub static CRC_32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+    /// Appends a CRC-32 of the whole message, without any checkpoints in between.
+    ///
+    /// The CRC covers the padded byte stream: the bytes of the nibble output as they end up
+    /// in the buffer, including the zero padding nibbles before byte runs and at the end of
+    /// the message. The output is aligned, then the CRC follows in little endian order, so it
+    /// can also be checked without decoding the message. The deserialization `NibbleCrc32`
+    /// flavor verifies it.
+    ///
+    /// That only holds while the wrapped flavor writes booleans and enum variants as usual.
+    /// Flavors such as `NibblePackedBools` change their encoding, the CRC still covers the
+    /// plain one then, as described in the [module level docs](self), and has to be checked
+    /// by decoding.
+    ///
+    /// ```rust
+    /// use postcard::serialize_with_nibble_flavor;
+    /// use postcard::ser_nibble_flavors::{crc::{NibbleCrc32, CRC_32}, NibbleSlice};
+    ///
+    /// let mut buf = [0u8; 8];
+    /// let used = serialize_with_nibble_flavor(&(true, 7u8, false), NibbleCrc32::new(NibbleSlice::new(&mut buf))).unwrap();
+    /// let (data, crc) = used.split_at(2);
+    /// assert_eq!(data, &[0x17, 0x00]);
+    /// assert_eq!(crc, &CRC_32.checksum(data).to_le_bytes());
+    /// ```
+    pub struct NibbleCrc32<F>
+    where
+        F: NibbleFlavor,
+    {
+        flav: NibbleCrcCheckpoints<'static, F, u32>,
+    }
+
+    impl<F> NibbleCrc32<F>
+    where
+        F: NibbleFlavor,
+    {
+        /// Create a new `NibbleCrc32` modifier Flavor
+        pub fn new(flav: F) -> Self {
+            Self {
+                // The final CRC only
+                flav: NibbleCrcCheckpoints::new(flav, CRC_32.digest(), usize::MAX),
+            }
+        }
+    }
+
+    impl<F> NibbleFlavor for NibbleCrc32<F>
+    where
+        F: NibbleFlavor,
+    {
+        type Output = F::Output;
+
+        forward_nibble_hooks! {
+            flav =>
+                try_extend try_push_u8 try_push_nib try_push_bool try_push_variant field_start
+                field_end map_start map_entry map_end serde_call finalize
+        }
+    }
+
+    /// The SLIP byte ending a frame
+    pub const SLIP_END: u8 = 0xC0;
+    /// The SLIP byte starting an escape sequence
+    pub const SLIP_ESC: u8 = 0xDB;
+    /// Follows [`SLIP_ESC`] in place of a [`SLIP_END`] data byte
+    pub const SLIP_ESC_END: u8 = 0xDC;
+    /// Follows [`SLIP_ESC`] in place of a [`SLIP_ESC`] data byte
+    pub const SLIP_ESC_ESC: u8 = 0xDD;
+
+    /// Packs the nibbles into bytes and escapes them as SLIP data
+    struct SlipEscape<F>
+    where
+        F: NibbleFlavor,
+    {
+        flav: F,
+        /// The high nibble of a partially written byte
+        pending: Option<u8>,
+    }
+
+    impl<F> SlipEscape<F>
+    where
+        F: NibbleFlavor,
+    {
+        fn push_byte(&mut self, byte: u8) -> Result<()> {
+            match byte {
+                SLIP_END => {
+                    self.flav.try_push_u8(SLIP_ESC)?;
+                    self.flav.try_push_u8(SLIP_ESC_END)
+                }
+                SLIP_ESC => {
+                    self.flav.try_push_u8(SLIP_ESC)?;
+                    self.flav.try_push_u8(SLIP_ESC_ESC)
+                }
+                _ => self.flav.try_push_u8(byte),
+            }
         }
     }
 
-    impl IndexMut<usize> for AllocVec {
+    impl<F> NibbleFlavor for SlipEscape<F>
+    where
+        F: NibbleFlavor,
+    {
+        type Output = F::Output;
+
+        forward_nibble_hooks! {
+            flav => field_start field_end serde_call
+        }
+
+        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            if let Some(hi) = self.pending.take() {
+                self.push_byte(hi)?;
+            }
+            data.iter().try_for_each(|b| self.push_byte(*b))
+        }
+
+        #[inline]
+        fn try_push_u8(&mut self, data: u8) -> Result<()> {
+            match self.pending.take() {
+                Some(hi) => {
+                    self.pending = Some(data << 4);
+                    self.push_byte(hi | (data >> 4))
+                }
+                None => self.push_byte(data),
+            }
+        }
+
         #[inline]
-        fn index_mut(&mut self, idx: usize) -> &mut u8 {
-            &mut self.vec[idx]
+        fn try_push_nib(&mut self, nib: u8) -> Result<()> {
+            match self.pending.take() {
+                Some(hi) => self.push_byte(hi | (nib & 0x0F)),
+                None => {
+                    self.pending = Some(nib << 4);
+                    Ok(())
+                }
+            }
+        }
+
+        fn finalize(mut self) -> Result<Self::Output> {
+            if let Some(hi) = self.pending.take() {
+                self.push_byte(hi)?;
+            }
+            self.flav.try_push_u8(SLIP_END)?;
+            self.flav.finalize()
         }
     }
-}
 
-////////////////////////////////////////////////////////////////////////////////
-// Modification Flavors
-////////////////////////////////////////////////////////////////////////////////
+    /// Frames the message for [SLIP] links, such as a UART: the output of [`NibbleCrc32`] is
+    /// escaped as SLIP data and terminated with [`SLIP_END`].
+    ///
+    /// Data bytes equal to [`SLIP_END`] or [`SLIP_ESC`] are replaced by [`SLIP_ESC`] followed by
+    /// [`SLIP_ESC_END`] or [`SLIP_ESC_ESC`], so the frame contains no other `SLIP_END` and is
+    /// self-delimiting. The CRC covers the unescaped bytes and is escaped like the data.
+    ///
+    /// The escaping works on whole bytes, so booleans and enum variants are always written in
+    /// their plain encoding here. Flavors that change how those are encoded, such as
+    /// `NibblePackedBools` or `NibbleNamedVariants`, wrap `NibbleSlip` instead, and so does
+    /// `NibbleCanonical`.
+    ///
+    /// ```rust
+    /// use postcard::serialize_with_nibble_flavor;
+    /// use postcard::ser_nibble_flavors::{crc::{NibbleSlip, SLIP_END}, NibbleSlice};
+    ///
+    /// let mut buf = [0u8; 16];
+    /// let used = serialize_with_nibble_flavor(&(true, 7u8, false), NibbleSlip::new(NibbleSlice::new(&mut buf))).unwrap();
+    /// assert_eq!(&used[..2], &[0x17, 0x00]);
+    /// assert_eq!(used.last(), Some(&SLIP_END));
+    /// assert_eq!(used.iter().filter(|b| **b == SLIP_END).count(), 1);
+    /// ```
+    ///
+    /// [SLIP]: https://datatracker.ietf.org/doc/html/rfc1055
+    pub struct NibbleSlip<F>
+    where
+        F: NibbleFlavor,
+    {
+        flav: NibbleCrc32<SlipEscape<F>>,
+    }
+
+    impl<F> NibbleSlip<F>
+    where
+        F: NibbleFlavor,
+    {
+        /// Create a new `NibbleSlip` modifier Flavor
+        pub fn new(flav: F) -> Self {
+            Self {
+                flav: NibbleCrc32::new(SlipEscape {
+                    flav,
+                    pending: None,
+                }),
+            }
+        }
+    }
 
-/// The `Size` flavor is a measurement flavor, which accumulates the number of bytes needed to
-/// serialize the data.
+    impl<F> NibbleFlavor for NibbleSlip<F>
+    where
+        F: NibbleFlavor,
+    {
+        type Output = F::Output;
+
+        forward_nibble_hooks! {
+            flav =>
+                try_extend try_push_u8 try_push_nib try_push_bool try_push_variant field_start
+                field_end map_start map_entry map_end serde_call finalize
+        }
+    }
+}
+
+/// The `NibbleSize` flavor is a measurement flavor, which accumulates the number of nibbles
+/// needed to serialize the data. [`nibble_size()`](crate::nibble_size) and
+/// [`nibble_size_bytes()`](crate::nibble_size_bytes) measure a value with it.
 ///
 /// ```
-/// use postcard::{serialize_with_flavor, ser_flavors};
+/// use postcard::{nibble_size, serialize_with_nibble_flavor, ser_nibble_flavors::NibbleSize};
 ///
-/// let value = false;
-/// let size = serialize_with_flavor(&value, ser_flavors::Size::default()).unwrap();
+/// // The string starts at a byte boundary, after a padding nibble
+/// let value = (false, 7u8, "hi");
+/// let size = serialize_with_nibble_flavor(&value, NibbleSize::default()).unwrap();
 ///
-/// assert_eq!(size, 1);
+/// assert_eq!(size, 8);
+/// assert_eq!(nibble_size(&value).unwrap(), size);
 /// ```
 #[derive(Default)]
 pub struct NibbleSize {
@@ -352,7 +4219,7 @@ impl NibbleFlavor for NibbleSize {
 
     #[inline(always)]
     fn try_extend(&mut self, b: &[u8]) -> Result<()> {
-        self.size_nibbles += b.len() * 2;
+        self.size_nibbles += (self.size_nibbles & 1) + b.len() * 2;
         Ok(())
     }
 
@@ -360,3 +4227,511 @@ impl NibbleFlavor for NibbleSize {
         Ok(self.size_nibbles)
     }
 }
+
+/// The `NibbleFieldCount` flavor is a measurement flavor, which counts the fields of the
+/// outermost struct, or of the outermost struct variant. Fields of nested structs don't count,
+/// and other values have none. [`to_nibble_vec_prefix_versioned()`](crate::to_nibble_vec_prefix_versioned)
+/// writes the count in front of the message.
+///
+/// ```
+/// # use serde::Serialize;
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::NibbleFieldCount};
+///
+/// #[derive(Serialize)]
+/// struct Inner { a: u8, b: u8, c: u8 }
+/// #[derive(Serialize)]
+/// struct Outer { id: u16, inner: Inner }
+///
+/// let value = Outer { id: 1, inner: Inner { a: 1, b: 2, c: 3 } };
+/// assert_eq!(serialize_with_nibble_flavor(&value, NibbleFieldCount::default()).unwrap(), 2);
+/// assert_eq!(serialize_with_nibble_flavor(&(1u8, 2u8), NibbleFieldCount::default()).unwrap(), 0);
+/// ```
+#[derive(Default)]
+pub struct NibbleFieldCount {
+    fields: u32,
+    /// The number of fields currently being written
+    open: usize,
+}
+
+impl NibbleFlavor for NibbleFieldCount {
+    type Output = u32;
+
+    #[inline(always)]
+    fn try_push_u8(&mut self, _b: u8) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_push_nib(&mut self, _nib: u8) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_extend(&mut self, _b: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    fn field_start(&mut self, _name: &'static str) -> Result<()> {
+        if self.open == 0 {
+            self.fields += 1;
+        }
+        self.open += 1;
+        Ok(())
+    }
+
+    fn field_end(&mut self) -> Result<()> {
+        self.open -= 1;
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok(self.fields)
+    }
+}
+
+/// The `NibbleFieldSizes` flavor is a measurement flavor, which records how many nibbles every
+/// struct field takes, to find the fields worth a smaller type, a varint or compression.
+///
+/// The fields are listed by name in the order they start, nested fields following the field
+/// that contains them, whose size includes theirs. Fields of structs within sequences are listed
+/// once per element, so summing the sizes by name gives a histogram over the whole message.
+/// Elements of tuples and sequences are part of the enclosing field.
+///
+/// ```
+/// # use serde::Serialize;
+/// use postcard::{serialize_with_nibble_flavor, ser_nibble_flavors::NibbleFieldSizes};
+///
+/// #[derive(Serialize)]
+/// struct Reading { id: u8, name: &'static str }
+///
+/// let value = Reading { id: 200, name: "temp" };
+/// let sizes = serialize_with_nibble_flavor(&value, NibbleFieldSizes::default()).unwrap();
+/// assert_eq!(sizes, [("id", 3), ("name", 9)]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(Default)]
+pub struct NibbleFieldSizes {
+    nibbles: usize,
+    /// Name and size of every field, the start position until the field ends
+    fields: alloc::vec::Vec<(&'static str, usize)>,
+    /// Indices into `fields` of the fields currently being written
+    open: alloc::vec::Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl NibbleFlavor for NibbleFieldSizes {
+    type Output = alloc::vec::Vec<(&'static str, usize)>;
+
+    #[inline(always)]
+    fn try_push_u8(&mut self, _b: u8) -> Result<()> {
+        self.nibbles += 2;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_push_nib(&mut self, _nib: u8) -> Result<()> {
+        self.nibbles += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_extend(&mut self, b: &[u8]) -> Result<()> {
+        // Storage flavors align to a whole byte first
+        self.nibbles += (self.nibbles & 1) + b.len() * 2;
+        Ok(())
+    }
+
+    fn field_start(&mut self, name: &'static str) -> Result<()> {
+        self.open.push(self.fields.len());
+        self.fields.push((name, self.nibbles));
+        Ok(())
+    }
+
+    fn field_end(&mut self) -> Result<()> {
+        if let Some(i) = self.open.pop() {
+            self.fields[i].1 = self.nibbles - self.fields[i].1;
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok(self.fields)
+    }
+}
+
+/// The `NibbleSizeBounds` flavor is a measurement flavor, which computes the smallest and the
+/// largest number of nibbles that values with the same shape as the serialized one can take.
+///
+/// Integers are assumed to take any value of their type, so a `u16` counts as one to six
+/// nibbles no matter its actual value, and a `char` as one to four bytes. Everything that
+/// changes the shape of a message is taken from the value as-is: enum variants, `Option`s,
+/// and the lengths of strings, byte slices, sequences and maps. Serializing a representative
+/// value, e.g. with empty collections, gives the frame size range to document for a message.
+///
+/// ```
+/// use postcard::nibble_size_bounds;
+///
+/// // Two bools and an u8, which takes one to three nibbles
+/// assert_eq!(nibble_size_bounds(&(true, 7u8, false)).unwrap(), (3, 5));
+/// ```
+#[derive(Default)]
+pub struct NibbleSizeBounds {
+    min: usize,
+    max: usize,
+    /// Set while the pushes of an integer are skipped, as its bounds were already added
+    widened: Option<SerdeCall>,
+}
+
+impl NibbleSizeBounds {
+    /// The bounds of a value serialized by the given serializer method, in nibbles, or `None`
+    /// if its size depends only on its shape.
+    fn widen(call: SerdeCall) -> Option<(usize, usize)> {
+        use crate::varint::varint_max;
+
+        // Nibble varints carry 3 bits per nibble, byte varints 7 bits per byte
+        let bounds = match call {
+            SerdeCall::I8 | SerdeCall::U8 => (1, 3),
+            SerdeCall::U16 => (1, 6),
+            SerdeCall::I16 => (2, 2 * varint_max::<u16>()),
+            SerdeCall::I32 | SerdeCall::U32 => (2, 2 * varint_max::<u32>()),
+            SerdeCall::I64 | SerdeCall::U64 => (1, 22),
+            SerdeCall::I128 | SerdeCall::U128 => (1, 43),
+            // The length prefix, the bytes are added when they are aligned
+            SerdeCall::Char => (1, 1),
+            _ => return None,
+        };
+        Some(bounds)
+    }
+}
+
+impl NibbleFlavor for NibbleSizeBounds {
+    type Output = (usize, usize);
+
+    #[inline]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        // Aligning keeps the order of the bounds, so it can be applied to both
+        self.min += self.min & 1;
+        self.max += self.max & 1;
+        if self.widened.take() == Some(SerdeCall::Char) {
+            self.min += 2;
+            self.max += 2 * 4;
+        } else {
+            self.min += data.len() * 2;
+            self.max += data.len() * 2;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn try_push_u8(&mut self, _data: u8) -> Result<()> {
+        if self.widened.is_none() {
+            self.min += 2;
+            self.max += 2;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn try_push_nib(&mut self, _nib: u8) -> Result<()> {
+        if self.widened.is_none() {
+            self.min += 1;
+            self.max += 1;
+        }
+        Ok(())
+    }
+
+    fn serde_call(&mut self, call: SerdeCall) {
+        // Every value starts with a serializer call, which also ends skipping the previous one
+        self.widened = Self::widen(call).map(|(min, max)| {
+            self.min += min;
+            self.max += max;
+            call
+        });
+    }
+
+    fn finalize(self) -> Result<Self::Output> {
+        Ok((self.min, self.max))
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ser::{serialize_with_nibble_flavor, to_nibble_vec};
+    use heapless::Vec;
+
+    #[test]
+    fn double_buffer_spans_both() {
+        let data = (0x12u8, true, "Hello, nibbles!", 0xC7A5u16, 0x0F0F_0F0Fu32);
+        let expected: Vec<u8, 64> = to_nibble_vec(&data).unwrap();
+
+        // Split at every possible point, including inside a value and an empty first buffer
+        for split in 0..=expected.len() {
+            let mut first = [0u8; 64];
+            let mut second = [0u8; 64];
+            let (a, b) = serialize_with_nibble_flavor(
+                &data,
+                NibbleDoubleBuffer::new(&mut first[..split], &mut second),
+            )
+            .unwrap();
+            assert_eq!(a.len(), split);
+
+            let mut joined: Vec<u8, 64> = Vec::new();
+            joined.extend_from_slice(a).unwrap();
+            joined.extend_from_slice(b).unwrap();
+            assert_eq!(joined, expected);
+
+            let out: (u8, bool, &str, u16, u32) = crate::from_nibbles(&joined).unwrap();
+            assert_eq!(out, data);
+        }
+    }
+
+    #[test]
+    fn double_buffer_full() {
+        let mut first = [0u8; 2];
+        let mut second = [0u8; 2];
+        let res = serialize_with_nibble_flavor(
+            &"Hello",
+            NibbleDoubleBuffer::new(&mut first, &mut second),
+        );
+        assert_eq!(res.err(), Some(Error::SerializeBufferFull));
+    }
+
+    #[test]
+    fn call_count_known_struct() {
+        #[derive(serde::Serialize)]
+        struct Reading<'a> {
+            id: u16,
+            channel: u8,
+            value: Option<u64>,
+            unit: &'a str,
+            raw: [u8; 3],
+        }
+
+        let data = Reading {
+            id: 0x1234,
+            channel: 2,
+            value: Some(7),
+            unit: "mV",
+            raw: [1, 2, 3],
+        };
+        let flavor = NibbleCallCount::new(NibbleHVec::<32>::default());
+        let (out, counts) = serialize_with_nibble_flavor(&data, flavor).unwrap();
+
+        let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+        assert_eq!(out, plain);
+
+        assert_eq!(counts.get(SerdeCall::Struct), 1);
+        assert_eq!(counts.get(SerdeCall::U16), 1);
+        assert_eq!(counts.get(SerdeCall::Some), 1);
+        assert_eq!(counts.get(SerdeCall::U64), 1);
+        assert_eq!(counts.get(SerdeCall::Str), 1);
+        assert_eq!(counts.get(SerdeCall::Tuple), 1);
+        // `channel` and the three array elements
+        assert_eq!(counts.get(SerdeCall::U8), 4);
+        assert_eq!(counts.get(SerdeCall::None), 0);
+        assert_eq!(counts.total(), 10);
+    }
+
+    #[cfg(feature = "use-std")]
+    #[test]
+    fn call_count_described() {
+        #[derive(serde::Serialize)]
+        struct Inner {
+            a: u8,
+            b: Option<bool>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Outer {
+            id: u8,
+            #[serde(flatten)]
+            inner: Inner,
+        }
+
+        let data = Outer {
+            id: 1,
+            inner: Inner {
+                a: 2,
+                b: Some(true),
+            },
+        };
+        let flavor = NibbleCallCount::new(NibbleHVec::<32>::default());
+        let (out, counts) = serialize_with_nibble_flavor(&data, flavor).unwrap();
+
+        let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+        assert_eq!(out, plain);
+
+        // The self-described entries count once each, with the method the impl called
+        assert_eq!(counts.get(SerdeCall::Map), 1);
+        assert_eq!(counts.get(SerdeCall::Str), 3);
+        assert_eq!(counts.get(SerdeCall::U8), 2);
+        assert_eq!(counts.get(SerdeCall::U64), 0);
+        assert_eq!(counts.get(SerdeCall::Some), 1);
+        assert_eq!(counts.get(SerdeCall::Bool), 1);
+        assert_eq!(counts.total(), 8);
+    }
+
+    #[test]
+    fn align_mid_stream() {
+        use crate::de::nibble_flavors::{NibbleFlavor as _, NibbleSlice as DeSlice};
+
+        let mut flavor = NibbleHVec::<8>::default();
+        flavor.try_push_nib(0xA).unwrap();
+        flavor.try_extend(&[]).unwrap();
+        flavor.try_extend(&[0x01, 0x02]).unwrap();
+        flavor.try_push_nib(0xB).unwrap();
+        flavor.try_push_u8(0xCD).unwrap();
+        flavor.try_extend(&[]).unwrap();
+        flavor.try_extend(&[0x03]).unwrap();
+        let out = flavor.finalize().unwrap();
+        assert_eq!(&out, &[0xA0, 0x01, 0x02, 0xBC, 0xD0, 0x03]);
+
+        let mut de = DeSlice::new(&out);
+        assert_eq!(de.try_take_nib(), Ok(0xA));
+        assert_eq!(de.try_take_n(0), Ok(&[][..]));
+        assert_eq!(de.try_take_n(2), Ok(&[0x01, 0x02][..]));
+        assert_eq!(de.try_take_nib(), Ok(0xB));
+        assert_eq!(de.try_take_u8(), Ok(0xCD));
+        assert_eq!(de.try_take_n(0), Ok(&[][..]));
+        assert_eq!(de.try_take_u8(), Ok(0x03));
+    }
+
+    #[test]
+    fn hvec_reuse() {
+        use crate::ser::nibble_serializer::NibbleSerializer;
+        use serde::Serialize;
+
+        // Ends in the middle of a byte, then starts with a nibble
+        let first = (0x12u8, true, 0xC7A5u16);
+        let second = (false, "Hello", 3u8);
+        let fresh_first: Vec<u8, 32> = to_nibble_vec(&first).unwrap();
+        let fresh_second: Vec<u8, 32> = to_nibble_vec(&second).unwrap();
+
+        let mut serializer = NibbleSerializer {
+            output: NibbleHVec::<32>::default(),
+        };
+        first.serialize(&mut serializer).unwrap();
+        assert_eq!(serializer.output.len(), fresh_first.len());
+        assert!(!serializer.output.is_empty());
+
+        serializer.output.clear();
+        assert!(serializer.output.is_empty());
+        assert_eq!(serializer.output.nibbles_written(), 0);
+        second.serialize(&mut serializer).unwrap();
+        assert_eq!(serializer.output.finalize().unwrap(), fresh_second);
+    }
+
+    #[cfg(feature = "use-std")]
+    #[test]
+    fn golden_layout() {
+        #[derive(serde::Serialize)]
+        struct BasicU8S {
+            st: u16,
+            ei: u8,
+            sf: u64,
+            tt: u32,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Outer {
+            id: u8,
+            inner: BasicU8S,
+            tail: bool,
+        }
+
+        let data = BasicU8S {
+            st: 0xABCD,
+            ei: 0x5,
+            sf: 0x1234_4321_ABCD_DCBA,
+            tt: 0xACAC_ACAC,
+        };
+        let (bytes, layout): (Vec<u8, 64>, _) =
+            serialize_with_nibble_flavor(&data, NibbleLayoutRecorder::new(NibbleHVec::default()))
+                .unwrap();
+        let plain: Vec<u8, 64> = to_nibble_vec(&data).unwrap();
+        assert_eq!(bytes, plain);
+        assert_eq!(layout, "st 0..6\nei 6..7\nsf 7..28\ntt 28..38\n");
+
+        // Nested fields get dotted paths, within the range of the enclosing field
+        let data = Outer {
+            id: 3,
+            inner: data,
+            tail: true,
+        };
+        let (_, layout): (Vec<u8, 64>, _) =
+            serialize_with_nibble_flavor(&data, NibbleLayoutRecorder::new(NibbleHVec::default()))
+                .unwrap();
+        assert_eq!(
+            layout,
+            "id 0..1\ninner 1..39\ninner.st 1..7\ninner.ei 7..8\n\
+             inner.sf 8..29\ninner.tt 29..39\ntail 39..40\n"
+        );
+
+        // Variants reach the inner flavor, the layout has them in the plain encoding
+        #[derive(serde::Serialize)]
+        enum Mode {
+            _Idle,
+            Run(u8),
+        }
+
+        #[derive(serde::Serialize)]
+        struct Step {
+            mode: Mode,
+            next: u8,
+        }
+
+        let data = Step {
+            mode: Mode::Run(4),
+            next: 9,
+        };
+        let (bytes, layout): (Vec<u8, 64>, _) = serialize_with_nibble_flavor(
+            &data,
+            NibbleLayoutRecorder::new(NibbleNamedVariants::new(NibbleHVec::default())),
+        )
+        .unwrap();
+        let named: Vec<u8, 64> = crate::to_nibble_vec_named_variants(&data).unwrap();
+        assert_eq!(bytes, named);
+        assert_eq!(layout, "mode 0..2\nnext 2..4\n");
+    }
+
+    #[cfg(feature = "bbqueue")]
+    #[test]
+    fn bbq_writer() {
+        let bb: bbqueue::BBBuffer<16> = bbqueue::BBBuffer::new();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Ends mid-byte, followed by a second message in the same queue
+        let data = (0x12u8, "hi", true);
+        let flavor = NibbleBbqWriter::new(&mut prod, 8).unwrap();
+        assert_eq!(serialize_with_nibble_flavor(&data, flavor).unwrap(), 5);
+        let flavor = NibbleBbqWriter::new(&mut prod, 8).unwrap();
+        assert_eq!(serialize_with_nibble_flavor(&5u8, flavor).unwrap(), 1);
+
+        let expected: Vec<u8, 16> = to_nibble_vec(&data).unwrap();
+        let read = cons.read().unwrap();
+        assert_eq!(&read[..5], &expected[..]);
+        assert_eq!(&read[5..], &[0x50]);
+        let out: (u8, &str, bool) = crate::from_nibbles(&read[..5]).unwrap();
+        assert_eq!(out, data);
+        read.release(6);
+
+        // Grants that are too small fail, without committing anything
+        let flavor = NibbleBbqWriter::new(&mut prod, 2).unwrap();
+        assert_eq!(
+            serialize_with_nibble_flavor(&data, flavor),
+            Err(Error::SerializeBufferFull)
+        );
+        assert!(cons.read().is_err());
+
+        // As do full queues
+        let bb: bbqueue::BBBuffer<8> = bbqueue::BBBuffer::new();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let flavor = NibbleBbqWriter::new(&mut prod, 16).unwrap();
+        assert_eq!(serialize_with_nibble_flavor(&[0u8; 16], flavor).unwrap(), 8);
+        assert!(NibbleBbqWriter::new(&mut prod, 16).is_err());
+        assert_eq!(&cons.read().unwrap()[..], &[0u8; 8]);
+    }
+}
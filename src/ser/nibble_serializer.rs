@@ -1,9 +1,9 @@
 use serde::{ser, Serialize};
 
 use crate::error::{Error, Result};
-use crate::ser::nibble_flavors::NibbleFlavor;
+use crate::ser::nibble_flavors::{NibbleFlavor, SerdeCall};
 use crate::varint::*;
-use crate::vlu32n::Vlu32N;
+use crate::vlu32n::{Vlu128N, Vlu32N, Vlu64N};
 
 /// A `serde` compatible serializer, generic over "Flavors" of serializing plugins.
 ///
@@ -34,29 +34,102 @@ impl<F: NibbleFlavor> NibbleSerializer<F> {
         }
     }
 
-    /// Attempt to push a variably encoded [u128] into the output data stream
+    /// Attempt to push a length prefixed string into the output data stream
     #[inline]
-    pub(crate) fn try_push_varint_u128(&mut self, data: u128) -> Result<()> {
-        let mut buf = [0u8; varint_max::<u128>()];
-        let used_buf = varint_u128(data, &mut buf);
-        // must match deserializer, which takes byte by byte or expose alignment into NibbleFlavor
-        // self.output.try_extend(used_buf)
-        for b in used_buf {
-            self.output.try_push_u8(*b)?;
+    pub(crate) fn try_push_str(&mut self, v: &str) -> Result<()> {
+        self.try_push_bytes(v.as_bytes())
+    }
+
+    /// Attempt to push a length prefixed run of bytes into the output data stream
+    #[inline]
+    pub(crate) fn try_push_bytes(&mut self, v: &[u8]) -> Result<()> {
+        self.try_push_varint_usize(v.len())
+            .map_err(|_| Error::SerializeBufferFull)?;
+        self.output
+            .try_extend(v)
+            .map_err(|_| Error::SerializeBufferFull)
+    }
+
+    /// Attempt to push the `Display` output of `value` as a length prefixed string
+    pub(crate) fn try_push_display<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: core::fmt::Display + ?Sized,
+    {
+        use core::fmt::Write;
+
+        // Unfortunately, we need to know the size of the serialized data before
+        // we can place it into the output. In order to do this, we run the formatting
+        // of the output data TWICE, the first time to determine the length, the
+        // second time to actually format the data
+        //
+        // There are potentially other ways to do this, such as:
+        //
+        // * Reserving a fixed max size, such as 5 bytes, for the length field, and
+        //     leaving non-canonical trailing zeroes at the end. This would work up
+        //     to some reasonable length, but might have some portability vs max size
+        //     tradeoffs, e.g. 64KiB if we pick 3 bytes, or 4GiB if we pick 5 bytes
+        // * Expose some kind of "memmove" capability to flavors, to allow us to
+        //     format into the buffer, then "scoot over" that many times.
+        //
+        // Despite the current approaches downside in speed, it is likely flexible
+        // enough for the rare-ish case where formatting a Debug impl is necessary.
+        // This is better than the previous panicking behavior, and can be improved
+        // in the future.
+        struct CountWriter {
+            ct: usize,
+        }
+        impl Write for CountWriter {
+            fn write_str(&mut self, s: &str) -> core::result::Result<(), core::fmt::Error> {
+                self.ct += s.as_bytes().len();
+                Ok(())
+            }
+        }
+
+        let mut ctr = CountWriter { ct: 0 };
+
+        // This is the first pass through, where we just count the length of the
+        // data that we are given
+        write!(&mut ctr, "{}", value).map_err(|_| Error::CollectStrError)?;
+        let len = ctr.ct;
+        self.try_push_varint_usize(len)
+            .map_err(|_| Error::SerializeBufferFull)?;
+
+        struct FmtWriter<'a, IF>
+        where
+            IF: NibbleFlavor,
+        {
+            output: &'a mut IF,
         }
+        impl<'a, IF> Write for FmtWriter<'a, IF>
+        where
+            IF: NibbleFlavor,
+        {
+            fn write_str(&mut self, s: &str) -> core::result::Result<(), core::fmt::Error> {
+                self.output
+                    .try_extend(s.as_bytes())
+                    .map_err(|_| core::fmt::Error::default())
+            }
+        }
+
+        // This second pass actually inserts the data.
+        let mut fw = FmtWriter {
+            output: &mut self.output,
+        };
+        write!(&mut fw, "{}", value).map_err(|_| Error::CollectStrError)?;
+
         Ok(())
     }
 
-    /// Attempt to push a variably encoded [u64] into the output data stream
+    /// Attempt to push a nibble varint encoded [u128] into the output data stream
+    #[inline]
+    pub(crate) fn try_push_varint_u128(&mut self, data: u128) -> Result<()> {
+        Vlu128N(data).ser(&mut self.output)
+    }
+
+    /// Attempt to push a nibble varint encoded [u64] into the output data stream
     #[inline]
     pub(crate) fn try_push_varint_u64(&mut self, data: u64) -> Result<()> {
-        let mut buf = [0u8; varint_max::<u64>()];
-        let used_buf = varint_u64(data, &mut buf);
-        // self.output.try_extend(used_buf)
-        for b in used_buf {
-            self.output.try_push_u8(*b)?;
-        }
-        Ok(())
+        Vlu64N(data).ser(&mut self.output)
     }
 
     /// Attempt to push a variably encoded [u32] into the output data stream
@@ -100,7 +173,7 @@ where
     type SerializeTuple = Self;
     type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Self;
-    type SerializeMap = Self;
+    type SerializeMap = MapCompound<'a, F>;
     type SerializeStruct = Self;
     type SerializeStructVariant = Self;
 
@@ -111,16 +184,19 @@ where
 
     #[inline]
     fn serialize_bool(self, v: bool) -> Result<()> {
-        self.output.try_push_nib(if v { 1 } else { 0 })
+        self.output.serde_call(SerdeCall::Bool);
+        self.output.try_push_bool(v)
     }
 
     #[inline]
     fn serialize_i8(self, v: i8) -> Result<()> {
-        self.serialize_u8(v.to_le_bytes()[0])
+        self.output.serde_call(SerdeCall::I8);
+        Vlu32N(v.to_le_bytes()[0] as u32).ser(&mut self.output)
     }
 
     #[inline]
     fn serialize_i16(self, v: i16) -> Result<()> {
+        self.output.serde_call(SerdeCall::I16);
         let zzv = zig_zag_i16(v);
         self.try_push_varint_u16(zzv)
             .map_err(|_| Error::SerializeBufferFull)
@@ -128,6 +204,7 @@ where
 
     #[inline]
     fn serialize_i32(self, v: i32) -> Result<()> {
+        self.output.serde_call(SerdeCall::I32);
         let zzv = zig_zag_i32(v);
         self.try_push_varint_u32(zzv)
             .map_err(|_| Error::SerializeBufferFull)
@@ -135,6 +212,7 @@ where
 
     #[inline]
     fn serialize_i64(self, v: i64) -> Result<()> {
+        self.output.serde_call(SerdeCall::I64);
         let zzv = zig_zag_i64(v);
         self.try_push_varint_u64(zzv)
             .map_err(|_| Error::SerializeBufferFull)
@@ -142,6 +220,7 @@ where
 
     #[inline]
     fn serialize_i128(self, v: i128) -> Result<()> {
+        self.output.serde_call(SerdeCall::I128);
         let zzv = zig_zag_i128(v);
         self.try_push_varint_u128(zzv)
             .map_err(|_| Error::SerializeBufferFull)
@@ -149,36 +228,42 @@ where
 
     #[inline]
     fn serialize_u8(self, v: u8) -> Result<()> {
+        self.output.serde_call(SerdeCall::U8);
         let v = Vlu32N(v as u32);
         v.ser(&mut self.output)
     }
 
     #[inline]
     fn serialize_u16(self, v: u16) -> Result<()> {
+        self.output.serde_call(SerdeCall::U16);
         let v = Vlu32N(v as u32);
         v.ser(&mut self.output)
     }
 
     #[inline]
     fn serialize_u32(self, v: u32) -> Result<()> {
+        self.output.serde_call(SerdeCall::U32);
         self.try_push_varint_u32(v)
             .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_u64(self, v: u64) -> Result<()> {
+        self.output.serde_call(SerdeCall::U64);
         self.try_push_varint_u64(v)
             .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_u128(self, v: u128) -> Result<()> {
+        self.output.serde_call(SerdeCall::U128);
         self.try_push_varint_u128(v)
             .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<()> {
+        self.output.serde_call(SerdeCall::F32);
         let buf = v.to_bits().to_le_bytes();
         self.output
             .try_extend(&buf)
@@ -187,6 +272,7 @@ where
 
     #[inline]
     fn serialize_f64(self, v: f64) -> Result<()> {
+        self.output.serde_call(SerdeCall::F64);
         let buf = v.to_bits().to_le_bytes();
         self.output
             .try_extend(&buf)
@@ -195,33 +281,27 @@ where
 
     #[inline]
     fn serialize_char(self, v: char) -> Result<()> {
+        self.output.serde_call(SerdeCall::Char);
         let mut buf = [0u8; 4];
-        let strsl = v.encode_utf8(&mut buf);
-        strsl.serialize(self)
+        self.try_push_str(v.encode_utf8(&mut buf))
     }
 
     #[inline]
     fn serialize_str(self, v: &str) -> Result<()> {
-        self.try_push_varint_usize(v.len())
-            .map_err(|_| Error::SerializeBufferFull)?;
-        self.output
-            .try_extend(v.as_bytes())
-            .map_err(|_| Error::SerializeBufferFull)?;
-        Ok(())
+        self.output.serde_call(SerdeCall::Str);
+        self.try_push_str(v)
     }
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        self.try_push_varint_usize(v.len())
-            .map_err(|_| Error::SerializeBufferFull)?;
-        self.output
-            .try_extend(v)
-            .map_err(|_| Error::SerializeBufferFull)
+        self.output.serde_call(SerdeCall::Bytes);
+        self.try_push_bytes(v)
     }
 
     #[inline]
     fn serialize_none(self) -> Result<()> {
-        self.serialize_u8(0)
+        self.output.serde_call(SerdeCall::None);
+        Vlu32N(0).ser(&mut self.output)
     }
 
     #[inline]
@@ -229,29 +309,32 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.serialize_u8(1)?;
+        self.output.serde_call(SerdeCall::Some);
+        Vlu32N(1).ser(&mut self.output)?;
         value.serialize(self)
     }
 
     #[inline]
     fn serialize_unit(self) -> Result<()> {
+        self.output.serde_call(SerdeCall::Unit);
         Ok(())
     }
 
     #[inline]
     fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.output.serde_call(SerdeCall::UnitStruct);
         Ok(())
     }
 
     #[inline]
     fn serialize_unit_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<()> {
-        let v = Vlu32N(variant_index);
-        v.ser(&mut self.output)
+        self.output.serde_call(SerdeCall::UnitVariant);
+        self.output.try_push_variant(name, variant_index, variant)
     }
 
     #[inline]
@@ -259,27 +342,29 @@ where
     where
         T: ?Sized + Serialize,
     {
+        self.output.serde_call(SerdeCall::NewtypeStruct);
         value.serialize(self)
     }
 
     #[inline]
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        let v = Vlu32N(variant_index);
-        v.ser(&mut self.output)?;
+        self.output.serde_call(SerdeCall::NewtypeVariant);
+        self.output.try_push_variant(name, variant_index, variant)?;
         value.serialize(self)
     }
 
     #[inline]
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.output.serde_call(SerdeCall::Seq);
         self.try_push_varint_usize(len.ok_or(Error::SerializeSeqLengthUnknown)?)
             .map_err(|_| Error::SerializeBufferFull)?;
         Ok(self)
@@ -287,6 +372,7 @@ where
 
     #[inline]
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        self.output.serde_call(SerdeCall::Tuple);
         Ok(self)
     }
 
@@ -296,44 +382,64 @@ where
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
+        self.output.serde_call(SerdeCall::TupleStruct);
         Ok(self)
     }
 
     #[inline]
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        let v = Vlu32N(variant_index);
-        v.ser(&mut self.output)?;
+        self.output.serde_call(SerdeCall::TupleVariant);
+        self.output.try_push_variant(name, variant_index, variant)?;
         Ok(self)
     }
 
     #[inline]
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
-        self.try_push_varint_usize(len.ok_or(Error::SerializeSeqLengthUnknown)?)
+        self.output.serde_call(SerdeCall::Map);
+        let len = match len {
+            Some(len) => len,
+            None => {
+                // Structs with flattened fields, the entries describe themselves
+                for nib in UNSIZED_MAP {
+                    self.output.try_push_nib(nib)?;
+                }
+                return Ok(MapCompound {
+                    ser: self,
+                    terminated: true,
+                });
+            }
+        };
+        self.output.map_start()?;
+        self.try_push_varint_usize(len)
             .map_err(|_| Error::SerializeBufferFull)?;
-        Ok(self)
+        Ok(MapCompound {
+            ser: self,
+            terminated: false,
+        })
     }
 
     #[inline]
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        self.output.serde_call(SerdeCall::Struct);
         Ok(self)
     }
 
     #[inline]
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        let v = Vlu32N(variant_index);
-        v.ser(&mut self.output)?;
+        self.output.serde_call(SerdeCall::StructVariant);
+        self.output.try_push_variant(name, variant_index, variant)?;
         Ok(self)
     }
 
@@ -342,69 +448,8 @@ where
     where
         T: core::fmt::Display,
     {
-        use core::fmt::Write;
-
-        // Unfortunately, we need to know the size of the serialized data before
-        // we can place it into the output. In order to do this, we run the formatting
-        // of the output data TWICE, the first time to determine the length, the
-        // second time to actually format the data
-        //
-        // There are potentially other ways to do this, such as:
-        //
-        // * Reserving a fixed max size, such as 5 bytes, for the length field, and
-        //     leaving non-canonical trailing zeroes at the end. This would work up
-        //     to some reasonable length, but might have some portability vs max size
-        //     tradeoffs, e.g. 64KiB if we pick 3 bytes, or 4GiB if we pick 5 bytes
-        // * Expose some kind of "memmove" capability to flavors, to allow us to
-        //     format into the buffer, then "scoot over" that many times.
-        //
-        // Despite the current approaches downside in speed, it is likely flexible
-        // enough for the rare-ish case where formatting a Debug impl is necessary.
-        // This is better than the previous panicking behavior, and can be improved
-        // in the future.
-        struct CountWriter {
-            ct: usize,
-        }
-        impl Write for CountWriter {
-            fn write_str(&mut self, s: &str) -> core::result::Result<(), core::fmt::Error> {
-                self.ct += s.as_bytes().len();
-                Ok(())
-            }
-        }
-
-        let mut ctr = CountWriter { ct: 0 };
-
-        // This is the first pass through, where we just count the length of the
-        // data that we are given
-        write!(&mut ctr, "{}", value).map_err(|_| Error::CollectStrError)?;
-        let len = ctr.ct;
-        self.try_push_varint_usize(len)
-            .map_err(|_| Error::SerializeBufferFull)?;
-
-        struct FmtWriter<'a, IF>
-        where
-            IF: NibbleFlavor,
-        {
-            output: &'a mut IF,
-        }
-        impl<'a, IF> Write for FmtWriter<'a, IF>
-        where
-            IF: NibbleFlavor,
-        {
-            fn write_str(&mut self, s: &str) -> core::result::Result<(), core::fmt::Error> {
-                self.output
-                    .try_extend(s.as_bytes())
-                    .map_err(|_| core::fmt::Error::default())
-            }
-        }
-
-        // This second pass actually inserts the data.
-        let mut fw = FmtWriter {
-            output: &mut self.output,
-        };
-        write!(&mut fw, "{}", value).map_err(|_| Error::CollectStrError)?;
-
-        Ok(())
+        self.output.serde_call(SerdeCall::CollectStr);
+        self.try_push_display(value)
     }
 }
 
@@ -496,7 +541,17 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeMap for &'a mut NibbleSerializer<F>
+/// The state of a map being serialized
+pub struct MapCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    ser: &'a mut NibbleSerializer<F>,
+    /// The length was not known up front, entries are self-described and terminated
+    terminated: bool,
+}
+
+impl<'a, F> ser::SerializeMap for MapCompound<'a, F>
 where
     F: NibbleFlavor,
 {
@@ -508,7 +563,11 @@ where
     where
         T: ?Sized + Serialize,
     {
-        key.serialize(&mut **self)
+        if self.terminated {
+            return key.serialize(Described { ser: self.ser });
+        }
+        self.ser.output.map_entry()?;
+        key.serialize(&mut *self.ser)
     }
 
     #[inline]
@@ -516,12 +575,18 @@ where
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(&mut **self)
+        if self.terminated {
+            return value.serialize(Described { ser: self.ser });
+        }
+        value.serialize(&mut *self.ser)
     }
 
     #[inline]
     fn end(self) -> Result<()> {
-        Ok(())
+        if self.terminated {
+            return self.ser.output.try_push_nib(tag::END);
+        }
+        self.ser.output.map_end()
     }
 }
 
@@ -533,11 +598,13 @@ where
     type Error = Error;
 
     #[inline]
-    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(&mut **self)
+        self.output.field_start(key)?;
+        value.serialize(&mut **self)?;
+        self.output.field_end()
     }
 
     #[inline]
@@ -554,11 +621,13 @@ where
     type Error = Error;
 
     #[inline]
-    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(&mut **self)
+        self.output.field_start(key)?;
+        value.serialize(&mut **self)?;
+        self.output.field_end()
     }
 
     #[inline]
@@ -567,6 +636,501 @@ where
     }
 }
 
+////////////////////////////////////////
+// Self-described values
+////////////////////////////////////////
+
+/// Marks a map of unknown length, in place of the length prefix. Ten continued digits of 7 are
+/// already more than 32 bits, so this can't be mistaken for a length, not even one with the
+/// leading zero digits that lenient decoding accepts.
+pub(crate) const UNSIZED_MAP: [u8; 10] = [0xF; 10];
+
+/// The type tags written in front of self-described values
+pub(crate) mod tag {
+    pub(crate) const UNIT: u8 = 0x0;
+    pub(crate) const BOOL: u8 = 0x1;
+    /// Any unsigned integer, as a vlu128n, which is the same as a vlu64n for 64 bit values
+    pub(crate) const UNSIGNED: u8 = 0x2;
+    /// Any signed integer, as a zigzag encoded vlu128n
+    pub(crate) const SIGNED: u8 = 0x3;
+    pub(crate) const F32: u8 = 0x4;
+    pub(crate) const F64: u8 = 0x5;
+    /// Strings and chars
+    pub(crate) const STR: u8 = 0x6;
+    pub(crate) const BYTES: u8 = 0x7;
+    pub(crate) const NONE: u8 = 0x8;
+    pub(crate) const SOME: u8 = 0x9;
+    /// Sequences, tuples and the contents of tuple variants, with a length prefix
+    pub(crate) const SEQ: u8 = 0xA;
+    /// Maps, structs, and the single `variant => contents` entry of non unit variants, with a
+    /// length prefix
+    pub(crate) const MAP: u8 = 0xB;
+    /// A sequence of unknown length, terminated by `END`
+    pub(crate) const UNSIZED_SEQ: u8 = 0xC;
+    /// A map of unknown length, terminated by `END`
+    pub(crate) const UNSIZED_MAP: u8 = 0xD;
+    pub(crate) const END: u8 = 0xF;
+}
+
+/// Serializes values with a type tag nibble in front of each of them, so that they can be
+/// decoded without knowing their type.
+///
+/// serde serializes a struct with `#[serde(flatten)]` fields as a map of unknown length, and
+/// deserializes it by buffering the entries that don't belong to the outer struct, which only
+/// works with a self-describing format. The entries of such maps are written by this
+/// serializer, which comes with some limitations:
+///
+/// * The entries take more space than regular nibble data: every field name is written out,
+///   and every value has a type tag.
+/// * serde can't buffer 128-bit integers that don't fit into 64 bits, so those only round trip
+///   in fields of the outer struct, not in the flattened ones.
+/// * Flavors see the entries as plain nibbles and byte runs, without map or field hooks, so for
+///   example `NibbleCanonical` does not reorder them.
+/// * Deserializing them needs serde's `alloc` support, which `#[serde(flatten)]` requires anyway.
+struct Described<'a, F>
+where
+    F: NibbleFlavor,
+{
+    ser: &'a mut NibbleSerializer<F>,
+}
+
+impl<'a, F> Described<'a, F>
+where
+    F: NibbleFlavor,
+{
+    /// Report the serializer method and write the tag of its value. Values are written with
+    /// the `try_push_*` helpers, the `Serializer` methods would report the call a second time.
+    fn tag(&mut self, call: SerdeCall, tag: u8) -> Result<()> {
+        self.ser.output.serde_call(call);
+        self.ser.output.try_push_nib(tag)
+    }
+
+    fn unsigned(mut self, call: SerdeCall, v: u128) -> Result<()> {
+        self.tag(call, tag::UNSIGNED)?;
+        self.ser.try_push_varint_u128(v)
+    }
+
+    fn signed(mut self, call: SerdeCall, v: i128) -> Result<()> {
+        self.tag(call, tag::SIGNED)?;
+        self.ser.try_push_varint_u128(zig_zag_i128(v))
+    }
+
+    fn str(mut self, call: SerdeCall, v: &str) -> Result<()> {
+        self.tag(call, tag::STR)?;
+        self.ser.try_push_str(v)
+    }
+
+    fn sized(mut self, call: SerdeCall, tag: u8, len: usize) -> Result<DescribedCompound<'a, F>> {
+        self.tag(call, tag)?;
+        self.ser.try_push_varint_usize(len)?;
+        Ok(DescribedCompound {
+            ser: self.ser,
+            terminated: false,
+        })
+    }
+
+    fn terminated(mut self, call: SerdeCall, tag: u8) -> Result<DescribedCompound<'a, F>> {
+        self.tag(call, tag)?;
+        Ok(DescribedCompound {
+            ser: self.ser,
+            terminated: true,
+        })
+    }
+
+    /// Start the single `variant => contents` entry of a non unit variant
+    fn variant(mut self, call: SerdeCall, variant: &'static str) -> Result<Self> {
+        self.tag(call, tag::MAP)?;
+        self.ser.try_push_varint_usize(1)?;
+        self.ser.output.try_push_nib(tag::STR)?;
+        self.ser.try_push_str(variant)?;
+        Ok(self)
+    }
+}
+
+impl<'a, F> ser::Serializer for Described<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = DescribedCompound<'a, F>;
+    type SerializeTuple = DescribedCompound<'a, F>;
+    type SerializeTupleStruct = DescribedCompound<'a, F>;
+    type SerializeTupleVariant = DescribedCompound<'a, F>;
+    type SerializeMap = DescribedCompound<'a, F>;
+    type SerializeStruct = DescribedCompound<'a, F>;
+    type SerializeStructVariant = DescribedCompound<'a, F>;
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(mut self, v: bool) -> Result<()> {
+        self.tag(SerdeCall::Bool, tag::BOOL)?;
+        self.ser.output.try_push_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.signed(SerdeCall::I8, v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.signed(SerdeCall::I16, v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.signed(SerdeCall::I32, v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.signed(SerdeCall::I64, v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.signed(SerdeCall::I128, v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.unsigned(SerdeCall::U8, v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.unsigned(SerdeCall::U16, v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.unsigned(SerdeCall::U32, v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.unsigned(SerdeCall::U64, v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.unsigned(SerdeCall::U128, v)
+    }
+
+    fn serialize_f32(mut self, v: f32) -> Result<()> {
+        self.tag(SerdeCall::F32, tag::F32)?;
+        self.ser.output.try_extend(&v.to_bits().to_le_bytes())
+    }
+
+    fn serialize_f64(mut self, v: f64) -> Result<()> {
+        self.tag(SerdeCall::F64, tag::F64)?;
+        self.ser.output.try_extend(&v.to_bits().to_le_bytes())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        // Same as a one character string
+        let mut buf = [0u8; 4];
+        self.str(SerdeCall::Char, v.encode_utf8(&mut buf))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.str(SerdeCall::Str, v)
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<()> {
+        self.tag(SerdeCall::Bytes, tag::BYTES)?;
+        self.ser.try_push_bytes(v)
+    }
+
+    fn serialize_none(mut self) -> Result<()> {
+        self.tag(SerdeCall::None, tag::NONE)
+    }
+
+    fn serialize_some<T>(mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.tag(SerdeCall::Some, tag::SOME)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(mut self) -> Result<()> {
+        self.tag(SerdeCall::Unit, tag::UNIT)
+    }
+
+    fn serialize_unit_struct(mut self, _name: &'static str) -> Result<()> {
+        self.tag(SerdeCall::UnitStruct, tag::UNIT)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.str(SerdeCall::UnitVariant, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.ser.output.serde_call(SerdeCall::NewtypeStruct);
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self.variant(SerdeCall::NewtypeVariant, variant)?)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        match len {
+            Some(len) => self.sized(SerdeCall::Seq, tag::SEQ, len),
+            None => self.terminated(SerdeCall::Seq, tag::UNSIZED_SEQ),
+        }
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.sized(SerdeCall::Tuple, tag::SEQ, len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.sized(SerdeCall::TupleStruct, tag::SEQ, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        let inner = self.variant(SerdeCall::TupleVariant, variant)?;
+        inner.ser.output.try_push_nib(tag::SEQ)?;
+        inner.ser.try_push_varint_usize(len)?;
+        Ok(DescribedCompound {
+            ser: inner.ser,
+            terminated: false,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        match len {
+            Some(len) => self.sized(SerdeCall::Map, tag::MAP, len),
+            None => self.terminated(SerdeCall::Map, tag::UNSIZED_MAP),
+        }
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.sized(SerdeCall::Struct, tag::MAP, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        let inner = self.variant(SerdeCall::StructVariant, variant)?;
+        inner.ser.output.try_push_nib(tag::MAP)?;
+        inner.ser.try_push_varint_usize(len)?;
+        Ok(DescribedCompound {
+            ser: inner.ser,
+            terminated: false,
+        })
+    }
+
+    fn collect_str<T>(mut self, value: &T) -> Result<()>
+    where
+        T: core::fmt::Display + ?Sized,
+    {
+        self.tag(SerdeCall::CollectStr, tag::STR)?;
+        self.ser.try_push_display(value)
+    }
+}
+
+/// The state of a compound value being serialized by [`Described`]
+struct DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    ser: &'a mut NibbleSerializer<F>,
+    /// The length was not known up front, the elements are terminated
+    terminated: bool,
+}
+
+impl<'a, F> DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    fn element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Described {
+            ser: &mut *self.ser,
+        })
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.terminated {
+            self.ser.output.try_push_nib(tag::END)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, F> ser::SerializeSeq for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, F> ser::SerializeTuple for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, F> ser::SerializeTupleStruct for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, F> ser::SerializeTupleVariant for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, F> ser::SerializeMap for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, F> ser::SerializeStruct for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(key)?;
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, F> ser::SerializeStructVariant for DescribedCompound<'a, F>
+where
+    F: NibbleFlavor,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(key)?;
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
 fn zig_zag_i16(n: i16) -> u16 {
     ((n << 1) ^ (n >> 15)) as u16
 }
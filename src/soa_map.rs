@@ -0,0 +1,177 @@
+//! # Struct-of-Arrays Maps
+//!
+//! Maps are serialized as a length prefix followed by the entries, each key
+//! directly followed by its value. When keys and values look quite different,
+//! e.g. small sequential ids mapped to large readings, the alternation makes
+//! the output harder to compress than necessary.
+//!
+//! [`NibbleSoaMap`] stores the same data as all keys, then all values. The
+//! size is unchanged, but similar varints end up next to each other, which
+//! general purpose compressors handle much better.
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{DeserializeSeed, Error as _, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A `BTreeMap`, serialized as the entry count, then all keys in order, then all values.
+///
+/// On the wire the keys are a regular sequence, with its length prefix, followed by the
+/// values without another prefix. The size is exactly that of the map itself.
+///
+/// ```rust
+/// use postcard::soa_map::NibbleSoaMap;
+/// use std::collections::BTreeMap;
+///
+/// let map: BTreeMap<u8, u8> = [(1, 5), (2, 6)].iter().copied().collect();
+/// let ser = postcard::to_nibble_allocvec(&NibbleSoaMap(map.clone())).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x21, 0x25, 0x60]);
+/// assert_eq!(postcard::to_nibble_allocvec(&map).unwrap().as_slice(), &[0x21, 0x52, 0x60]);
+/// assert_eq!(postcard::from_nibbles::<NibbleSoaMap<u8, u8>>(&ser).unwrap().0, map);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct NibbleSoaMap<K, V>(pub BTreeMap<K, V>);
+
+impl<K, V> From<BTreeMap<K, V>> for NibbleSoaMap<K, V> {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        Self(map)
+    }
+}
+
+impl<K, V> Serialize for NibbleSoaMap<K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut soa = serializer.serialize_tuple(2)?;
+        soa.serialize_element(&Keys(&self.0))?;
+        soa.serialize_element(&Values(&self.0))?;
+        soa.end()
+    }
+}
+
+/// The keys, as a length prefixed sequence
+struct Keys<'a, K, V>(&'a BTreeMap<K, V>);
+
+impl<'a, K, V> Serialize for Keys<'a, K, V>
+where
+    K: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.keys())
+    }
+}
+
+/// The values, as a tuple, as their number is already known from the keys
+struct Values<'a, K, V>(&'a BTreeMap<K, V>);
+
+impl<'a, K, V> Serialize for Values<'a, K, V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut values = serializer.serialize_tuple(self.0.len())?;
+        for v in self.0.values() {
+            values.serialize_element(v)?;
+        }
+        values.end()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for NibbleSoaMap<K, V>
+where
+    K: Deserialize<'de> + Ord,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, SoaVisitor(PhantomData))
+    }
+}
+
+struct SoaVisitor<K, V>(PhantomData<fn() -> (K, V)>);
+
+impl<'de, K, V> Visitor<'de> for SoaVisitor<K, V>
+where
+    K: Deserialize<'de> + Ord,
+    V: Deserialize<'de>,
+{
+    type Value = NibbleSoaMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("map keys followed by the values")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let keys: Vec<K> = seq
+            .next_element()?
+            .ok_or_else(|| S::Error::invalid_length(0, &self))?;
+        let values = seq
+            .next_element_seed(ValuesSeed(keys.len(), PhantomData))?
+            .ok_or_else(|| S::Error::invalid_length(1, &self))?;
+        Ok(NibbleSoaMap(keys.into_iter().zip(values).collect()))
+    }
+}
+
+/// Decodes as many values as there were keys
+struct ValuesSeed<V>(usize, PhantomData<fn() -> V>);
+
+impl<'de, V> DeserializeSeed<'de> for ValuesSeed<V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = Vec<V>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(self.0, self)
+    }
+}
+
+impl<'de, V> Visitor<'de> for ValuesSeed<V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = Vec<V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} map values", self.0)
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        // The count comes from the input, so don't trust it for the allocation
+        let mut values = Vec::with_capacity(self.0.min(4096));
+        for i in 0..self.0 {
+            let v = seq
+                .next_element()?
+                .ok_or_else(|| S::Error::invalid_length(i, &self))?;
+            values.push(v);
+        }
+        Ok(values)
+    }
+}
@@ -0,0 +1,396 @@
+//! # Type Descriptors
+//!
+//! Postcard messages carry no type information, so both peers have to agree on
+//! the types up front. A [`NibbleTypeDescriptor`] summarizes the wire layout of
+//! a type, so that peers can exchange descriptors in a handshake and check
+//! [`is_compatible`] before sending any data.
+//!
+//! The descriptor is built by deserializing the type from a tracer that records
+//! every value it is asked for, so it follows serde's view of the type rather
+//! than the Rust definition. The contents of options, sequences, maps and enum
+//! variants depend on the data, so only their kind is recorded.
+
+extern crate alloc;
+
+use crate::{Error, Result};
+use alloc::vec::Vec;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+/// The kind of a value in a [`NibbleTypeDescriptor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NibbleTypeKind {
+    /// A `bool`
+    Bool,
+    /// An `i8`
+    I8,
+    /// An `i16`
+    I16,
+    /// An `i32`
+    I32,
+    /// An `i64`
+    I64,
+    /// An `i128`
+    I128,
+    /// A `u8`
+    U8,
+    /// A `u16`
+    U16,
+    /// A `u32`
+    U32,
+    /// A `u64`
+    U64,
+    /// A `u128`
+    U128,
+    /// An `f32`
+    F32,
+    /// An `f64`
+    F64,
+    /// A `char`
+    Char,
+    /// A string
+    Str,
+    /// A byte slice
+    Bytes,
+    /// An `Option`
+    Option,
+    /// A unit or unit struct, which takes no space
+    Unit,
+    /// An enum
+    Enum,
+    /// A sequence with a length prefix
+    Seq,
+    /// A map
+    Map,
+    /// A tuple, tuple struct or array, followed by its elements
+    Tuple(u32),
+    /// A struct, followed by its fields
+    Struct(u32),
+}
+
+/// The wire layout of a type, as a list of value kinds in the order they are encoded.
+///
+/// Newtype structs don't change the encoding, so they don't show up in the descriptor.
+/// Building a descriptor fails for types whose `Deserialize` impl rejects the placeholder
+/// values the tracer hands out, such as zero for `NonZeroU8`.
+///
+/// ```rust
+/// use postcard::type_descriptor::{is_compatible, NibbleTypeDescriptor, NibbleTypeKind};
+/// # use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let point = NibbleTypeDescriptor::of::<Point>().unwrap();
+/// assert_eq!(
+///     point.kinds(),
+///     &[NibbleTypeKind::Struct(2), NibbleTypeKind::I32, NibbleTypeKind::I32]
+/// );
+/// // A tuple is encoded the same way
+/// assert!(is_compatible(&point, &NibbleTypeDescriptor::of::<(i32, i32)>().unwrap()));
+/// assert!(!is_compatible(&point, &NibbleTypeDescriptor::of::<(i32, u32)>().unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NibbleTypeDescriptor {
+    kinds: Vec<NibbleTypeKind>,
+}
+
+impl NibbleTypeDescriptor {
+    /// Describe the type `T`
+    pub fn of<'de, T>() -> Result<Self>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut tracer = Tracer {
+            kinds: Vec::new(),
+            muted: 0,
+        };
+        T::deserialize(&mut tracer)?;
+        Ok(Self {
+            kinds: tracer.kinds,
+        })
+    }
+
+    /// The value kinds, in encoding order
+    pub fn kinds(&self) -> &[NibbleTypeKind] {
+        &self.kinds
+    }
+}
+
+/// Returns `true` if values of the type described by `a` can be decoded as the type described
+/// by `b`, which is the case if both have the same encoding.
+///
+/// Structs and tuples with the same elements are compatible, as are types that only differ in
+/// their newtype wrappers. Field names don't matter, as they aren't encoded.
+pub fn is_compatible(a: &NibbleTypeDescriptor, b: &NibbleTypeDescriptor) -> bool {
+    fn wire(kind: NibbleTypeKind) -> NibbleTypeKind {
+        match kind {
+            NibbleTypeKind::Struct(n) => NibbleTypeKind::Tuple(n),
+            kind => kind,
+        }
+    }
+
+    a.kinds.len() == b.kinds.len()
+        && a.kinds
+            .iter()
+            .zip(b.kinds.iter())
+            .all(|(a, b)| wire(*a) == wire(*b))
+}
+
+/// Records the values a type deserializes, answering with placeholders
+struct Tracer {
+    kinds: Vec<NibbleTypeKind>,
+    /// Nesting depth within data dependent values, whose contents aren't recorded
+    muted: usize,
+}
+
+impl Tracer {
+    fn record(&mut self, kind: NibbleTypeKind) {
+        if self.muted == 0 {
+            self.kinds.push(kind);
+        }
+    }
+
+    fn elements<'de, V>(&mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Elements { tracer: self, len })
+    }
+
+    fn muted<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.muted += 1;
+        let res = f(self);
+        self.muted -= 1;
+        res
+    }
+}
+
+macro_rules! trace_primitive {
+    ($($method:ident => $kind:ident, $visit:ident($value:expr);)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                self.record(NibbleTypeKind::$kind);
+                visitor.$visit($value)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Tracer {
+    type Error = Error;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    trace_primitive! {
+        deserialize_bool => Bool, visit_bool(false);
+        deserialize_i8 => I8, visit_i8(0);
+        deserialize_i16 => I16, visit_i16(0);
+        deserialize_i32 => I32, visit_i32(0);
+        deserialize_i64 => I64, visit_i64(0);
+        deserialize_i128 => I128, visit_i128(0);
+        deserialize_u8 => U8, visit_u8(0);
+        deserialize_u16 => U16, visit_u16(0);
+        deserialize_u32 => U32, visit_u32(0);
+        deserialize_u64 => U64, visit_u64(0);
+        deserialize_u128 => U128, visit_u128(0);
+        deserialize_f32 => F32, visit_f32(0.0);
+        deserialize_f64 => F64, visit_f64(0.0);
+        deserialize_char => Char, visit_char('\0');
+        deserialize_str => Str, visit_borrowed_str("");
+        deserialize_string => Str, visit_borrowed_str("");
+        deserialize_bytes => Bytes, visit_borrowed_bytes(&[]);
+        deserialize_byte_buf => Bytes, visit_borrowed_bytes(&[]);
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Unit);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Option);
+        visitor.visit_none()
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Seq);
+        self.elements(0, visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Tuple(len as u32));
+        self.elements(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Map);
+        visitor.visit_map(de::value::MapDeserializer::new(
+            core::iter::empty::<((), ())>(),
+        ))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Struct(fields.len() as u32));
+        self.elements(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.record(NibbleTypeKind::Enum);
+        // The first variant stands in for all of them
+        self.muted(|tracer| visitor.visit_enum(tracer))
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::WontImplement)
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::WontImplement)
+    }
+
+    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::WontImplement)
+    }
+}
+
+/// The elements of a tuple or struct, or of an always empty sequence
+struct Elements<'a> {
+    tracer: &'a mut Tracer,
+    len: usize,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Elements<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.tracer).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Tracer {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(0u32.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Tracer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.elements(len, visitor)
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.elements(fields.len(), visitor)
+    }
+}
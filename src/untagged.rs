@@ -0,0 +1,111 @@
+//! # Untagged Alternatives
+//!
+//! `#[serde(untagged)]` enums can't be used with postcard, as serde decodes them
+//! by first buffering the input through `deserialize_any`, which requires a
+//! self-describing format. [`NibbleUntagged`] offers the same "try each variant"
+//! behavior for the nibble format: the first alternative is decoded, and if that
+//! fails the deserializer rewinds and decodes the second one instead.
+//!
+//! More than two alternatives can be expressed by nesting, e.g.
+//! `NibbleUntagged<A, NibbleUntagged<B, C>>`.
+//!
+//! ## Performance
+//!
+//! Nothing on the wire says which alternative was written, so a message of the
+//! second kind is first (partially) decoded as the first kind, and the work is
+//! thrown away when that fails. The cost is the sum of all failed attempts, plus
+//! the successful one. Put the most common alternative first, and prefer regular
+//! enums, whose one nibble tag costs far less, whenever the format is up to you.
+//!
+//! Rewinding needs support from the deserialization flavor (see
+//! `NibbleFlavor::mark`). With other deserializers, including the byte based
+//! postcard one, only the first alternative is tried.
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The name the nibble deserializer recognizes as a request to try alternatives
+pub(crate) const UNTAGGED_NAME: &str = "postcard::NibbleUntagged";
+
+/// One of two alternatives, serialized without any tag.
+///
+/// ```rust
+/// use postcard::untagged::NibbleUntagged;
+///
+/// // A pair of bools, or else two numbers
+/// type Either = NibbleUntagged<(bool, bool), (u8, u8)>;
+///
+/// let first: Either = postcard::from_nibbles(&[0x10]).unwrap();
+/// assert_eq!(first, NibbleUntagged::First((true, false)));
+///
+/// // 5 is not a valid bool, so the second alternative is used
+/// let second: Either = postcard::from_nibbles(&[0x57]).unwrap();
+/// assert_eq!(second, NibbleUntagged::Second((5, 7)));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NibbleUntagged<A, B> {
+    /// The first alternative decoded successfully
+    First(A),
+    /// The first alternative failed, and the second one decoded successfully
+    Second(B),
+}
+
+impl<A, B> Serialize for NibbleUntagged<A, B>
+where
+    A: Serialize,
+    B: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            NibbleUntagged::First(a) => a.serialize(serializer),
+            NibbleUntagged::Second(b) => b.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, A, B> Deserialize<'de> for NibbleUntagged<A, B>
+where
+    A: Deserialize<'de>,
+    B: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // The nibble deserializer yields the first alternative, or nothing if it failed to
+        // decode, then the second one.
+        deserializer.deserialize_tuple_struct(UNTAGGED_NAME, 2, UntaggedVisitor(PhantomData))
+    }
+}
+
+struct UntaggedVisitor<A, B>(PhantomData<fn() -> (A, B)>);
+
+impl<'de, A, B> Visitor<'de> for UntaggedVisitor<A, B>
+where
+    A: Deserialize<'de>,
+    B: Deserialize<'de>,
+{
+    type Value = NibbleUntagged<A, B>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("one of two untagged alternatives")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        if let Some(a) = seq.next_element()? {
+            return Ok(NibbleUntagged::First(a));
+        }
+        match seq.next_element()? {
+            Some(b) => Ok(NibbleUntagged::Second(b)),
+            None => Err(S::Error::invalid_length(1, &self)),
+        }
+    }
+}
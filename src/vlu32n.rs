@@ -4,7 +4,40 @@ use crate::ser::nibble_flavors::NibbleFlavor as NibbleFlavorSer;
 
 pub struct Vlu32N(pub u32);
 
+/// The first of at most 11 nibbles holds bits 31:30
+const MAX_VLU32N_NIBBLES: usize = 11;
+
 impl Vlu32N {
+    /// The number of nibbles [`ser`](Self::ser) writes for this value, without a flavor
+    pub const fn nibble_len(&self) -> usize {
+        let val = self.0;
+        if val >> 30 != 0 {
+            // bits 31:30, then all ten 3 bit groups
+            return MAX_VLU32N_NIBBLES;
+        }
+        // significant 3 bit groups of bits 29:0, a zero still takes one nibble
+        let bits = (32 - val.leading_zeros()) as usize;
+        if bits == 0 {
+            1
+        } else {
+            bits.div_ceil(3)
+        }
+    }
+
+    /// The nibbles [`ser`](Self::ser) writes for this value, without a flavor
+    pub fn nibbles(&self) -> impl Iterator<Item = u8> {
+        let val = self.0;
+        (0..self.nibble_len()).rev().map(move |i| {
+            // the 11th nibble holds the remaining bits 31:30
+            let nib = (val >> (3 * i)) as u8 & 0b111;
+            if i == 0 {
+                nib
+            } else {
+                nib | 0b1000
+            }
+        })
+    }
+
     pub fn ser(&self, flavor: &mut impl NibbleFlavorSer) -> Result<(), Error> {
         let mut val = self.0;
         let mut msb_found = false;
@@ -33,21 +66,159 @@ impl Vlu32N {
     }
 
     pub fn de<'de>(flavor: &mut impl NibbleFlavorDe<'de>) -> Result<Self, Error> {
-        let mut num = 0;
-        for i in 0..=10 {
-            let nib = flavor.try_take_nib()?;
-            if i == 10 {
-                // maximum 32 bits in 11 nibbles, 11th nibble should be the last
-                if nib & 0b1000 != 0 {
-                    return Err(Error::DeserializeBadVlu32N);
-                }
+        let first = flavor.try_take_nib()?;
+        Self::de_from(first, flavor)
+    }
+
+    /// Decode a number whose first nibble was already taken from the flavor
+    ///
+    /// Running out of nibbles mid-number is reported by the flavor, typically as
+    /// [`Error::DeserializeUnexpectedEnd`], a number that doesn't fit in 32 bits or that is
+    /// still continued after 11 nibbles as [`Error::DeserializeBadVlu32N`].
+    pub fn de_from<'de>(first: u8, flavor: &mut impl NibbleFlavorDe<'de>) -> Result<Self, Error> {
+        if first == 0b1000 {
+            // leading zero digit, the encoder never emits those
+            flavor.non_canonical_varint()?;
+        }
+        Self::de_continued(0, 0, first, flavor)
+    }
+
+    /// Decode a number whose first `taken` nibbles, worth `num`, and the nibble `nib` after
+    /// them were already taken from the flavor
+    pub fn de_continued<'de>(
+        mut num: u32,
+        taken: usize,
+        mut nib: u8,
+        flavor: &mut impl NibbleFlavorDe<'de>,
+    ) -> Result<Self, Error> {
+        for i in taken..MAX_VLU32N_NIBBLES {
+            if num >> 29 != 0 {
+                // more than 32 bits
+                return Err(Error::DeserializeBadVlu32N);
             }
-            num |= nib as u32 & 0b111;
+            num = (num << 3) | (nib & 0b111) as u32;
             if nib & 0b1000 == 0 {
+                return Ok(Vlu32N(num));
+            }
+            if i + 1 == MAX_VLU32N_NIBBLES {
                 break;
             }
-            num <<= 3;
+            nib = flavor.try_take_nib()?;
+        }
+        // maximum 32 bits in 11 nibbles, the 11th nibble should be the last
+        Err(Error::DeserializeBadVlu32N)
+    }
+}
+
+/// A nibble varint for 64 bit values: groups of 3 bits, most significant first, with the
+/// continuation bit 0x8 set on all but the last nibble. The first of at most 22 nibbles
+/// holds only bit 63.
+pub struct Vlu64N(pub u64);
+
+/// A nibble varint for 128 bit values, encoded like [`Vlu64N`]. The first of at most 43
+/// nibbles holds bits 127:126.
+pub struct Vlu128N(pub u128);
+
+macro_rules! impl_wide_vlu {
+    ($($ty:ident: $int:ty, $max_nibbles:literal, $err:ident;)*) => {
+        $(
+            impl $ty {
+                const MAX_NIBBLES: usize = $max_nibbles;
+
+                pub fn ser(&self, flavor: &mut impl NibbleFlavorSer) -> Result<(), Error> {
+                    let bits = <$int>::BITS as usize - self.0.leading_zeros() as usize;
+                    let nibbles = bits.div_ceil(3).max(1);
+                    for i in (0..nibbles).rev() {
+                        let nib = (self.0 >> (3 * i)) as u8 & 0b111;
+                        if i == 0 {
+                            flavor.try_push_nib(nib)?;
+                        } else {
+                            flavor.try_push_nib(nib | 0b1000)?;
+                        }
+                    }
+                    Ok(())
+                }
+
+                pub fn de<'de>(flavor: &mut impl NibbleFlavorDe<'de>) -> Result<Self, Error> {
+                    let mut num: $int = 0;
+                    for i in 0..Self::MAX_NIBBLES {
+                        let nib = flavor.try_take_nib()?;
+                        if i == 0 && nib == 0b1000 {
+                            // leading zero digit, the encoder never emits those
+                            flavor.non_canonical_varint()?;
+                        }
+                        if num >> (<$int>::BITS - 3) != 0 {
+                            // more bits than the type has
+                            return Err(Error::$err);
+                        }
+                        num = (num << 3) | (nib & 0b111) as $int;
+                        if nib & 0b1000 == 0 {
+                            return Ok($ty(num));
+                        }
+                    }
+                    // the last nibble still had the continuation bit set
+                    Err(Error::$err)
+                }
+            }
+        )*
+    };
+}
+
+impl_wide_vlu! {
+    Vlu64N: u64, 22, DeserializeBadVlu64N;
+    Vlu128N: u128, 43, DeserializeBadVlu128N;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vlu32N;
+    use crate::de::nibble_flavors::{NibbleFlavor as _, NibbleSlice};
+    use crate::error::Error;
+    use crate::ser::nibble_flavors::{NibbleFlavor, NibbleSize, NibbleSlice as SerSlice};
+
+    #[test]
+    fn vlu32n_nibble_len() {
+        for val in [0, 0x7, 0x8, 0x3F, 0x40, 0x3FFF_FFFF, 0x4000_0000, u32::MAX] {
+            let mut size = NibbleSize::default();
+            Vlu32N(val).ser(&mut size).unwrap();
+            assert_eq!(
+                Vlu32N(val).nibble_len(),
+                size.finalize().unwrap(),
+                "{:#x}",
+                val
+            );
+
+            let mut buf = [0u8; 6];
+            let mut ser = SerSlice::new(&mut buf);
+            Vlu32N(val).ser(&mut ser).unwrap();
+            ser.finalize().unwrap();
+            let mut nibs = Vlu32N(val).nibbles();
+            let mut de = NibbleSlice::new(&buf);
+            for _ in 0..Vlu32N(val).nibble_len() {
+                assert_eq!(nibs.next(), de.try_take_nib().ok(), "{:#x}", val);
+            }
+            assert_eq!(nibs.next(), None);
         }
-        Ok(Vlu32N(num))
+        assert_eq!(Vlu32N(0).nibble_len(), 1);
+        assert_eq!(Vlu32N(0x7).nibble_len(), 1);
+        assert_eq!(Vlu32N(0x3FFF_FFFF).nibble_len(), 10);
+        assert_eq!(Vlu32N(u32::MAX).nibble_len(), 11);
+    }
+
+    #[test]
+    fn vlu32n_truncated_or_malformed() {
+        let de = |data: &[u8]| Vlu32N::de(&mut NibbleSlice::new(data)).map(|v| v.0);
+
+        assert_eq!(de(&[0xBF, 0xFF, 0xFF, 0xFF, 0xFF, 0x70]), Ok(u32::MAX));
+        // The buffer ends while the continuation bit is set
+        assert_eq!(de(&[0x98]), Err(Error::DeserializeUnexpectedEnd));
+        assert_eq!(de(&[]), Err(Error::DeserializeUnexpectedEnd));
+        // Still continued after 11 nibbles
+        assert_eq!(de(&[0xFF; 6]), Err(Error::DeserializeBadVlu32N));
+        // 11 nibbles, but 33 bits
+        assert_eq!(
+            de(&[0xCF, 0xFF, 0xFF, 0xFF, 0xFF, 0x70]),
+            Err(Error::DeserializeBadVlu32N)
+        );
     }
 }
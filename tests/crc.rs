@@ -37,3 +37,392 @@ fn test_crc_8() {
     let remaining_bytes = [];
     assert_eq!(res, (expected_bytes, remaining_bytes.as_slice()));
 }
+
+#[test]
+#[cfg(feature = "use-crc")]
+fn test_nibble_crc_checkpoints() {
+    use crc::{Crc, CRC_32_ISCSI};
+    use postcard::de_nibble_flavors::crc::from_nibbles_u32;
+    use postcard::ser_nibble_flavors::crc::to_nibble_slice_u32;
+    use postcard::Error;
+
+    static CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+    type Frame<'a> = (u32, [u16; 12], &'a str, bool, [u64; 4]);
+    let data: Frame = (
+        0xDEAD_BEEF,
+        [0x1234; 12],
+        "a string that is longer than the interval",
+        true,
+        [0x0102_0304_0506_0708; 4],
+    );
+
+    let mut plain = [0u8; 256];
+    let plain = postcard::to_nibble_slice_reserve(&data, &mut plain, 0)
+        .unwrap()
+        .0
+        .len();
+
+    let mut buf = [0u8; 256];
+    let frame = to_nibble_slice_u32(&data, &mut buf, CRC.digest(), 16).unwrap();
+    // Five checkpoints, and the final CRC
+    assert_eq!(frame.len(), plain + 6 * 4);
+    assert_eq!(from_nibbles_u32::<Frame>(frame, CRC.digest(), 16), Ok(data));
+
+    // The interval is part of the format
+    assert!(from_nibbles_u32::<Frame>(frame, CRC.digest(), 32).is_err());
+
+    // Any corrupted byte is caught
+    for i in 0..frame.len() {
+        let mut corrupt = [0u8; 256];
+        corrupt[..frame.len()].copy_from_slice(frame);
+        corrupt[i] ^= 0x10;
+        let res = from_nibbles_u32::<Frame>(&corrupt[..frame.len()], CRC.digest(), 16);
+        assert!(res.is_err(), "corrupted byte {} not detected", i);
+    }
+
+    // A corrupted first chunk fails at the first checkpoint, before the decoder
+    // would notice that the rest of the frame is missing
+    let half = frame.len() / 2;
+    assert_eq!(
+        from_nibbles_u32::<Frame>(&frame[..half], CRC.digest(), 16),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    frame[2] ^= 0x01;
+    assert_eq!(
+        from_nibbles_u32::<Frame>(&frame[..half], CRC.digest(), 16),
+        Err(Error::DeserializeBadChecksum)
+    );
+}
+
+#[test]
+#[cfg(all(feature = "use-crc", feature = "heapless"))]
+fn test_nibble_crc_checkpoints_hooks() {
+    use crc::{Crc, CRC_32_ISCSI};
+    use postcard::de_nibble_flavors::{
+        crc::NibbleCrcCheckpoints as DeCheckpoints, NibblePackedBools as DePacked,
+        NibbleSlice as DeSlice,
+    };
+    use postcard::ser_nibble_flavors::{crc::NibbleCrcCheckpoints, NibblePackedBools, NibbleSlice};
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    static CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+    // Booleans packed by the inner flavor, with checkpoints and byte runs in between
+    type Flags<'a> = ([bool; 7], &'a str, [bool; 3], u16);
+    let flags: Flags = ([true; 7], "flags", [false, true, false], 0x1234);
+    let mut buf = [0u8; 64];
+    let frame = serialize_with_nibble_flavor(
+        &flags,
+        NibbleCrcCheckpoints::new(
+            NibblePackedBools::new(NibbleSlice::new(&mut buf)),
+            CRC.digest(),
+            2,
+        ),
+    )
+    .unwrap();
+    let (back, _) = from_nibbles_with_flavor::<Flags, _>(DeCheckpoints::new(
+        DePacked::new(DeSlice::new(frame)),
+        CRC.digest(),
+        2,
+    ))
+    .unwrap();
+    assert_eq!(back, flags);
+    // A flipped boolean fails at the first checkpoint
+    frame[0] ^= 0x10;
+    let res = from_nibbles_with_flavor::<Flags, _>(DeCheckpoints::new(
+        DePacked::new(DeSlice::new(frame)),
+        CRC.digest(),
+        2,
+    ));
+    assert_eq!(res.err(), Some(Error::DeserializeBadChecksum));
+
+    // Rewinding for the second alternative also rewinds the CRC
+    type Either = NibbleUntagged<(bool, bool), (u8, u8)>;
+    let value: (Either, Either, u32) = (
+        NibbleUntagged::Second((5, 7)),
+        NibbleUntagged::First((true, false)),
+        0xDEAD_BEEF,
+    );
+    let mut buf = [0u8; 64];
+    let frame = serialize_with_nibble_flavor(
+        &value,
+        NibbleCrcCheckpoints::new(NibbleSlice::new(&mut buf), CRC.digest(), 2),
+    )
+    .unwrap();
+    let (back, _) = from_nibbles_with_flavor::<(Either, Either, u32), _>(DeCheckpoints::new(
+        DeSlice::new(frame),
+        CRC.digest(),
+        2,
+    ))
+    .unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+#[cfg(all(feature = "use-crc", feature = "heapless"))]
+fn test_nibble_vec_with_crc() {
+    use crc::{Crc, CRC_32_ISCSI};
+    use heapless::Vec;
+
+    let crc = Crc::<u32>::new(&CRC_32_ISCSI);
+    let data = (0xABCDu16, "crc", [0x0102_0304_0506_0708u64; 2], true);
+
+    let (payload, sum): (Vec<u8, 64>, u32) =
+        postcard::to_nibble_vec_with_crc(&data, crc.digest()).unwrap();
+    // The payload is the plain nibble encoding, and the CRC covers exactly those bytes
+    let plain: Vec<u8, 64> = postcard::to_nibble_vec(&data).unwrap();
+    assert_eq!(payload, plain);
+    let mut digest = crc.digest();
+    digest.update(&payload);
+    assert_eq!(sum, digest.finalize());
+
+    let back: (u16, &str, [u64; 2], bool) = postcard::from_nibbles(&payload).unwrap();
+    assert_eq!(back, data);
+
+    assert_eq!(
+        postcard::to_nibble_vec_with_crc::<_, 4>(&data, crc.digest()),
+        Err(postcard::Error::SerializeBufferFull)
+    );
+}
+
+#[test]
+#[cfg(feature = "use-crc")]
+fn test_nibble_crc32() {
+    use postcard::de_nibble_flavors::{crc::NibbleCrc32 as DeCrc32, NibbleSlice as DeSlice};
+    use postcard::ser_nibble_flavors::crc::{NibbleCrc32, CRC_32};
+    use postcard::ser_nibble_flavors::NibbleSlice;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    type Frame<'a> = (bool, u16, &'a str, [u8; 3]);
+    let data: Frame = (true, 0x1234, "odd", [1, 2, 3]);
+
+    let mut buf = [0u8; 64];
+    let frame =
+        serialize_with_nibble_flavor(&data, NibbleCrc32::new(NibbleSlice::new(&mut buf))).unwrap();
+    // The plain message, padded to a whole byte, and the CRC of those bytes
+    let plain: heapless::Vec<u8, 64> = postcard::to_nibble_vec(&data).unwrap();
+    let (payload, crc) = frame.split_at(frame.len() - 4);
+    assert_eq!(payload, &plain[..]);
+    assert_eq!(crc, &CRC_32.checksum(payload).to_le_bytes());
+
+    let (back, rest) =
+        from_nibbles_with_flavor::<Frame, _>(DeCrc32::new(DeSlice::new(frame))).unwrap();
+    assert_eq!(back, data);
+    assert!(rest.is_empty());
+
+    // Any corrupted byte is caught
+    for i in 0..frame.len() {
+        let mut corrupt = [0u8; 64];
+        corrupt[..frame.len()].copy_from_slice(frame);
+        corrupt[i] ^= 0x01;
+        let res = from_nibbles_with_flavor::<Frame, _>(DeCrc32::new(DeSlice::new(
+            &corrupt[..frame.len()],
+        )));
+        assert!(res.is_err(), "corrupted byte {} not detected", i);
+    }
+    frame[frame.len() - 1] ^= 0x80;
+    assert_eq!(
+        from_nibbles_with_flavor::<Frame, _>(DeCrc32::new(DeSlice::new(frame))).err(),
+        Some(Error::DeserializeBadChecksum)
+    );
+}
+
+#[test]
+#[cfg(all(feature = "use-crc", feature = "heapless"))]
+fn test_nibble_crc32_hooks() {
+    use postcard::de_nibble_flavors::{
+        crc::NibbleCrc32 as DeCrc32, NibbleNamedVariants as DeNamed, NibblePackedBools as DePacked,
+        NibbleSlice as DeSlice,
+    };
+    use postcard::ser_nibble_flavors::{
+        crc::NibbleCrc32, NibbleNamedVariants, NibblePackedBools, NibbleSlice,
+    };
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+    use serde::{Deserialize, Serialize};
+
+    // The inner flavor packs the booleans, the CRC follows the packed message
+    type Flags<'a> = ([bool; 5], &'a str, bool);
+    let flags: Flags = ([true, false, true, true, false], "crc", true);
+    let mut buf = [0u8; 32];
+    let frame = serialize_with_nibble_flavor(
+        &flags,
+        NibbleCrc32::new(NibblePackedBools::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    let packed: heapless::Vec<u8, 32> = postcard::to_nibble_vec_packed(&flags).unwrap();
+    assert_eq!(&frame[..frame.len() - 4], &packed[..]);
+    let (back, _) =
+        from_nibbles_with_flavor::<Flags, _>(DeCrc32::new(DePacked::new(DeSlice::new(frame))))
+            .unwrap();
+    assert_eq!(back, flags);
+    frame[0] ^= 0x10;
+    let res =
+        from_nibbles_with_flavor::<Flags, _>(DeCrc32::new(DePacked::new(DeSlice::new(frame))));
+    assert_eq!(res.err(), Some(Error::DeserializeBadChecksum));
+
+    // The inner flavor writes the variants by name
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Idle,
+        Run(u8),
+    }
+    let modes = [Mode::Run(3), Mode::Idle];
+    let mut buf = [0u8; 32];
+    let frame = serialize_with_nibble_flavor(
+        &modes,
+        NibbleCrc32::new(NibbleNamedVariants::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    let named: heapless::Vec<u8, 32> = postcard::to_nibble_vec_named_variants(&modes).unwrap();
+    assert_eq!(&frame[..frame.len() - 4], &named[..]);
+    let (back, _) =
+        from_nibbles_with_flavor::<[Mode; 2], _>(DeCrc32::new(DeNamed::new(DeSlice::new(frame))))
+            .unwrap();
+    assert_eq!(back, modes);
+
+    // Rewinding for the second alternative
+    type Either = NibbleUntagged<(bool, bool), (u8, u8)>;
+    let value: (Either, Either) = (
+        NibbleUntagged::Second((5, 7)),
+        NibbleUntagged::First((true, false)),
+    );
+    let mut buf = [0u8; 32];
+    let frame =
+        serialize_with_nibble_flavor(&value, NibbleCrc32::new(NibbleSlice::new(&mut buf))).unwrap();
+    let (back, _) =
+        from_nibbles_with_flavor::<(Either, Either), _>(DeCrc32::new(DeSlice::new(frame))).unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+#[cfg(feature = "use-crc")]
+fn test_nibble_slip() {
+    use postcard::de_nibble_flavors::crc::NibbleSlip as DeSlip;
+    use postcard::ser_nibble_flavors::crc::{NibbleSlip, SLIP_END, SLIP_ESC};
+    use postcard::ser_nibble_flavors::NibbleSlice;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Frame<'a> {
+        flag: bool,
+        #[serde(with = "postcard::byte_array")]
+        raw: [u8; 6],
+        name: &'a str,
+    }
+
+    // Both special bytes, also back to back and at the end of the data
+    let data = Frame {
+        flag: true,
+        raw: [SLIP_END, SLIP_ESC, 0x00, SLIP_ESC, SLIP_END, SLIP_END],
+        name: "slip",
+    };
+
+    let mut plain = [0u8; 64];
+    let plain = postcard::to_nibble_slice(&data, &mut plain).unwrap();
+    assert_eq!(plain.iter().filter(|b| **b == SLIP_END).count(), 3);
+
+    let mut buf = [0u8; 64];
+    let frame =
+        serialize_with_nibble_flavor(&data, NibbleSlip::new(NibbleSlice::new(&mut buf))).unwrap();
+    assert_eq!(
+        frame.iter().position(|b| *b == SLIP_END),
+        Some(frame.len() - 1)
+    );
+    // Every special byte takes two bytes, then the CRC and the end follow
+    assert!(frame.len() > plain.len() + 5 + 4);
+
+    // Two frames back to back
+    let mut stream = [0u8; 128];
+    stream[..frame.len()].copy_from_slice(frame);
+    stream[frame.len()..][..frame.len()].copy_from_slice(frame);
+    let len = frame.len();
+
+    let mut copy = stream;
+    let (back, rest) =
+        from_nibbles_with_flavor::<Frame, _>(DeSlip::new(&mut copy).unwrap()).unwrap();
+    assert_eq!(back, data);
+    assert_eq!(rest, &stream[len..]);
+
+    let mut second = [0u8; 128];
+    second[..rest.len()].copy_from_slice(rest);
+    let (back, rest) =
+        from_nibbles_with_flavor::<Frame, _>(DeSlip::new(&mut second).unwrap()).unwrap();
+    assert_eq!(back, data);
+    assert!(rest.iter().all(|b| *b == 0));
+
+    // A truncated frame has no end
+    let mut truncated = stream;
+    assert_eq!(
+        DeSlip::new(&mut truncated[..len - 1]).err(),
+        Some(Error::DeserializeUnexpectedEnd)
+    );
+
+    // An escape that isn't followed by an escaped byte
+    let mut bad = [0x17, SLIP_ESC, 0x00, SLIP_END];
+    assert_eq!(
+        DeSlip::new(&mut bad).err(),
+        Some(Error::DeserializeBadEncoding)
+    );
+
+    // A changed payload byte is caught by the CRC
+    let mut changed = stream;
+    let name = changed.windows(4).position(|w| w == b"slip").unwrap();
+    changed[name] = b'S';
+    let res = from_nibbles_with_flavor::<Frame, _>(DeSlip::new(&mut changed).unwrap());
+    assert_eq!(res.err(), Some(Error::DeserializeBadChecksum));
+
+    // Any corrupted byte is caught, either as a bad escape or by the CRC
+    for i in 0..len - 1 {
+        let mut corrupt = stream;
+        corrupt[i] ^= 0x01;
+        let res = DeSlip::new(&mut corrupt)
+            .and_then(|flav| from_nibbles_with_flavor::<Frame, _>(flav).map(|(v, _)| v));
+        assert!(
+            res != Ok(Frame { ..data }),
+            "corrupted byte {} not detected",
+            i
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "use-crc")]
+fn test_nibble_slip_modifiers() {
+    use postcard::de_nibble_flavors::{crc::NibbleSlip as DeSlip, NibblePackedBools as DePacked};
+    use postcard::ser_nibble_flavors::{crc::NibbleSlip, NibblePackedBools, NibbleSlice};
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+
+    // Encoding modifiers wrap the framing
+    type Flags<'a> = ([bool; 6], &'a str);
+    let flags: Flags = ([true, false, true, true, false, true], "slip");
+    let mut buf = [0u8; 32];
+    let len = serialize_with_nibble_flavor(
+        &flags,
+        NibblePackedBools::new(NibbleSlip::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap()
+    .len();
+    let mut copy = buf;
+    let (back, rest) =
+        from_nibbles_with_flavor::<Flags, _>(DePacked::new(DeSlip::new(&mut copy[..len]).unwrap()))
+            .unwrap();
+    assert_eq!(back, flags);
+    assert!(rest.is_empty());
+
+    // Rewinding for the second alternative
+    type Either = NibbleUntagged<(bool, bool), (u8, u8)>;
+    let value: (Either, Either) = (
+        NibbleUntagged::Second((5, 7)),
+        NibbleUntagged::First((true, false)),
+    );
+    let mut buf = [0u8; 32];
+    serialize_with_nibble_flavor(&value, NibbleSlip::new(NibbleSlice::new(&mut buf))).unwrap();
+    let (back, _) =
+        from_nibbles_with_flavor::<(Either, Either), _>(DeSlip::new(&mut buf).unwrap()).unwrap();
+    assert_eq!(back, value);
+}
@@ -0,0 +1,98 @@
+#[cfg(feature = "heapless")]
+use heapless::Vec;
+
+#[cfg(feature = "heapless")]
+use postcard::{
+    from_nibbles,
+    ser_nibble_flavors::{NibbleFlavor, NibbleHVec},
+};
+
+#[cfg(feature = "heapless")]
+#[test]
+fn min_size_front() {
+    use postcard::{from_nibbles_min_size_front, ser_nibble_flavors::NibbleMinSizeFront};
+
+    let data = (0x12u8, 0xC7A5u16);
+    let plain: Vec<u8, 32> = postcard::to_nibble_vec(&data).unwrap();
+    assert_eq!(plain.len(), 4);
+
+    // Under the minimum: zero padded at the front, payload right-aligned
+    let mut flavor = NibbleMinSizeFront::new(NibbleHVec::<32>::default(), 8);
+    flavor.try_extend(&plain).unwrap();
+    let padded = flavor.finalize().unwrap();
+    assert_eq!(&padded[..4], &[0, 0, 0, 0]);
+    assert_eq!(&padded[4..], &plain[..]);
+    let out: (u8, u16) = from_nibbles_min_size_front(&padded, plain.len()).unwrap();
+    assert_eq!(out, data);
+
+    // Over the minimum: unchanged
+    let mut flavor = NibbleMinSizeFront::new(NibbleHVec::<32>::default(), 2);
+    flavor.try_extend(&plain).unwrap();
+    let unpadded = flavor.finalize().unwrap();
+    assert_eq!(unpadded, plain);
+    let out: (u8, u16) = from_nibbles(&unpadded).unwrap();
+    assert_eq!(out, data);
+
+    // Padding must be all zeroes, and cannot exceed the frame
+    let mut corrupt = padded.clone();
+    corrupt[1] = 0xAA;
+    assert_eq!(
+        from_nibbles_min_size_front::<(u8, u16)>(&corrupt, plain.len()),
+        Err(postcard::Error::DeserializeBadEncoding)
+    );
+    assert_eq!(
+        from_nibbles_min_size_front::<(u8, u16)>(&padded, 9),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+
+    // The storage capacity still applies to the padded frame
+    let mut flavor = NibbleMinSizeFront::new(NibbleHVec::<6>::default(), 8);
+    flavor.try_extend(&plain).unwrap();
+    assert_eq!(flavor.finalize(), Err(postcard::Error::SerializeBufferFull));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn min_size_front_round_trip() {
+    use postcard::{
+        from_nibbles_min_size_front, ser_nibble_flavors::NibbleMinSizeFront,
+        serialize_with_nibble_flavor,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Telemetry<'a> {
+        armed: bool,
+        channel: u8,
+        reading: i32,
+        unit: &'a str,
+    }
+
+    let data = Telemetry {
+        armed: true,
+        channel: 3,
+        reading: -1234,
+        unit: "mV",
+    };
+    let plain: Vec<u8, 32> = postcard::to_nibble_vec(&data).unwrap();
+
+    let frame = serialize_with_nibble_flavor(
+        &data,
+        NibbleMinSizeFront::new(NibbleHVec::<32>::default(), 16),
+    )
+    .unwrap();
+    assert_eq!(frame.len(), 16);
+    assert_eq!(&frame[16 - plain.len()..], &plain[..]);
+    let out: Telemetry = from_nibbles_min_size_front(&frame, plain.len()).unwrap();
+    assert_eq!(out, data);
+
+    // A frame minimum below the message size leaves it as it is
+    let frame = serialize_with_nibble_flavor(
+        &data,
+        NibbleMinSizeFront::new(NibbleHVec::<32>::default(), 4),
+    )
+    .unwrap();
+    assert_eq!(frame, plain);
+    let out: Telemetry = from_nibbles_min_size_front(&frame, plain.len()).unwrap();
+    assert_eq!(out, data);
+}
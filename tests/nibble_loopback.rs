@@ -68,7 +68,10 @@ fn loopback() {
     test_one(0xCDAB3412u32, &[0x92, 0xE8, 0xAC, 0xED, 0x0C]);
     test_one(
         0x1234_5678_90AB_CDEFu64,
-        &[0xEF, 0x9B, 0xAF, 0x85, 0x89, 0xCF, 0x95, 0x9A, 0x12],
+        &[
+            0x99, 0x8E, 0xCA, 0xDC, 0xFC, 0xAA, 0x8D, 0xAF, 0xCE, 0xFD,
+            0x70, // one free nib left at the end
+        ],
     );
 
     // https://github.com/jamesmunns/postcard/pull/83
@@ -90,8 +93,8 @@ fn loopback() {
             tt: 0xACAC_ACAC,
         },
         &[
-            0x9A, 0xDF, 0x95, 0xBF, 0x6B, 0xAB, 0x9B, 0x7D, 0xE9, 0xAE, 0x49, 0x09, 0xA1, 0x2A,
-            0xCD, 0x9B, 0x2E, 0x50, 0xA0, // one free nib left at the end
+            0x9A, 0xDF, 0x95, 0xBF, 0x69, 0x98, 0xEC, 0xA8, 0xEA, 0x8E, 0xDB, 0xEB, 0xBD, 0xEA,
+            0xF2, 0xAC, 0xD9, 0xB2, 0xE5, 0x0A,
         ],
     );
 
@@ -100,7 +103,8 @@ fn loopback() {
     test_one(
         DataEnum::Bim(u64::max_value()),
         &[
-            0x1F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF0, 0x10, // one free nib left at the end
+            0x19, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0x70, // one free nib left at the end
         ],
     );
     test_one(DataEnum::Bib(u16::max_value()), &[0x09, 0xFF, 0xFF, 0x70]);
@@ -165,9 +169,4236 @@ where
     let mut x: ::std::vec::Vec<u8> = vec![];
     x.extend(serialized.deref().iter().cloned());
     assert_eq!(x, ser_rep, "{:x?}", x);
+    #[cfg(feature = "use-std")]
+    assert_eq!(postcard::to_nibble_stdvec(&data).unwrap(), ser_rep);
     {
         // let deserialized: T = from_bytes(serialized.deref()).unwrap();
         let deserialized: T = from_nibbles(&x).unwrap();
         assert_eq!(data, deserialized);
     }
 }
+
+#[cfg(feature = "heapless")]
+/// Counts the `map_start`, `map_entry` and `map_end` calls reaching the flavor it wraps, to
+/// check that modifier flavors pass the map hooks on
+struct MapHooks<F> {
+    flav: F,
+    calls: [usize; 3],
+}
+
+#[cfg(feature = "heapless")]
+impl<F> MapHooks<F> {
+    fn new(flav: F) -> Self {
+        Self {
+            flav,
+            calls: [0; 3],
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<F> postcard::ser_nibble_flavors::NibbleFlavor for MapHooks<F>
+where
+    F: postcard::ser_nibble_flavors::NibbleFlavor,
+{
+    type Output = (F::Output, [usize; 3]);
+
+    fn try_push_u8(&mut self, data: u8) -> postcard::Result<()> {
+        self.flav.try_push_u8(data)
+    }
+
+    fn try_push_nib(&mut self, nib: u8) -> postcard::Result<()> {
+        self.flav.try_push_nib(nib)
+    }
+
+    fn try_extend(&mut self, data: &[u8]) -> postcard::Result<()> {
+        self.flav.try_extend(data)
+    }
+
+    fn map_start(&mut self) -> postcard::Result<()> {
+        self.calls[0] += 1;
+        self.flav.map_start()
+    }
+
+    fn map_entry(&mut self) -> postcard::Result<()> {
+        self.calls[1] += 1;
+        self.flav.map_entry()
+    }
+
+    fn map_end(&mut self) -> postcard::Result<()> {
+        self.calls[2] += 1;
+        self.flav.map_end()
+    }
+
+    fn finalize(self) -> postcard::Result<Self::Output> {
+        Ok((self.flav.finalize()?, self.calls))
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn reports_top_level_variant() {
+    use postcard::from_nibbles_with_variant;
+
+    let cases = [
+        (DataEnum::Bib(u16::MAX), 0),
+        (DataEnum::Bap(u8::MAX), 2),
+        (
+            DataEnum::Chi {
+                a: 0x0F,
+                b: 0xC7C7C7C7,
+            },
+            4,
+        ),
+        (DataEnum::Sho(0x6969, 0x07), 5),
+    ];
+    for (data, index) in cases {
+        let serialized: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+        let (out, variant) = from_nibbles_with_variant::<DataEnum>(&serialized).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(variant, Some(index));
+    }
+
+    // Enums nested inside other values are not reported
+    let serialized: Vec<u8, 32> = to_nibble_vec(&(DataEnum::Bap(1), 3u8)).unwrap();
+    let (out, variant) = from_nibbles_with_variant::<(DataEnum, u8)>(&serialized).unwrap();
+    assert_eq!(out, (DataEnum::Bap(1), 3));
+    assert_eq!(variant, None);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn fold_map_into_sum() {
+    use postcard::fold_nibble_map;
+
+    let mut input: FnvIndexMap<u8, u16, 4> = FnvIndexMap::new();
+    input.insert(0x10, 0x0105).unwrap();
+    input.insert(0x20, 0x0206).unwrap();
+    input.insert(0x30, 0x0307).unwrap();
+    let serialized: Vec<u8, 32> = to_nibble_vec(&input).unwrap();
+
+    let (sum, rest) = fold_nibble_map(&serialized, 0u32, |acc, k: u8, v: u16| {
+        acc + k as u32 * v as u32
+    })
+    .unwrap();
+    assert_eq!(sum, 0x10 * 0x0105 + 0x20 * 0x0206 + 0x30 * 0x0307);
+    assert!(rest.is_empty());
+
+    // An empty map folds to the initial value
+    let empty: FnvIndexMap<u8, u16, 4> = FnvIndexMap::new();
+    let serialized: Vec<u8, 32> = to_nibble_vec(&empty).unwrap();
+    let (sum, _) =
+        fold_nibble_map(&serialized, 7u32, |acc, _k: u8, v: u16| acc + v as u32).unwrap();
+    assert_eq!(sum, 7);
+
+    // A map ending on an odd nibble: the padding nibble is not part of the remainder
+    let (sum, rest) = fold_nibble_map(&[0x21, 0x52, 0x60, 0xAA], 0u32, |acc, k: u8, v: u8| {
+        acc + k as u32 * v as u32
+    })
+    .unwrap();
+    assert_eq!(sum, 5 + 2 * 6);
+    assert_eq!(rest, &[0xAA]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn wrapping_and_saturating() {
+    use core::num::{Saturating, Wrapping};
+
+    // Both are transparent over their inner integer
+    test_one(
+        Wrapping(0x1234_5678_90AB_CDEFu64),
+        &[
+            0x99, 0x8E, 0xCA, 0xDC, 0xFC, 0xAA, 0x8D, 0xAF, 0xCE, 0xFD, 0x70,
+        ],
+    );
+    test_one(Saturating(-32768i32), &[0xFF, 0xFF, 0x03]);
+
+    for v in [0u64, 1, u64::MAX] {
+        let inner: Vec<u8, 16> = to_nibble_vec(&v).unwrap();
+        let wrapped: Vec<u8, 16> = to_nibble_vec(&Wrapping(v)).unwrap();
+        assert_eq!(inner, wrapped);
+        test_one(Wrapping(v), &inner);
+    }
+    for v in [i32::MIN, -1, 0, 1, i32::MAX] {
+        let inner: Vec<u8, 16> = to_nibble_vec(&v).unwrap();
+        let saturating: Vec<u8, 16> = to_nibble_vec(&Saturating(v)).unwrap();
+        assert_eq!(inner, saturating);
+        test_one(Saturating(v), &inner);
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn field_offsets() {
+    use postcard::nibble_field_offset;
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let serialized: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+
+    // `st` takes 6 nibbles, `ei` takes 3
+    assert_eq!(
+        nibble_field_offset::<BasicU8S>(&serialized, 0),
+        Ok((0, true))
+    );
+    assert_eq!(
+        nibble_field_offset::<BasicU8S>(&serialized, 1),
+        Ok((3, true))
+    );
+    assert_eq!(
+        nibble_field_offset::<BasicU8S>(&serialized, 2),
+        Ok((4, false))
+    );
+    // `sf` is 21 nibbles
+    assert_eq!(
+        nibble_field_offset::<BasicU8S>(&serialized, 3),
+        Ok((15, true))
+    );
+    assert_eq!(
+        nibble_field_offset::<BasicU8S>(&serialized, 4),
+        Err(postcard::Error::SerdeDeCustom)
+    );
+
+    // Tuples work the same way
+    let serialized: Vec<u8, 32> = to_nibble_vec(&(true, 0x12u8, 0xC7A5u16)).unwrap();
+    assert_eq!(
+        nibble_field_offset::<(bool, u8, u16)>(&serialized, 2),
+        Ok((1, false))
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn duration_deltas() {
+    use core::time::Duration;
+    use postcard::nibble_time::NibbleDurationDelta;
+
+    let base = Duration::new(1_700_000_000, 0);
+    let samples = [
+        (Duration::new(1_700_000_000, 0), 0i64, &[0x00][..]),
+        (Duration::new(1_700_000_000, 1), 1, &[0x20]),
+        (Duration::new(1_699_999_999, 999_999_999), -1, &[0x10]),
+        (
+            Duration::new(1_700_000_001, 500),
+            1_000_000_500,
+            &[0x9E, 0xF9, 0xDB, 0x9B, 0xFD, 0x00],
+        ),
+        (
+            Duration::new(1_699_999_990, 0),
+            -10_000_000_000,
+            &[0xAA, 0xD8, 0x8D, 0xFC, 0xBF, 0xF7],
+        ),
+    ];
+    for (current, nanos, ser_rep) in samples {
+        let delta = NibbleDurationDelta::between(base, current).unwrap();
+        assert_eq!(delta.nanos(), nanos);
+        test_one(delta, ser_rep);
+
+        let serialized: Vec<u8, 16> = to_nibble_vec(&delta).unwrap();
+        let decoded: NibbleDurationDelta = from_nibbles(&serialized).unwrap();
+        assert_eq!(decoded.apply(base), Some(current));
+    }
+
+    // A sequence of timestamps, each relative to the first one
+    let times = [
+        base,
+        base + Duration::from_millis(250),
+        base - Duration::from_micros(3),
+    ];
+    let deltas: Vec<NibbleDurationDelta, 3> = times
+        .iter()
+        .map(|t| NibbleDurationDelta::between(base, *t).unwrap())
+        .collect();
+    let serialized: Vec<u8, 32> = to_nibble_vec(&deltas).unwrap();
+    let decoded: Vec<NibbleDurationDelta, 3> = from_nibbles(&serialized).unwrap();
+    for (d, t) in decoded.iter().zip(times.iter()) {
+        assert_eq!(d.apply(base).as_ref(), Some(t));
+    }
+
+    // Out of range reconstruction
+    assert_eq!(NibbleDurationDelta(-1).apply(Duration::ZERO), None);
+    assert_eq!(
+        NibbleDurationDelta::between(Duration::ZERO, Duration::MAX),
+        None
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn separated_fields() {
+    use postcard::{from_nibbles_separated, to_nibble_vec_separated};
+
+    let data = EnumStruct { eight: 5, sixt: 6 };
+    let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+    assert_eq!(&plain, &[0x56]);
+    let separated: Vec<u8, 32> = to_nibble_vec_separated(&data).unwrap();
+    assert_eq!(&separated, &[0x5F, 0x6F]);
+    assert_eq!(from_nibbles_separated::<EnumStruct>(&separated), Ok(data));
+
+    // Struct variants are separated too, tuples and tuple variants are not
+    let data = DataEnum::Chi { a: 1, b: 2 };
+    let separated: Vec<u8, 32> = to_nibble_vec_separated(&data).unwrap();
+    assert_eq!(&separated, &[0x41, 0xF0, 0x2F]);
+    assert_eq!(from_nibbles_separated::<DataEnum>(&separated), Ok(data));
+    let data = DataEnum::Sho(0x6969, 0x07);
+    let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+    let separated: Vec<u8, 32> = to_nibble_vec_separated(&data).unwrap();
+    assert_eq!(plain, separated);
+
+    // Nested structs get a separator after each inner and outer field
+    let data = DataEnum::Kim(EnumStruct { eight: 1, sixt: 2 });
+    let separated: Vec<u8, 32> = to_nibble_vec_separated(&data).unwrap();
+    assert_eq!(&separated, &[0x31, 0xF2, 0xF0]);
+    assert_eq!(from_nibbles_separated::<DataEnum>(&separated), Ok(data));
+
+    // Missing or corrupted separators are rejected
+    assert_eq!(
+        from_nibbles_separated::<EnumStruct>(&[0x5E, 0x6F]),
+        Err(postcard::Error::DeserializeBadEncoding)
+    );
+    assert_eq!(
+        from_nibbles_separated::<EnumStruct>(&[0x56]),
+        Err(postcard::Error::DeserializeBadEncoding)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn seq_n_without_prefix() {
+    use postcard::from_nibbles_seq_n;
+
+    // Four u16s back to back (a tuple has no length prefix), followed by unrelated data
+    let mut data: Vec<u8, 32> = to_nibble_vec(&(1u16, 2u16, 0xA5C7u16, 0x0304u16)).unwrap();
+    let used = data.len();
+    data.extend_from_slice(&[0xCD, 0xDE]).unwrap();
+    let (values, rest) = from_nibbles_seq_n::<u16, 4>(&data).unwrap();
+    assert_eq!(values, [1, 2, 0xA5C7, 0x0304]);
+    assert_eq!(rest, &data[used..]);
+
+    let (values, rest) = from_nibbles_seq_n::<u8, 0>(&data).unwrap();
+    assert_eq!(values, []);
+    assert_eq!(rest, &data);
+
+    // Three bools end in the middle of a byte, the rest starts at the next one, like it does
+    // for take_from_nibbles
+    let data = [0x10, 0x10, 0xCD];
+    let (values, rest) = from_nibbles_seq_n::<bool, 3>(&data).unwrap();
+    assert_eq!(values, [true, false, true]);
+    assert_eq!(rest, &[0xCD]);
+    assert_eq!(
+        postcard::take_from_nibbles::<(bool, bool, bool)>(&data),
+        Ok(((true, false, true), &[0xCD][..]))
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn byte_escape_mixed() {
+    use postcard::byte_escape::PostcardByteEscape;
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Mixed {
+        flag: bool,
+        legacy: PostcardByteEscape<BasicU8S, 32>,
+        id: u16,
+    }
+
+    let legacy = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let byte_format: Vec<u8, 32> = postcard::to_vec(&legacy).unwrap();
+
+    let data = Mixed {
+        flag: true,
+        legacy: PostcardByteEscape(legacy),
+        id: 0x0304,
+    };
+    let serialized: Vec<u8, 64> = to_nibble_vec(&data).unwrap();
+    let out: Mixed = from_nibbles(&serialized).unwrap();
+    assert_eq!(out, data);
+
+    // The escaped field is the byte format as an aligned, length prefixed blob
+    let blob_start = serialized
+        .windows(byte_format.len())
+        .position(|w| w == &byte_format[..])
+        .unwrap();
+    assert_eq!(blob_start, 2);
+
+    // Values that don't fit the scratch buffer can't be escaped
+    let too_big = PostcardByteEscape::<_, 4>(u64::MAX);
+    assert_eq!(
+        to_nibble_vec::<_, 64>(&too_big),
+        Err(postcard::Error::SerdeSerCustom)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn lenient_warnings() {
+    use postcard::{de_nibble_flavors::Warning, from_nibbles_lenient};
+
+    // Canonical data decodes without warnings
+    assert_eq!(
+        from_nibbles_lenient::<(u8, u32)>(&[0x50, 0x10]),
+        Ok(((5, 1), vec![]))
+    );
+
+    // A leading zero digit in a nibble varint
+    assert_eq!(
+        from_nibbles_lenient::<u8>(&[0x85]),
+        Ok((5, vec![Warning::NonCanonicalVarint]))
+    );
+    // A trailing zero byte in a byte varint
+    assert_eq!(
+        from_nibbles_lenient::<u32>(&[0x81, 0x00]),
+        Ok((1, vec![Warning::NonCanonicalVarint]))
+    );
+
+    // One padding nibble is expected, anything more is reported
+    assert_eq!(from_nibbles_lenient::<bool>(&[0x10]), Ok((true, vec![])));
+    assert_eq!(
+        from_nibbles_lenient::<bool>(&[0x10, 0x00]),
+        Ok((true, vec![Warning::TrailingData { nibbles: 3 }]))
+    );
+
+    // The regular decoder accepts the same data without complaint
+    assert_eq!(from_nibbles::<u8>(&[0x85]), Ok(5));
+    assert_eq!(from_nibbles::<u32>(&[0x81, 0x00]), Ok(1));
+}
+
+#[cfg(all(feature = "smallvec", feature = "heapless"))]
+#[test]
+fn smallvec_inline_and_spilled() {
+    use smallvec::SmallVec;
+
+    // Below the inline threshold, the elements stay on the stack
+    let input: SmallVec<[u16; 4]> = SmallVec::from_slice(&[1, 2, 0xA5C7]);
+    let serialized: Vec<u8, 32> = to_nibble_vec(&input).unwrap();
+    let out: SmallVec<[u16; 4]> = from_nibbles(&serialized).unwrap();
+    assert_eq!(out, input);
+    assert!(!out.spilled());
+
+    // Above it, they are moved to the heap
+    let input: SmallVec<[u16; 4]> = SmallVec::from_slice(&[1, 2, 3, 4, 5, 0xA5C7]);
+    let serialized: Vec<u8, 32> = to_nibble_vec(&input).unwrap();
+    let out: SmallVec<[u16; 4]> = from_nibbles(&serialized).unwrap();
+    assert_eq!(out, input);
+    assert!(out.spilled());
+
+    // Same wire format as any other sequence
+    let plain: Vec<u8, 32> = to_nibble_vec(&[1u16, 2, 3, 4, 5, 0xA5C7][..]).unwrap();
+    assert_eq!(serialized, plain);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn canonical_maps() {
+    use postcard::to_nibble_vec_canonical;
+
+    let mut a: FnvIndexMap<u8, u16, 8> = FnvIndexMap::new();
+    let mut b: FnvIndexMap<u8, u16, 8> = FnvIndexMap::new();
+    for (k, v) in [(1, 0xA5C7), (0x30, 7), (9, 0x1234), (200, 0)] {
+        a.insert(k, v).unwrap();
+    }
+    for (k, v) in [(200, 0), (9, 0x1234), (1, 0xA5C7), (0x30, 7)] {
+        b.insert(k, v).unwrap();
+    }
+    assert_eq!(a, b);
+
+    // Iteration order leaks into the regular output...
+    let plain_a: Vec<u8, 64> = to_nibble_vec(&a).unwrap();
+    let plain_b: Vec<u8, 64> = to_nibble_vec(&b).unwrap();
+    assert_ne!(plain_a, plain_b);
+
+    // ...but not into the canonical one
+    let canon_a: Vec<u8, 64> = to_nibble_vec_canonical(&a).unwrap();
+    let canon_b: Vec<u8, 64> = to_nibble_vec_canonical(&b).unwrap();
+    assert_eq!(canon_a, canon_b);
+    assert_eq!(canon_a.len(), plain_a.len());
+    let out: FnvIndexMap<u8, u16, 8> = from_nibbles(&canon_a).unwrap();
+    assert_eq!(out, a);
+
+    // Nested maps with byte-aligned keys, inside a struct
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Index {
+        flag: bool,
+        names: FnvIndexMap<String<8>, FnvIndexMap<u8, bool, 4>, 4>,
+        id: u16,
+    }
+
+    let entries: [(&str, &[(u8, bool)]); 3] = [
+        ("alpha", &[(1, true), (2, false)]),
+        ("b", &[(7, true)]),
+        ("gamma", &[(3, false), (4, true), (5, true)]),
+    ];
+    let build = |reverse: bool| {
+        let mut names = FnvIndexMap::new();
+        for i in 0..entries.len() {
+            let (k, vals) = entries[if reverse { entries.len() - 1 - i } else { i }];
+            let mut inner = FnvIndexMap::new();
+            for j in 0..vals.len() {
+                let (ik, iv) = vals[if reverse { vals.len() - 1 - j } else { j }];
+                inner.insert(ik, iv).unwrap();
+            }
+            names.insert(String::from(k), inner).unwrap();
+        }
+        Index {
+            flag: true,
+            names,
+            id: 0x0304,
+        }
+    };
+    let x = build(false);
+    let y = build(true);
+    assert_eq!(x, y);
+    let plain_x: Vec<u8, 128> = to_nibble_vec(&x).unwrap();
+    let plain_y: Vec<u8, 128> = to_nibble_vec(&y).unwrap();
+    assert_ne!(plain_x, plain_y);
+
+    let canon_x: Vec<u8, 128> = to_nibble_vec_canonical(&x).unwrap();
+    let canon_y: Vec<u8, 128> = to_nibble_vec_canonical(&y).unwrap();
+    assert_eq!(canon_x, canon_y);
+    let out: Index = from_nibbles(&canon_x).unwrap();
+    assert_eq!(out, x);
+
+    // Values without maps are unaffected
+    let data = DataEnum::Chi { a: 1, b: 2 };
+    let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+    let canon: Vec<u8, 32> = to_nibble_vec_canonical(&data).unwrap();
+    assert_eq!(plain, canon);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn canonical_passes_variants_on() {
+    use postcard::de_nibble_flavors::{NibbleNamedVariants as DeNamed, NibbleSlice as DeSlice};
+    use postcard::ser_nibble_flavors::{
+        NibbleCanonical, NibbleHVec, NibbleNamedVariants, NibbleSlice,
+    };
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+    enum Mode {
+        Idle,
+        Run(u8),
+        Stop { at: u16 },
+    }
+
+    type Modes = FnvIndexMap<u8, FnvIndexMap<u8, Mode, 4>, 4>;
+    let entries: [(u8, &[(u8, Mode)]); 2] = [
+        (7, &[(1, Mode::Run(2)), (9, Mode::Idle), (4, Mode::Run(6))]),
+        (2, &[(3, Mode::Stop { at: 0x1234 })]),
+    ];
+    let build = |reverse: bool| {
+        let mut modes = Modes::new();
+        for i in 0..entries.len() {
+            let (k, vals) = entries[if reverse { entries.len() - 1 - i } else { i }];
+            let mut inner = FnvIndexMap::new();
+            for j in 0..vals.len() {
+                let (ik, iv) = &vals[if reverse { vals.len() - 1 - j } else { j }];
+                inner.insert(*ik, iv.clone()).unwrap();
+            }
+            modes.insert(k, inner).unwrap();
+        }
+        modes
+    };
+    let canon = |modes: &Modes| {
+        serialize_with_nibble_flavor(
+            modes,
+            NibbleCanonical::<_, 256>::new(NibbleNamedVariants::new(NibbleHVec::<128>::default())),
+        )
+        .unwrap()
+    };
+
+    // The inner flavor writes the variants by name, in the sorted order
+    let x = canon(&build(false));
+    assert_eq!(x, canon(&build(true)));
+    assert_eq!(x.windows(3).filter(|w| w == b"Run").count(), 2);
+    let mut buf = [0u8; 128];
+    let named = serialize_with_nibble_flavor(
+        &build(false),
+        NibbleNamedVariants::new(NibbleSlice::new(&mut buf)),
+    )
+    .unwrap();
+    assert_eq!(x.len(), named.len());
+    let (out, _) = from_nibbles_with_flavor::<Modes, _>(DeNamed::new(DeSlice::new(&x))).unwrap();
+    assert_eq!(out, build(false));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn scoped_borrowed_decode() {
+    use postcard::with_nibbles;
+
+    // `bytes` as written by `serialize_bytes`, then `str_s`
+    let serialized = [0x30, 0x01, 0x02, 0x03, 0x50, b'h', b'e', b'l', b'l', b'o'];
+
+    // Borrowed fields stay inside the closure, only `'static` data comes out
+    let (sum, name) = with_nibbles(&serialized, |res: postcard::Result<RefStruct>| {
+        let rs = res.unwrap();
+        let sum: u32 = rs.bytes.iter().map(|b| *b as u32).sum();
+        let name: String<8> = String::from(rs.str_s);
+        (sum, name)
+    });
+    assert_eq!(sum, 6);
+    assert_eq!(name, "hello");
+
+    // Errors are passed to the closure as well
+    let failed = with_nibbles(&serialized[..2], |res: postcard::Result<RefStruct>| {
+        res.is_err()
+    });
+    assert!(failed);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn fixed_point_samples() {
+    use postcard::fixed_point::{NibbleQ15, NibbleQ31};
+
+    // A length nibble, padding, then the raw little endian integer
+    test_one(NibbleQ15(0x4000), &[0x20, 0x00, 0x40]);
+    test_one(NibbleQ15(-1), &[0x20, 0xFF, 0xFF]);
+    test_one(NibbleQ31(i32::MIN), &[0x40, 0x00, 0x00, 0x00, 0x80]);
+
+    // The width does not depend on the value
+    let samples: [NibbleQ15; 4] = [0.0, 0.25, -0.75, 0.999].map(NibbleQ15::from_f32);
+    let serialized: Vec<u8, 32> = to_nibble_vec(&samples).unwrap();
+    assert_eq!(serialized.len(), 4 * 3);
+    let out: [NibbleQ15; 4] = from_nibbles(&serialized).unwrap();
+    assert_eq!(out, samples);
+
+    // After a bool nibble, the length takes the rest of the byte
+    let serialized: Vec<u8, 16> = to_nibble_vec(&(true, NibbleQ31(7), 5u8)).unwrap();
+    assert_eq!(&serialized, &[0x14, 0x07, 0x00, 0x00, 0x00, 0x50]);
+    let out: (bool, NibbleQ31, u8) = from_nibbles(&serialized).unwrap();
+    assert_eq!(out, (true, NibbleQ31(7), 5));
+    assert_eq!(
+        from_nibbles::<(bool, NibbleQ31)>(&serialized[..3]),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+
+    // The byte format stores them as a byte string too
+    let mut buf = [0u8; 8];
+    let used = postcard::to_slice(&NibbleQ15(-2), &mut buf).unwrap();
+    assert_eq!(used, &[0x02, 0xFE, 0xFF]);
+    assert_eq!(postcard::from_bytes(used), Ok(NibbleQ15(-2)));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn untagged_alternatives() {
+    use postcard::untagged::NibbleUntagged;
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+    struct Flags {
+        armed: bool,
+        muted: bool,
+        online: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+    struct Reading {
+        channel: u8,
+        value: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+    #[serde(from = "NibbleUntagged<Flags, Reading>")]
+    #[serde(into = "NibbleUntagged<Flags, Reading>")]
+    enum Message {
+        Flags(Flags),
+        Reading(Reading),
+    }
+
+    // Plain serde conversions stand in for `#[serde(untagged)]`
+    impl From<NibbleUntagged<Flags, Reading>> for Message {
+        fn from(u: NibbleUntagged<Flags, Reading>) -> Self {
+            match u {
+                NibbleUntagged::First(f) => Message::Flags(f),
+                NibbleUntagged::Second(r) => Message::Reading(r),
+            }
+        }
+    }
+    impl From<Message> for NibbleUntagged<Flags, Reading> {
+        fn from(m: Message) -> Self {
+            match m {
+                Message::Flags(f) => NibbleUntagged::First(f),
+                Message::Reading(r) => NibbleUntagged::Second(r),
+            }
+        }
+    }
+
+    // No tag on the wire, just the variant's fields
+    let flags = Message::Flags(Flags {
+        armed: true,
+        muted: false,
+        online: true,
+    });
+    test_one(flags.clone(), &[0x10, 0x10]);
+    let reading = Message::Reading(Reading {
+        channel: 7,
+        value: 0x1234,
+    });
+    test_one(reading.clone(), &[0x7B, 0x42, 0x40]);
+
+    // Rewinding also works in the middle of a message
+    let pair = (reading, 3u8, flags);
+    let serialized: Vec<u8, 32> = to_nibble_vec(&pair).unwrap();
+    let out: (Message, u8, Message) = from_nibbles(&serialized).unwrap();
+    assert_eq!(out, pair);
+
+    // The error of the last alternative is reported when none match
+    assert_eq!(
+        from_nibbles::<Message>(&[0xF0]),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn packed_bools() {
+    use postcard::de_nibble_flavors::{
+        NibbleNamedVariants as DeNamed, NibblePackedBools as DePacked, NibbleSlice as DeSlice,
+    };
+    use postcard::ser_nibble_flavors::{
+        NibbleCanonical, NibbleHVec, NibbleNamedVariants, NibblePackedBools, NibbleSlice,
+    };
+    use postcard::{
+        from_nibbles_packed, from_nibbles_with_flavor, serialize_with_nibble_flavor,
+        to_nibble_vec_packed,
+    };
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Flags {
+        armed: bool,
+        open: bool,
+        lit: bool,
+        mode: u8,
+        locked: bool,
+        active: bool,
+        level: u16,
+    }
+
+    let data = Flags {
+        armed: true,
+        open: false,
+        lit: true,
+        mode: 3,
+        locked: false,
+        active: true,
+        level: 9,
+    };
+    let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+    assert_eq!(&plain, &[0x10, 0x13, 0x01, 0x91]);
+    let packed: Vec<u8, 32> = to_nibble_vec_packed(&data).unwrap();
+    assert_eq!(&packed, &[0x53, 0x29, 0x10]);
+    assert_eq!(from_nibbles_packed::<Flags>(&packed), Ok(data));
+
+    // Runs longer than four bools continue in the next nibble
+    let data = [true, true, false, true, false, true];
+    let packed: Vec<u8, 32> = to_nibble_vec_packed(&data).unwrap();
+    assert_eq!(&packed, &[0xB2]);
+    assert_eq!(from_nibbles_packed::<[bool; 6]>(&packed), Ok(data));
+
+    // The unused bits of a bool nibble must be zero
+    assert_eq!(
+        from_nibbles_packed::<(bool, bool, u8)>(&[0x53]),
+        Err(postcard::Error::DeserializeBadBool)
+    );
+
+    // Map entries continue a run, like the deserializer expects
+    let mut map: FnvIndexMap<bool, bool, 2> = FnvIndexMap::new();
+    map.insert(false, true).unwrap();
+    map.insert(true, false).unwrap();
+    let packed: Vec<u8, 8> = to_nibble_vec_packed(&map).unwrap();
+    assert_eq!(&packed, &[0x26]);
+    assert_eq!(from_nibbles_packed(&packed), Ok(map.clone()));
+
+    // Sorted around the packing, the entries are packed in their sorted order
+    let mut rev: FnvIndexMap<bool, bool, 2> = FnvIndexMap::new();
+    rev.insert(true, false).unwrap();
+    rev.insert(false, true).unwrap();
+    let canon = |map: &FnvIndexMap<bool, bool, 2>| {
+        serialize_with_nibble_flavor(
+            map,
+            NibbleCanonical::<_, 32>::new(NibblePackedBools::new(NibbleHVec::<8>::default())),
+        )
+        .unwrap()
+    };
+    assert_eq!(canon(&rev), packed);
+    assert_eq!(canon(&map), packed);
+
+    // Variants are handed on, after the pending booleans
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Idle,
+        Run(bool),
+    }
+    let data = (true, Mode::Run(true), false);
+    let mut buf = [0u8; 32];
+    let used = serialize_with_nibble_flavor(
+        &data,
+        NibblePackedBools::new(NibbleNamedVariants::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    assert_eq!(used.windows(3).filter(|w| w == b"Run").count(), 1);
+    let flavor = DePacked::new(DeNamed::new(DeSlice::new(used)));
+    assert_eq!(
+        from_nibbles_with_flavor::<(bool, Mode, bool), _>(flavor).map(|(v, _)| v),
+        Ok(data)
+    );
+}
+
+#[test]
+fn out_of_range_discriminant() {
+    use postcard::{from_nibbles, from_nibbles_with_variant, Error};
+
+    // Sho is the last of the six variants
+    assert!(from_nibbles::<DataEnum>(&[0x51, 0x20]).is_ok());
+    assert_eq!(
+        from_nibbles::<DataEnum>(&[0x60]),
+        Err(Error::DeserializeBadEnum { index: 6, count: 6 })
+    );
+    assert_eq!(
+        from_nibbles::<DataEnum>(&[0xF7]),
+        Err(Error::DeserializeBadEnum {
+            index: 63,
+            count: 6
+        })
+    );
+    assert_eq!(
+        from_nibbles_with_variant::<DataEnum>(&[0x60]),
+        Err(Error::DeserializeBadEnum { index: 6, count: 6 })
+    );
+}
+
+#[test]
+fn reserved_tail() {
+    use postcard::{from_nibbles, to_nibble_slice_reserve, Error};
+
+    let data = DataEnum::Chi { a: 1, b: 300 };
+    // Stale data in the buffer must not leak into the padding nibble
+    let mut buf = [0xAA; 16];
+    let (used, tail) = to_nibble_slice_reserve(&data, &mut buf, 4).unwrap();
+    let used_len = used.len();
+    assert_eq!(used, &[0x41, 0xAC, 0x02]);
+    assert_eq!(tail.len(), 4);
+
+    // Sign the message in place, right behind it
+    let signature = used
+        .iter()
+        .fold(0u32, |acc, b| acc.rotate_left(5) ^ *b as u32);
+    tail.copy_from_slice(&signature.to_le_bytes());
+
+    let frame = &buf[..used_len + 4];
+    assert_eq!(&frame[used_len..], &signature.to_le_bytes());
+    assert_eq!(from_nibbles::<DataEnum>(frame), Ok(data));
+
+    // A message ending mid-byte includes that byte, zero padded
+    let mut buf = [0xAA; 16];
+    let (used, tail) = to_nibble_slice_reserve(&(1u8, 2u8, 3u8), &mut buf, 1).unwrap();
+    assert_eq!(used, &[0x12, 0x30]);
+    assert_eq!(tail, &[0xAA]);
+
+    // The message alone fits, but not together with the reserved bytes
+    let mut buf = [0u8; 6];
+    assert_eq!(
+        to_nibble_slice_reserve(&DataEnum::Chi { a: 1, b: 300 }, &mut buf, 4),
+        Err(Error::SerializeBufferFull)
+    );
+    assert_eq!(
+        to_nibble_slice_reserve(&0u8, &mut buf, 7),
+        Err(Error::SerializeBufferFull)
+    );
+}
+
+#[test]
+fn ranges() {
+    // serde writes both as a struct of `start` and `end`
+    test_one(3u32..300, &[0x03, 0xAC, 0x02]);
+    test_one(0u32..0, &[0x00, 0x00]);
+    test_one(-5i16..=1000, &[0x09, 0xD0, 0x0F]);
+    test_one(i16::MIN..=i16::MAX, &[0xFF, 0xFF, 0x03, 0xFE, 0xFF, 0x03]);
+
+    // A range after a nibble sized value starts mid-byte
+    test_one((true, 7u32..8), &[0x10, 0x70, 0x80]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn two_level_tags() {
+    use postcard::ser_nibble_flavors::NibbleTagMap;
+    use postcard::{from_nibbles_with_tags, to_nibble_vec_with_tags};
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    enum Command {
+        MotorStart,
+        MotorStop,
+        MotorSpeed(u16),
+        SensorRead(u8),
+        SensorCalibrate { offset: i16 },
+        PowerOff,
+        PowerReboot,
+    }
+
+    // Motor commands in category 1, sensors in 2, power in 3
+    const GROUPS: [(u8, u32); 3] = [(1, 0), (2, 3), (3, 5)];
+
+    struct Groups;
+
+    impl NibbleTagMap for Groups {
+        fn is_mapped(&self, name: &'static str) -> bool {
+            name == "Command"
+        }
+
+        fn tag(&self, _name: &'static str, index: u32) -> Option<(u8, u8)> {
+            let (category, first) = GROUPS.iter().rev().find(|(_, first)| index >= *first)?;
+            Some((*category, (index - first) as u8))
+        }
+
+        fn index(&self, _name: &'static str, category: u8, subtype: u8) -> Option<u32> {
+            let i = GROUPS.iter().position(|(c, _)| *c == category)?;
+            let index = GROUPS[i].1 + subtype as u32;
+            let end = GROUPS.get(i + 1).map_or(7, |(_, first)| *first);
+            (index < end).then_some(index)
+        }
+    }
+
+    fn check(data: Command, expected: &[u8]) {
+        let ser: Vec<u8, 32> = to_nibble_vec_with_tags(&data, Groups).unwrap();
+        assert_eq!(&ser, expected);
+        assert_eq!(from_nibbles_with_tags::<Command, _>(&ser, Groups), Ok(data));
+    }
+
+    check(Command::MotorStart, &[0x10]);
+    check(Command::PowerReboot, &[0x31]);
+    check(Command::MotorSpeed(5), &[0x12, 0x50]);
+    check(Command::SensorCalibrate { offset: -3 }, &[0x21, 0x05]);
+
+    // Other enums keep their varint tags
+    let data = (BasicEnum::Bap, Command::SensorRead(7));
+    let ser: Vec<u8, 32> = to_nibble_vec_with_tags(&data, Groups).unwrap();
+    assert_eq!(&ser, &[0x22, 0x07]);
+    assert_eq!(from_nibbles_with_tags(&ser, Groups), Ok(data));
+
+    // Tags without a variant are rejected
+    assert_eq!(
+        from_nibbles_with_tags::<Command, _>(&[0x13], Groups),
+        Err(postcard::Error::DeserializeBadEncoding)
+    );
+    assert_eq!(
+        from_nibbles_with_tags::<Command, _>(&[0x40], Groups),
+        Err(postcard::Error::DeserializeBadEncoding)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn custom_decode() {
+    use postcard::de_nibble_flavors::NibbleFlavor;
+    use postcard::nibble_decode::{NibbleDecode, NibbleDecoded};
+    use serde::Deserializer;
+
+    /// A version sent as a single `u16` varint, `major << 4 | minor`
+    #[derive(Debug, Eq, PartialEq)]
+    struct Version {
+        major: u16,
+        minor: u8,
+    }
+
+    impl NibbleDecode for Version {
+        fn decode<'de, F: NibbleFlavor<'de>>(flavor: &mut F) -> postcard::Result<Self> {
+            let mut raw = 0u32;
+            loop {
+                let nib = flavor.try_take_nib()?;
+                raw = (raw << 3) | (nib & 0b111) as u32;
+                if nib & 0b1000 == 0 {
+                    break;
+                }
+            }
+            if raw > u16::MAX as u32 || raw & 0xF == 0xF {
+                return Err(postcard::Error::DeserializeBadEncoding);
+            }
+            Ok(Version {
+                major: (raw >> 4) as u16,
+                minor: (raw & 0xF) as u8,
+            })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Version {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            postcard::nibble_decode::deserialize(deserializer)
+        }
+    }
+
+    let ser: Vec<u8, 32> = to_nibble_vec(&(7u8, 0x1234u16, true, "hi")).unwrap();
+    let out: (u8, Version, bool, &str) = from_nibbles(&ser).unwrap();
+    assert_eq!(
+        out,
+        (
+            7,
+            Version {
+                major: 0x123,
+                minor: 4
+            },
+            true,
+            "hi"
+        )
+    );
+
+    // Or through the wrapper
+    let ser: Vec<u8, 32> = to_nibble_vec(&[0x10u16, 0x21]).unwrap();
+    let out: [NibbleDecoded<Version>; 2] = from_nibbles(&ser).unwrap();
+    assert_eq!(out[0].0, Version { major: 1, minor: 0 });
+    assert_eq!(out[1].0, Version { major: 2, minor: 1 });
+
+    // Errors of the decode impl itself are reported as custom errors
+    let ser: Vec<u8, 32> = to_nibble_vec(&0x1Fu16).unwrap();
+    assert_eq!(
+        from_nibbles::<Version>(&ser),
+        Err(postcard::Error::SerdeDeCustom)
+    );
+}
+
+#[test]
+fn size_bounds() {
+    use postcard::nibble_size_bounds;
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    // u16, u8 and u64 are nibble varints of 1..=6, 1..=3 and 1..=22 nibbles, u32 is a
+    // byte varint of 1..=5 bytes
+    assert_eq!(nibble_size_bounds(&data).unwrap(), (5, 41));
+
+    #[derive(Serialize)]
+    struct Varints {
+        a: i16,
+        b: i32,
+        c: i64,
+        d: i128,
+        e: u128,
+        f: i8,
+    }
+    let zero = Varints {
+        a: 0,
+        b: 0,
+        c: 0,
+        d: 0,
+        e: 0,
+        f: 0,
+    };
+    let full = Varints {
+        a: i16::MIN,
+        b: i32::MIN,
+        c: i64::MIN,
+        d: i128::MIN,
+        e: u128::MAX,
+        f: i8::MIN,
+    };
+    let bounds = nibble_size_bounds(&zero).unwrap();
+    assert_eq!(bounds, (8, 2 * (3 + 5) + 22 + 43 + 43 + 3));
+    assert_eq!(nibble_size_bounds(&full).unwrap(), bounds);
+
+    // The actual sizes are within the bounds
+    #[cfg(feature = "heapless")]
+    for v in [&zero, &full] {
+        let ser: Vec<u8, 64> = to_nibble_vec(v).unwrap();
+        assert!(bounds.0.div_ceil(2) <= ser.len() && ser.len() <= bounds.1.div_ceil(2));
+    }
+
+    // Shapes are taken from the value: strings keep their length but may need padding,
+    // chars are one to four bytes
+    assert_eq!(nibble_size_bounds(&(5u8, "abc")).unwrap(), (8, 10));
+    assert_eq!(nibble_size_bounds(&('a', 1.0f32)).unwrap(), (12, 18));
+    assert_eq!(nibble_size_bounds(&Some(3u16)).unwrap(), (2, 7));
+    assert_eq!(nibble_size_bounds(&None::<u16>).unwrap(), (1, 1));
+}
+
+#[cfg(feature = "use-std")]
+#[test]
+fn stdvec_round_trip() {
+    use postcard::to_nibble_stdvec;
+
+    let data = (
+        7u8,
+        DataEnum::Chi {
+            a: 0x0F,
+            b: 0xC7C7C7C7,
+        },
+        "nibbles",
+        0xABCDu16,
+        vec![1.5f32, -2.0],
+        true,
+    );
+    let ser = to_nibble_stdvec(&data).unwrap();
+    let out: (u8, DataEnum, &str, u16, std::vec::Vec<f32>, bool) = from_nibbles(&ser).unwrap();
+    assert_eq!(out, data);
+
+    // Odd nibble counts leave a zero nibble at the end
+    assert_eq!(to_nibble_stdvec(&(1u8, 2u8, 3u8)).unwrap(), &[0x12, 0x30]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cow_scratch() {
+    use postcard::to_nibble_cow;
+    use std::borrow::Cow;
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let expected = postcard::to_nibble_allocvec(&data).unwrap();
+
+    // Fits, including an exact fit
+    for len in [expected.len(), 64] {
+        let mut scratch = [0u8; 64];
+        match to_nibble_cow(&data, &mut scratch[..len]).unwrap() {
+            Cow::Borrowed(ser) => assert_eq!(ser, &expected[..]),
+            Cow::Owned(_) => panic!("{} bytes of scratch should fit", len),
+        }
+    }
+
+    // Overflows
+    let mut scratch = [0u8; 64];
+    match to_nibble_cow(&data, &mut scratch[..expected.len() - 1]).unwrap() {
+        Cow::Owned(ser) => assert_eq!(ser, expected),
+        Cow::Borrowed(_) => panic!("the scratch buffer is too small"),
+    }
+    let ser = to_nibble_cow(&data, &mut []).unwrap();
+    assert_eq!(from_nibbles::<BasicU8S>(&ser).unwrap(), data);
+
+    // Other errors are not retried
+    let unknown_len = core::iter::repeat_n(1u8, 3);
+    assert_eq!(
+        to_nibble_cow(&Unsized(unknown_len), &mut scratch),
+        Err(postcard::Error::SerializeSeqLengthUnknown)
+    );
+
+    struct Unsized<I>(I);
+
+    impl<I: Iterator<Item = u8> + Clone> Serialize for Unsized<I> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.clone().filter(|_| true))
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn named_variants() {
+    use postcard::{from_nibbles_named_variants, to_nibble_vec_named_variants};
+
+    // The variant name, aligned like a string, then the payload
+    let ser: Vec<u8, 32> = to_nibble_vec_named_variants(&DataEnum::Bap(5)).unwrap();
+    assert_eq!(ser.deref(), &[0x30, b'B', b'a', b'p', 0x50]);
+    let ser: Vec<u8, 32> = to_nibble_vec_named_variants(&(true, BasicEnum::Bim)).unwrap();
+    assert_eq!(ser.deref(), &[0x13, b'B', b'i', b'm']);
+
+    let data = (
+        BasicEnum::Bap,
+        DataEnum::Chi {
+            a: 0x0F,
+            b: 0xC7C7C7C7,
+        },
+        DataEnum::Kim(EnumStruct {
+            eight: 0xF0,
+            sixt: 0xACAC,
+        }),
+        DataEnum::Sho(0x6969, 0x07),
+        BasicEnum::Bib,
+    );
+    let ser: Vec<u8, 64> = to_nibble_vec_named_variants(&data).unwrap();
+    let out: (BasicEnum, DataEnum, DataEnum, DataEnum, BasicEnum) =
+        from_nibbles_named_variants(&ser).unwrap();
+    assert_eq!(out, data);
+
+    // Variants are matched by name, not by position
+    #[derive(Deserialize, Debug, PartialEq, Eq)]
+    enum Reordered {
+        Bap,
+        Bim,
+        Bib,
+    }
+    let ser: Vec<u8, 32> = to_nibble_vec_named_variants(&BasicEnum::Bib).unwrap();
+    assert_eq!(from_nibbles_named_variants(&ser), Ok(Reordered::Bib));
+
+    // Unknown names are rejected
+    assert_eq!(
+        from_nibbles_named_variants::<BasicEnum>(&[0x30, b'B', b'o', b'p']),
+        Err(postcard::Error::DeserializeBadEncoding)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn custom_flavor_remainder() {
+    use postcard::de_nibble_flavors::{NibblePackedBools, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, to_nibble_vec_packed};
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let mut buf: Vec<u8, 64> = to_nibble_vec(&data).unwrap();
+    let used = buf.len();
+    buf.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+
+    let (out, rest) = from_nibbles_with_flavor::<BasicU8S, _>(NibbleSlice::new(&buf)).unwrap();
+    assert_eq!(out, data);
+    // The message ends on a byte boundary
+    assert_eq!(rest, &buf[used..]);
+
+    // Modifier flavors in front of the slice
+    let mut buf: Vec<u8, 64> = to_nibble_vec_packed(&(true, false, true, 3u8)).unwrap();
+    let used = buf.len();
+    buf.extend_from_slice(&[0xAA, 0x55]).unwrap();
+    let flavor = NibblePackedBools::new(NibbleSlice::new(&buf));
+    let (out, rest) = from_nibbles_with_flavor::<(bool, bool, bool, u8), _>(flavor).unwrap();
+    assert_eq!(out, (true, false, true, 3));
+    assert_eq!(rest, &buf[used..]);
+}
+
+#[test]
+fn to_slice_partial_byte() {
+    use postcard::to_nibble_slice;
+
+    // A single nibble still takes up a whole byte, with stale data cleared
+    let mut buf = [0xFF; 4];
+    let used = to_nibble_slice(&BasicEnum::Bim, &mut buf).unwrap();
+    assert_eq!(used, &[0x10]);
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let mut buf = [0u8; 32];
+    let len = to_nibble_slice(&data, &mut buf).unwrap().len();
+    assert_eq!(len, 20);
+    assert_eq!(from_nibbles::<BasicU8S>(&buf[..len]).unwrap(), data);
+
+    // Too small by a single byte, or without any space
+    assert_eq!(
+        to_nibble_slice(&data, &mut buf[..19]),
+        Err(postcard::Error::SerializeBufferFull)
+    );
+    assert_eq!(
+        to_nibble_slice(&BasicEnum::Bim, &mut []),
+        Err(postcard::Error::SerializeBufferFull)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn aead_round_trip() {
+    use postcard::ser_nibble_flavors::{NibbleAeadCipher, NibbleHVec};
+    use postcard::{de_nibble_flavors, from_nibbles_with_flavor, ser_nibble_flavors};
+    use postcard::{serialize_with_nibble_flavor, Error};
+
+    /// XORs with a key byte, the tag is a running sum over the AAD and the ciphertext.
+    /// Good for testing the plumbing, not for anything else.
+    struct StubAead(u8);
+
+    impl StubAead {
+        fn tag(&self, aad: &[u8], ciphertext: &[u8]) -> [u8; 2] {
+            let sum = aad
+                .iter()
+                .chain(ciphertext)
+                .fold(self.0 as u16, |acc, b| acc.rotate_left(3) ^ *b as u16);
+            sum.to_le_bytes()
+        }
+    }
+
+    impl NibbleAeadCipher for StubAead {
+        type Tag = [u8; 2];
+        const TAG_LEN: usize = 2;
+
+        fn encrypt_in_place(&mut self, aad: &[u8], buffer: &mut [u8]) -> postcard::Result<[u8; 2]> {
+            buffer.iter_mut().for_each(|b| *b ^= self.0);
+            Ok(self.tag(aad, buffer))
+        }
+
+        fn decrypt_in_place(
+            &mut self,
+            aad: &[u8],
+            buffer: &mut [u8],
+            tag: &[u8],
+        ) -> postcard::Result<()> {
+            if self.tag(aad, buffer) != tag {
+                return Err(Error::DeserializeBadEncoding);
+            }
+            buffer.iter_mut().for_each(|b| *b ^= self.0);
+            Ok(())
+        }
+    }
+
+    let data = DataEnum::Chi {
+        a: 0x0F,
+        b: 0xC7C7C7C7,
+    };
+    let aad = b"route 7";
+    let plain: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+    let flavor =
+        ser_nibble_flavors::NibbleAead::new(NibbleHVec::<32>::default(), aad, StubAead(0x5A));
+    let mut sealed = serialize_with_nibble_flavor(&data, flavor).unwrap();
+    assert_eq!(sealed.len(), plain.len() + 2);
+    assert_ne!(&sealed[..plain.len()], &plain[..]);
+
+    let mut buf = sealed.clone();
+    let flavor = de_nibble_flavors::NibbleAead::new(&mut buf, aad, StubAead(0x5A)).unwrap();
+    let (out, rest) = from_nibbles_with_flavor::<DataEnum, _>(flavor).unwrap();
+    assert_eq!(out, data);
+    // The last byte holds a free nibble
+    assert_eq!(rest, &plain[plain.len() - 1..]);
+
+    // Tampered associated data, or a tampered message, are rejected before decoding
+    let mut buf = sealed.clone();
+    assert!(matches!(
+        de_nibble_flavors::NibbleAead::new(&mut buf, b"route 8", StubAead(0x5A)),
+        Err(Error::DeserializeBadEncoding)
+    ));
+    sealed[1] ^= 0x10;
+    assert!(matches!(
+        de_nibble_flavors::NibbleAead::new(&mut sealed, aad, StubAead(0x5A)),
+        Err(Error::DeserializeBadEncoding)
+    ));
+    assert!(matches!(
+        de_nibble_flavors::NibbleAead::new(&mut [0u8], aad, StubAead(0x5A)),
+        Err(Error::DeserializeUnexpectedEnd)
+    ));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn take_concatenated() {
+    use postcard::take_from_nibbles;
+
+    // An odd number of nibbles, padded to a whole byte, then an even one
+    let mut stream: Vec<u8, 16> = to_nibble_vec(&0x1234u16).unwrap();
+    assert_eq!(stream.len(), 3);
+    let second: Vec<u8, 16> = to_nibble_vec(&0xA5C7u16).unwrap();
+    stream.extend_from_slice(&second).unwrap();
+
+    let (first, rest) = take_from_nibbles::<u16>(&stream).unwrap();
+    assert_eq!(first, 0x1234);
+    assert_eq!(rest, &second[..]);
+    let (value, rest) = take_from_nibbles::<u16>(rest).unwrap();
+    assert_eq!(value, 0xA5C7);
+    assert!(rest.is_empty());
+
+    // Decoding in a loop
+    let mut rest = &stream[..];
+    let mut values = std::vec::Vec::new();
+    while !rest.is_empty() {
+        let (value, tail) = take_from_nibbles::<u16>(rest).unwrap();
+        values.push(value);
+        rest = tail;
+    }
+    assert_eq!(values, [0x1234, 0xA5C7]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn wide_nibble_varints() {
+    use postcard::Error;
+
+    // u64 takes up to 22 nibbles, the first one only holding the top bit
+    test_one(0u64, &[0x00]);
+    test_one(8u64, &[0x90]);
+    let max: Vec<u8, 16> = to_nibble_vec(&u64::MAX).unwrap();
+    assert_eq!(max.len(), 11);
+    assert_eq!(max[0], 0x9F);
+    for v in [i64::MIN, -1, 0, 1, i64::MAX] {
+        let ser: Vec<u8, 16> = to_nibble_vec(&v).unwrap();
+        assert_eq!(from_nibbles::<i64>(&ser), Ok(v));
+    }
+
+    // Still continued on the 22nd nibble
+    assert_eq!(
+        from_nibbles::<u64>(&[0x99; 12]),
+        Err(Error::DeserializeBadVlu64N)
+    );
+    // 22 nibbles, but more than 64 bits
+    let mut wide = [0x99u8; 11];
+    wide[0] = 0xA9;
+    wide[10] = 0x90;
+    assert_eq!(from_nibbles::<u64>(&wide), Err(Error::DeserializeBadVlu64N));
+
+    // u128 takes up to 43 nibbles, the first one holding the top two bits
+    test_one(0u128, &[0x00]);
+    let mut max = [0xFFu8; 22];
+    max[0] = 0xBF;
+    max[21] = 0x70;
+    test_one(u128::MAX, &max);
+    let mut mid = [0x88u8; 11];
+    mid[0] = 0xA8;
+    mid[10] = 0x80;
+    test_one(1u128 << 64, &mid);
+    for v in [i128::MIN, -1, 0, 1, i128::MAX] {
+        let ser: Vec<u8, 32> = to_nibble_vec(&v).unwrap();
+        assert_eq!(from_nibbles::<i128>(&ser), Ok(v));
+    }
+    assert_eq!(
+        from_nibbles::<u128>(&[0x99; 23]),
+        Err(Error::DeserializeBadVlu128N)
+    );
+}
+
+#[cfg(all(feature = "heapless", feature = "alloc"))]
+#[test]
+fn flattened_option() {
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Sub {
+        scale: u32,
+        label: std::string::String,
+        pos: (u8, i16),
+        limit: Option<u16>,
+        mode: BasicEnum,
+        data: DataEnum,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Outer {
+        id: u8,
+        #[serde(flatten)]
+        extra: Option<Sub>,
+        tail: bool,
+    }
+
+    // The struct is written as a map of self-described entries, behind a marker of ten 0xF
+    // nibbles and terminated by 0xF
+    test_one(
+        Outer {
+            id: 3,
+            extra: None,
+            tail: true,
+        },
+        &[
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x62, b'i', b'd', 0x23, 0x64, b't', b'a', b'i', b'l',
+            0x11, 0xF0,
+        ],
+    );
+    // The fields of the sub-struct are entries of the same map
+    let values = [
+        Outer {
+            id: 200,
+            extra: Some(Sub {
+                scale: 70_000,
+                label: "flat".into(),
+                pos: (7, -300),
+                limit: None,
+                mode: BasicEnum::Bap,
+                data: DataEnum::Chi { a: 1, b: 2 },
+            }),
+            tail: false,
+        },
+        Outer {
+            id: 1,
+            extra: Some(Sub {
+                scale: 0,
+                label: std::string::String::new(),
+                pos: (0, 0),
+                limit: Some(9),
+                mode: BasicEnum::Bib,
+                data: DataEnum::Sho(1, 2),
+            }),
+            tail: true,
+        },
+    ];
+    for value in values {
+        let ser: Vec<u8, 128> = to_nibble_vec(&value).unwrap();
+        assert_eq!(ser[..6], [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x62]);
+        assert_eq!(from_nibbles::<Outer>(&ser).unwrap(), value);
+    }
+
+    // 128-bit integers of the outer struct, beyond the range of 64 bits
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Wide {
+        big: u128,
+        small: i128,
+        #[serde(flatten)]
+        extra: Option<Sub>,
+    }
+
+    for (big, small) in [(u128::MAX, i128::MIN), (7, -7), (1 << 64, -(1 << 100))] {
+        let value = Wide {
+            big,
+            small,
+            extra: None,
+        };
+        let ser: Vec<u8, 128> = to_nibble_vec(&value).unwrap();
+        assert_eq!(from_nibbles::<Wide>(&ser).unwrap(), value);
+    }
+
+    // Regular maps keep their length prefix
+    let mut map: FnvIndexMap<u8, u16, 4> = FnvIndexMap::new();
+    map.insert(1, 2).unwrap();
+    test_one(map, &[0x11, 0x20]);
+
+    // Including lengths that start like the marker, and lengths with a leading zero digit
+    let mut map: FnvIndexMap<u8, u8, 64> = FnvIndexMap::new();
+    for k in 0..63 {
+        map.insert(k, 0).unwrap();
+    }
+    let ser: Vec<u8, 128> = to_nibble_vec(&map).unwrap();
+    assert_eq!(ser[0], 0xF7);
+    assert_eq!(from_nibbles::<FnvIndexMap<u8, u8, 64>>(&ser).unwrap(), map);
+    let (empty, warnings) =
+        postcard::from_nibbles_lenient::<std::collections::BTreeMap<u8, u8>>(&[0x80]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(
+        warnings,
+        [postcard::de_nibble_flavors::Warning::NonCanonicalVarint]
+    );
+}
+
+#[test]
+fn truncated_at_odd_nibble() {
+    use postcard::Error;
+
+    // The byte of the u32 varint starts on the low nibble of the last byte, and is missing its
+    // second half
+    assert_eq!(
+        from_nibbles::<(bool, u32)>(&[0x17]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(from_nibbles::<(bool, u32)>(&[0x17, 0xF0]), Ok((true, 0x7F)));
+
+    // Running out of nibbles altogether
+    assert_eq!(
+        from_nibbles::<(bool, bool, bool)>(&[0x11]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(
+        from_nibbles::<bool>(&[]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn typed_messages() {
+    use postcard::de_nibble_flavors::{
+        NibbleFlavor, NibbleSlice, NibbleTypeRegistry, NibbleTyped as DeTyped, NibbleTypedValue,
+    };
+    use postcard::ser_nibble_flavors::{NibbleHVec, NibbleTyped};
+    use postcard::{serialize_with_nibble_flavor, Error};
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Basic(BasicU8S),
+        Data(DataEnum),
+    }
+
+    struct Registry;
+
+    impl<'de> NibbleTypeRegistry<'de> for Registry {
+        type Output = Message;
+
+        fn decode<F>(
+            &mut self,
+            id: u32,
+            value: NibbleTypedValue<'_, 'de, F>,
+        ) -> postcard::Result<Message>
+        where
+            F: NibbleFlavor<'de>,
+        {
+            match id {
+                1 => value.decode().map(Message::Basic),
+                20 => value.decode().map(Message::Data),
+                _ => Err(Error::DeserializeBadEncoding),
+            }
+        }
+    }
+
+    let basic = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let ser_basic =
+        serialize_with_nibble_flavor(&basic, NibbleTyped::new(NibbleHVec::<32>::default(), 1))
+            .unwrap();
+    let ser_data = serialize_with_nibble_flavor(
+        &DataEnum::Bim(7),
+        NibbleTyped::new(NibbleHVec::<32>::default(), 20),
+    )
+    .unwrap();
+    // The wire version and the id take a nibble varint each in front of the message
+    assert_eq!(ser_basic[0], (postcard::NIBBLE_WIRE_VERSION << 4) | 0x1);
+    assert_eq!(&ser_data[..], &[0x3A, 0x41, 0x70]);
+
+    let (msg, _) = DeTyped::new(NibbleSlice::new(&ser_basic))
+        .decode(&mut Registry)
+        .unwrap();
+    assert_eq!(msg, Message::Basic(basic));
+    let (msg, _) = DeTyped::new(NibbleSlice::new(&ser_data))
+        .decode(&mut Registry)
+        .unwrap();
+    assert_eq!(msg, Message::Data(DataEnum::Bim(7)));
+
+    // Ids nobody registered
+    let unknown = serialize_with_nibble_flavor(
+        &DataEnum::Bim(7),
+        NibbleTyped::new(NibbleHVec::<32>::default(), 3),
+    )
+    .unwrap();
+    assert_eq!(
+        DeTyped::new(NibbleSlice::new(&unknown)).decode(&mut Registry),
+        Err(Error::DeserializeBadEncoding)
+    );
+
+    // Messages from peers with another wire version, even with a registered id
+    let mut other_version = ser_data.clone();
+    other_version[0] = ((postcard::NIBBLE_WIRE_VERSION + 1) << 4) | (other_version[0] & 0x0F);
+    assert_eq!(
+        DeTyped::new(NibbleSlice::new(&other_version)).decode(&mut Registry),
+        Err(Error::DeserializeWireVersionMismatch)
+    );
+}
+
+#[test]
+fn peek_nibble() {
+    use postcard::de_nibble_flavors::{NibbleFlavor, NibblePackedBools, NibbleSlice};
+    use postcard::Error;
+
+    let mut flav = NibbleSlice::new(&[0x12, 0x34]);
+    assert_eq!(flav.try_peek_nib(), Ok(0x1));
+    assert_eq!(flav.try_peek_nib(), Ok(0x1));
+    assert_eq!(flav.try_take_nib(), Ok(0x1));
+    assert_eq!(flav.try_peek_nib(), Ok(0x2));
+    assert_eq!(flav.try_take_u8(), Ok(0x23));
+    assert_eq!(flav.try_peek_nib(), Ok(0x4));
+    assert_eq!(flav.try_take_nib(), Ok(0x4));
+    assert_eq!(flav.try_peek_nib(), Err(Error::DeserializeUnexpectedEnd));
+    assert_eq!(flav.finalize(), Ok(&[][..]));
+
+    // Modifiers without their own peek rewind instead
+    let mut flav = NibblePackedBools::new(NibbleSlice::new(&[0x35]));
+    assert_eq!(flav.try_peek_nib(), Ok(0x3));
+    assert_eq!(flav.try_take_bool(), Ok(true));
+    // Within a boolean nibble there's no position to go back to
+    assert_eq!(flav.try_peek_nib(), Err(Error::NotYetImplemented));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn transcode_byte_postcard() {
+    use postcard::{from_bytes, to_allocvec, to_nibble_allocvec, transcode_from_byte_postcard};
+
+    let value = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let bytes = to_allocvec(&value).unwrap();
+    let nibbles = transcode_from_byte_postcard::<BasicU8S>(&bytes).unwrap();
+    assert_eq!(nibbles, to_nibble_allocvec(&value).unwrap());
+
+    // And back again
+    let back: BasicU8S = from_nibbles(&nibbles).unwrap();
+    assert_eq!(to_allocvec(&back).unwrap(), bytes);
+    assert_eq!(from_bytes::<BasicU8S>(&bytes).unwrap(), value);
+
+    // Truncated input is still an error
+    assert!(transcode_from_byte_postcard::<BasicU8S>(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn transcode_flavors() {
+    use postcard::de_flavors::Slice;
+    use postcard::ser_nibble_flavors::NibbleHVec;
+    use postcard::{to_vec, transcode, Error};
+
+    let values = [
+        DataEnum::Bib(0x1234),
+        DataEnum::Bim(u64::MAX),
+        DataEnum::Bap(7),
+        DataEnum::Kim(EnumStruct {
+            eight: 0xF0,
+            sixt: 0xACAC,
+        }),
+        DataEnum::Chi {
+            a: 1,
+            b: 0x8000_0000,
+        },
+        DataEnum::Sho(0x6969, 0x07),
+    ];
+    for value in values.iter() {
+        let bytes: Vec<u8, 32> = to_vec(value).unwrap();
+        let nibbles =
+            transcode::<DataEnum, _, _>(Slice::new(&bytes), NibbleHVec::<32>::default()).unwrap();
+        let expected: Vec<u8, 32> = to_nibble_vec(value).unwrap();
+        assert_eq!(nibbles, expected);
+    }
+
+    // Borrowed data, and truncated input
+    let bytes: Vec<u8, 32> = to_vec(&("borrowed", 5u8)).unwrap();
+    let nibbles =
+        transcode::<(&str, u8), _, _>(Slice::new(&bytes), NibbleHVec::<32>::default()).unwrap();
+    assert_eq!(from_nibbles::<(&str, u8)>(&nibbles), Ok(("borrowed", 5)));
+    assert_eq!(
+        transcode::<DataEnum, _, _>(Slice::new(&[0x01, 0xFF]), NibbleHVec::<32>::default()),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[test]
+fn slice_nibbles_left() {
+    use postcard::de_nibble_flavors::{NibbleFlavor, NibbleSlice};
+
+    let mut flav = NibbleSlice::new(&[0x12, 0x34, 0x56]);
+    assert_eq!(flav.nibbles_left(), 6);
+    assert!(flav.is_at_byte_boundary());
+    flav.try_take_nib().unwrap();
+    assert_eq!(flav.nibbles_left(), 5);
+    assert!(!flav.is_at_byte_boundary());
+    flav.try_take_u8().unwrap();
+    assert_eq!(flav.nibbles_left(), 3);
+    assert!(!flav.is_at_byte_boundary());
+    flav.try_take_nib().unwrap();
+    assert_eq!(flav.nibbles_left(), 2);
+    assert!(flav.is_at_byte_boundary());
+    flav.try_take_u8().unwrap();
+    assert_eq!(flav.nibbles_left(), 0);
+}
+
+#[test]
+fn framed_check() {
+    use postcard::de_nibble_flavors::{NibbleFramedCheck, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, Error};
+
+    fn decode<'a, T: Deserialize<'a>>(frame: &'a [u8]) -> postcard::Result<T> {
+        from_nibbles_with_flavor(NibbleFramedCheck::new(NibbleSlice::new(frame))?).map(|(t, _)| t)
+    }
+
+    // Length 3, then a bool and a two nibble u8
+    assert_eq!(decode::<(bool, u8)>(&[0x31, 0xB2]), Ok((true, 26)));
+    // Odd lengths leave the padding nibble alone
+    assert_eq!(decode::<(bool, u8)>(&[0x21, 0x50]), Ok((true, 5)));
+    // Byte runs count their padding nibble
+    assert_eq!(
+        decode::<(bool, &str)>(&[0x71, 0x20, b'h', b'i']),
+        Ok((true, "hi"))
+    );
+
+    // Under-consuming
+    assert_eq!(
+        decode::<(bool, u8)>(&[0x41, 0x50, 0x00]),
+        Err(Error::DeserializeLengthMismatch)
+    );
+    // Over-consuming
+    assert_eq!(
+        decode::<(bool, u8)>(&[0x21, 0xB2]),
+        Err(Error::DeserializeLengthMismatch)
+    );
+    assert_eq!(
+        decode::<(bool, u8, bool)>(&[0x21, 0x51, 0x00]),
+        Err(Error::DeserializeLengthMismatch)
+    );
+}
+
+#[test]
+fn nibbles_written() {
+    use postcard::ser_nibble_flavors::{NibbleFlavor, NibbleSlice};
+
+    let mut buf = [0u8; 4];
+    let mut flav = NibbleSlice::new(&mut buf);
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (0, 0));
+    flav.try_push_nib(0x1).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (1, 1));
+    flav.try_push_u8(0x23).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (3, 2));
+    flav.try_push_nib(0x4).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (4, 2));
+    flav.try_push_u8(0x56).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (6, 3));
+    flav.try_push_nib(0x7).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (7, 4));
+    assert_eq!(flav.finalize().unwrap(), &[0x12, 0x34, 0x56, 0x70]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn nibbles_written_hvec() {
+    use postcard::ser_nibble_flavors::{NibbleFlavor, NibbleHVec};
+
+    let mut flav = NibbleHVec::<4>::default();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (0, 0));
+    flav.try_push_nib(0x1).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (1, 1));
+    flav.try_push_u8(0x23).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (3, 2));
+    flav.try_push_nib(0x4).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (4, 2));
+    // Padding up to the byte boundary counts as written
+    flav.try_push_nib(0x5).unwrap();
+    flav.try_extend(&[0x67]).unwrap();
+    assert_eq!((flav.nibbles_written(), flav.bytes_written()), (8, 4));
+    assert_eq!(flav.finalize().unwrap(), &[0x12, 0x34, 0x50, 0x67]);
+}
+
+#[test]
+fn parity_bit_errors() {
+    use postcard::de_nibble_flavors::{NibblePackedBools as DePacked, NibbleParity as DeParity};
+    use postcard::ser_nibble_flavors::{NibblePackedBools, NibbleParity, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    type Msg<'a> = (bool, u16, &'a str, u8);
+    let data: Msg = (true, 0xABCD, "noisy", 0x5A);
+
+    let mut buf = [0u8; 64];
+    let frame =
+        serialize_with_nibble_flavor(&data, NibbleParity::new(NibbleSlice::new(&mut buf))).unwrap();
+    let mut plain = [0u8; 32];
+    let plain = postcard::to_nibble_slice(&data, &mut plain).unwrap();
+    // A byte per nibble of the plain message
+    assert!(frame.len() == plain.len() * 2 || frame.len() == plain.len() * 2 - 1);
+
+    let mut copy = [0u8; 64];
+    let decode = |copy: &mut [u8; 64], correct: bool| {
+        let copy = &mut copy[..frame.len()];
+        let flav = match correct {
+            false => DeParity::new(copy)?,
+            true => DeParity::with_correction(copy)?,
+        };
+        let corrected = flav.corrected();
+        from_nibbles_with_flavor::<Msg, _>(flav).map(|(msg, _)| (msg.0, msg.1, msg.3, corrected))
+    };
+    copy[..frame.len()].copy_from_slice(frame);
+    assert_eq!(decode(&mut copy, false), Ok((true, 0xABCD, 0x5A, 0)));
+
+    for i in 0..frame.len() {
+        for bit in 0..8 {
+            // Single bit errors are detected, or corrected if enabled
+            copy[..frame.len()].copy_from_slice(frame);
+            copy[i] ^= 1 << bit;
+            assert_eq!(decode(&mut copy, false), Err(Error::DeserializeParityError));
+            copy[..frame.len()].copy_from_slice(frame);
+            copy[i] ^= 1 << bit;
+            assert_eq!(decode(&mut copy, true), Ok((true, 0xABCD, 0x5A, 1)));
+
+            // Two bit errors in the same nibble are detected, but can't be corrected
+            for other in (bit + 1)..8 {
+                copy[..frame.len()].copy_from_slice(frame);
+                copy[i] ^= (1 << bit) | (1 << other);
+                assert_eq!(decode(&mut copy, true), Err(Error::DeserializeParityError));
+            }
+        }
+    }
+
+    // Errors in different nibbles are corrected independently
+    copy[..frame.len()].copy_from_slice(frame);
+    copy[0] ^= 0x01;
+    copy[3] ^= 0x40;
+    assert_eq!(decode(&mut copy, true), Ok((true, 0xABCD, 0x5A, 2)));
+
+    // Booleans packed around the parity flavor
+    let flags = [true, false, false, true, true];
+    let mut buf = [0u8; 16];
+    let frame = serialize_with_nibble_flavor(
+        &flags,
+        NibblePackedBools::new(NibbleParity::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    // Two nibbles of packed booleans
+    assert_eq!(frame.len(), 2);
+    let flav = DePacked::new(DeParity::new(frame).unwrap());
+    assert_eq!(
+        from_nibbles_with_flavor::<[bool; 5], _>(flav).map(|(v, _)| v),
+        Ok(flags)
+    );
+}
+
+#[test]
+fn peek_discriminant() {
+    use postcard::{from_nibbles_with_variant, peek_nibble_discriminant, to_nibble_slice, Error};
+
+    let values = [
+        DataEnum::Bib(0x1234),
+        DataEnum::Bim(u64::MAX),
+        DataEnum::Bap(7),
+        DataEnum::Kim(EnumStruct { eight: 1, sixt: 2 }),
+        DataEnum::Chi { a: 3, b: 0xFFFF },
+        DataEnum::Sho(4, 5),
+    ];
+    for (index, value) in values.iter().enumerate() {
+        let mut buf = [0u8; 32];
+        let ser = to_nibble_slice(value, &mut buf).unwrap();
+        assert_eq!(peek_nibble_discriminant(ser), Ok(index as u32));
+        let (_, variant) = from_nibbles_with_variant::<DataEnum>(ser).unwrap();
+        assert_eq!(variant, Some(index as u32));
+    }
+
+    // Only the discriminant has to be there
+    assert_eq!(peek_nibble_discriminant(&[0x50]), Ok(5));
+    assert_eq!(
+        peek_nibble_discriminant(&[]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn nibble_cobs_frames() {
+    use postcard::ser_nibble_flavors::{NibbleCanonical, NibbleCobs, NibbleHVec};
+    use postcard::{from_nibbles_cobs, serialize_with_nibble_flavor, to_nibble_vec_cobs};
+
+    let chi = DataEnum::Chi { a: 7, b: 0 };
+    let plain: Vec<u8, 32> = to_nibble_vec(&chi).unwrap();
+    assert!(plain.contains(&0));
+    let mut frame: Vec<u8, 32> = to_nibble_vec_cobs(&chi).unwrap();
+    // Self-delimiting: a single zero, at the end
+    let (last, body) = frame.split_last().unwrap();
+    assert_eq!(*last, 0);
+    assert!(!body.contains(&0));
+    assert_eq!(frame.len(), plain.len() + 2);
+    assert_eq!(from_nibbles_cobs::<DataEnum>(&mut frame), Ok(chi));
+
+    // Longer than a COBS block, with and without zeros
+    for fill in [0u8, 0x55] {
+        let bytes = [fill; 300];
+        let data = (true, core::str::from_utf8(&bytes).unwrap());
+        let mut frame: Vec<u8, 700> = to_nibble_vec_cobs(&data).unwrap();
+        let (last, body) = frame.split_last().unwrap();
+        assert_eq!(*last, 0);
+        assert!(!body.contains(&0));
+        assert_eq!(from_nibbles_cobs::<(bool, &str)>(&mut frame), Ok(data));
+    }
+
+    // Canonical wraps the framing, the map is sorted before it is encoded
+    let mut map: FnvIndexMap<u8, bool, 4> = FnvIndexMap::new();
+    map.insert(9, false).unwrap();
+    map.insert(1, true).unwrap();
+    let mut sorted: FnvIndexMap<u8, bool, 4> = FnvIndexMap::new();
+    sorted.insert(1, true).unwrap();
+    sorted.insert(9, false).unwrap();
+    let mut frame = serialize_with_nibble_flavor(
+        &map,
+        NibbleCanonical::<_, 64>::new(NibbleCobs::new(NibbleHVec::<32>::default())),
+    )
+    .unwrap();
+    let expected: Vec<u8, 32> = to_nibble_vec_cobs(&sorted).unwrap();
+    assert_eq!(frame, expected);
+    assert_eq!(
+        from_nibbles_cobs::<FnvIndexMap<u8, bool, 4>>(&mut frame),
+        Ok(map)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn soa_map_layout() {
+    extern crate alloc;
+    use alloc::collections::BTreeMap;
+    use postcard::soa_map::NibbleSoaMap;
+    use postcard::to_nibble_allocvec;
+
+    /// Order-0 entropy of the bytes, in bits
+    fn entropy_bits(data: &[u8]) -> f64 {
+        let mut counts = [0usize; 256];
+        data.iter().for_each(|b| counts[*b as usize] += 1);
+        let len = data.len() as f64;
+        counts
+            .iter()
+            .filter(|c| **c != 0)
+            .map(|c| {
+                let p = *c as f64 / len;
+                -p * p.log2() * len
+            })
+            .sum()
+    }
+
+    // Small sequential ids, mapped to large similar readings
+    let map: BTreeMap<u16, u16> = (0..64u16).map(|k| (k, 0xF000 + (k % 4))).collect();
+    let soa = to_nibble_allocvec(&NibbleSoaMap(map.clone())).unwrap();
+    let interleaved = to_nibble_allocvec(&map).unwrap();
+
+    // Same size, lower entropy
+    assert_eq!(soa.len(), interleaved.len());
+    assert!(entropy_bits(&soa) < entropy_bits(&interleaved));
+
+    let back: NibbleSoaMap<u16, u16> = from_nibbles(&soa).unwrap();
+    assert_eq!(back.0, map);
+
+    // Empty maps, and the byte format
+    let empty = NibbleSoaMap::<u16, u16>::default();
+    assert_eq!(to_nibble_allocvec(&empty).unwrap(), &[0x00]);
+    let bytes = postcard::to_allocvec(&NibbleSoaMap(map.clone())).unwrap();
+    let back: NibbleSoaMap<u16, u16> = postcard::from_bytes(&bytes).unwrap();
+    assert_eq!(back.0, map);
+
+    // Fewer values than keys
+    assert!(from_nibbles::<NibbleSoaMap<u16, u16>>(&soa[..soa.len() - 2]).is_err());
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn field_type_tags() {
+    use postcard::{from_nibbles_field_types, to_nibble_vec_field_types, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Frame<'a> {
+        id: u16,
+        kind: DataEnum,
+        inner: EnumStruct,
+        name: &'a str,
+        flags: Vec<bool, 4>,
+        nothing: (),
+    }
+
+    // Same layout, with `name` decoded as a number
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Swapped {
+        id: u16,
+        kind: DataEnum,
+        inner: EnumStruct,
+        name: u16,
+        flags: Vec<bool, 4>,
+        nothing: (),
+    }
+
+    // Unsigned tag and value for both fields
+    let data = EnumStruct { eight: 1, sixt: 2 };
+    let tagged: Vec<u8, 8> = to_nibble_vec_field_types(&data).unwrap();
+    assert_eq!(&tagged, &[0x11, 0x12]);
+    assert_eq!(from_nibbles_field_types::<EnumStruct>(&tagged), Ok(data));
+
+    let mut flags = Vec::new();
+    flags.extend_from_slice(&[true, false]).unwrap();
+    let frame = Frame {
+        id: 0x1234,
+        kind: DataEnum::Chi { a: 3, b: 4 },
+        inner: EnumStruct { eight: 5, sixt: 6 },
+        name: "hi",
+        flags,
+        nothing: (),
+    };
+    let tagged: Vec<u8, 32> = to_nibble_vec_field_types(&frame).unwrap();
+    let plain: Vec<u8, 32> = to_nibble_vec(&frame).unwrap();
+    assert!(tagged.len() > plain.len());
+    assert_eq!(from_nibbles_field_types::<Frame>(&tagged), Ok(frame));
+    assert_eq!(
+        from_nibbles_field_types::<Swapped>(&tagged).err(),
+        Some(Error::DeserializeFieldTypeMismatch)
+    );
+
+    // Without tags, a bool and a small number are indistinguishable
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Flagged {
+        flag: bool,
+        sixt: u16,
+    }
+    let plain: Vec<u8, 8> = to_nibble_vec(&EnumStruct { eight: 1, sixt: 2 }).unwrap();
+    assert_eq!(
+        from_nibbles::<Flagged>(&plain),
+        Ok(Flagged {
+            flag: true,
+            sixt: 2
+        })
+    );
+    let tagged: Vec<u8, 8> = to_nibble_vec_field_types(&EnumStruct { eight: 1, sixt: 2 }).unwrap();
+    assert_eq!(
+        from_nibbles_field_types::<Flagged>(&tagged),
+        Err(Error::DeserializeFieldTypeMismatch)
+    );
+}
+
+#[cfg(feature = "use-std")]
+#[test]
+fn io_writer_matches_stdvec() {
+    use postcard::ser_nibble_flavors::NibbleIOWriter;
+    use postcard::{serialize_with_nibble_flavor, to_nibble_stdvec, Error};
+
+    // Nibbles, unaligned bytes and aligned runs, ending on a half byte
+    let data = (
+        true,
+        0x1234_5678u32,
+        "hi",
+        DataEnum::Chi { a: 3, b: 0xFFFF },
+        BasicU8S {
+            st: 0xABCD,
+            ei: 0xFE,
+            sf: u64::MAX,
+            tt: 7,
+        },
+        5u8,
+    );
+    let out =
+        serialize_with_nibble_flavor(&data, NibbleIOWriter::new(std::vec::Vec::new())).unwrap();
+    assert_eq!(out, to_nibble_stdvec(&data).unwrap());
+
+    let out = serialize_with_nibble_flavor(&(), NibbleIOWriter::new(std::vec::Vec::new())).unwrap();
+    assert!(out.is_empty());
+
+    // A writer that runs out of space
+    let mut buf = [0u8; 2];
+    assert_eq!(
+        serialize_with_nibble_flavor(&data, NibbleIOWriter::new(&mut buf[..])).err(),
+        Some(Error::SerializeBufferFull)
+    );
+}
+
+#[cfg(feature = "use-std")]
+#[test]
+fn io_writer_align_and_flush() {
+    use postcard::de_nibble_flavors::{NibbleFlavor as _, NibbleSlice};
+    use postcard::ser_nibble_flavors::{NibbleFlavor, NibbleIOWriter};
+    use std::io::Write;
+
+    /// Remembers how much was written at each flush
+    #[derive(Default)]
+    struct Flushes {
+        out: std::vec::Vec<u8>,
+        flushed_at: std::vec::Vec<usize>,
+    }
+
+    impl Write for Flushes {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.out.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushed_at.push(self.out.len());
+            Ok(())
+        }
+    }
+
+    let mut flavor = NibbleIOWriter::new(Flushes::default());
+    flavor.try_push_nib(0xA).unwrap();
+    flavor.align_and_flush().unwrap();
+    flavor.try_extend(&[0x01, 0x02]).unwrap();
+    flavor.try_push_nib(0xB).unwrap();
+    flavor.try_push_u8(0xCD).unwrap();
+    flavor.align_and_flush().unwrap();
+    // Already aligned, nothing is padded
+    flavor.align_and_flush().unwrap();
+    flavor.try_extend(&[0x03]).unwrap();
+    flavor.try_push_nib(0xE).unwrap();
+    let writer = flavor.finalize().unwrap();
+    assert_eq!(writer.out, &[0xA0, 0x01, 0x02, 0xBC, 0xD0, 0x03, 0xE0]);
+    assert_eq!(writer.flushed_at, &[1, 5, 5, 7]);
+
+    let mut de = NibbleSlice::new(&writer.out);
+    assert_eq!(de.try_take_nib(), Ok(0xA));
+    assert_eq!(de.try_take_n(0), Ok(&[][..]));
+    assert_eq!(de.try_take_n(2), Ok(&[0x01, 0x02][..]));
+    assert_eq!(de.try_take_nib(), Ok(0xB));
+    assert_eq!(de.try_take_u8(), Ok(0xCD));
+    assert_eq!(de.try_take_n(0), Ok(&[][..]));
+    assert_eq!(de.try_take_n(1), Ok(&[0x03][..]));
+    assert_eq!(de.try_take_nib(), Ok(0xE));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn frame_ranges() {
+    use postcard::de_nibble_flavors::{NibbleFramedCheck, NibbleSlice};
+    use postcard::{frame_ranges, from_nibbles_with_flavor, Error};
+
+    fn decode<'a, T: Deserialize<'a>>(frame: &'a [u8]) -> postcard::Result<T> {
+        from_nibbles_with_flavor(NibbleFramedCheck::new(NibbleSlice::new(frame))?).map(|(t, _)| t)
+    }
+
+    // Three nibbles, two nibbles with padding, and a byte run
+    let data = [0x31, 0xB2, 0x21, 0x50, 0x71, 0x20, b'h', b'i'];
+    let ranges = frame_ranges(&data).unwrap();
+    assert_eq!(ranges, [0..2, 2..4, 4..8]);
+
+    // Decoded on demand, in any order
+    assert_eq!(
+        decode::<(bool, &str)>(&data[ranges[2].clone()]),
+        Ok((true, "hi"))
+    );
+    assert_eq!(
+        decode::<(bool, u8)>(&data[ranges[0].clone()]),
+        Ok((true, 26))
+    );
+    assert_eq!(
+        decode::<(bool, u8)>(&data[ranges[1].clone()]),
+        Ok((true, 5))
+    );
+
+    // A three nibble header, for 64 nibbles
+    let mut long = std::vec![0x98, 0x00];
+    long.extend_from_slice(&[0xAA; 32]);
+    let ranges = frame_ranges(&long).unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0], 0..34);
+
+    assert_eq!(frame_ranges(&[]).unwrap(), []);
+    assert_eq!(
+        frame_ranges(&data[..7]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "use-std")]
+#[test]
+fn io_reader_loopback() {
+    use postcard::de_nibble_flavors::NibbleIOReader;
+    use postcard::{from_nibbles_with_flavor, to_nibble_stdvec, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Message<'a> {
+        flag: bool,
+        id: u32,
+        name: &'a str,
+        kind: DataEnum,
+        tail: u8,
+    }
+
+    let first = Message {
+        flag: true,
+        id: 0x1234_5678,
+        name: "first",
+        kind: DataEnum::Chi { a: 3, b: 0xFFFF },
+        tail: 0xAB,
+    };
+    let second = (BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: u64::MAX,
+        tt: 7,
+    },);
+    let mut stream = to_nibble_stdvec(&first).unwrap();
+    stream.extend(to_nibble_stdvec(&second).unwrap());
+
+    // Both messages one after the other, from the same reader
+    let mut scratch = [0u8; 16];
+    let reader: &[u8] = &stream;
+    let (back, (reader, scratch_left)) =
+        from_nibbles_with_flavor::<Message, _>(NibbleIOReader::new(reader, &mut scratch)).unwrap();
+    assert_eq!(back, first);
+    assert_eq!(scratch_left.len(), 16 - "first".len());
+    let mut scratch = [0u8; 16];
+    let (back, (reader, _)) =
+        from_nibbles_with_flavor::<(BasicU8S,), _>(NibbleIOReader::new(reader, &mut scratch))
+            .unwrap();
+    assert_eq!(back, second);
+    assert!(reader.is_empty());
+
+    // Running out of input, or of scratch space
+    let ser = to_nibble_stdvec(&first).unwrap();
+    let mut scratch = [0u8; 16];
+    let reader: &[u8] = &ser[..ser.len() - 1];
+    assert_eq!(
+        from_nibbles_with_flavor::<Message, _>(NibbleIOReader::new(reader, &mut scratch)).err(),
+        Some(Error::DeserializeUnexpectedEnd)
+    );
+    let mut scratch = [0u8; 4];
+    assert_eq!(
+        from_nibbles_with_flavor::<Message, _>(NibbleIOReader::new(&ser[..], &mut scratch)).err(),
+        Some(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[test]
+fn strict_remainder() {
+    use postcard::{from_nibbles_strict, Error};
+
+    // Exactly sized, a whole number of bytes
+    assert_eq!(from_nibbles_strict::<(u8, u16)>(&[0x32]), Ok((3, 2)));
+    assert_eq!(from_nibbles_strict::<&str>(&[0x20, b'h', b'i']), Ok("hi"));
+    // Ending in the middle of a byte, with its padding nibble
+    assert_eq!(
+        from_nibbles_strict::<(u8, bool)>(&[0x91, 0x10]),
+        Ok((9, true))
+    );
+    assert_eq!(from_nibbles_strict::<bool>(&[0x00]), Ok(false));
+
+    // An extra byte, after a whole or a partial last byte
+    assert_eq!(
+        from_nibbles_strict::<(u8, u16)>(&[0x32, 0x00]),
+        Err(Error::DeserializeUnexpectedLength)
+    );
+    assert_eq!(
+        from_nibbles_strict::<(u8, bool)>(&[0x91, 0x10, 0x00]),
+        Err(Error::DeserializeUnexpectedLength)
+    );
+    // The padding nibble has to be zero
+    assert_eq!(
+        from_nibbles_strict::<(u8, bool)>(&[0x91, 0x15]),
+        Err(Error::DeserializeUnexpectedLength)
+    );
+    // Too short is still an error of its own
+    assert_eq!(
+        from_nibbles_strict::<(u8, u16)>(&[0x9C]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn budgeted_steps() {
+    use postcard::ser_nibble_flavors::{
+        NibbleBudgeted, NibbleFlavor, NibbleHVec, NibbleNamedVariants, NibblePackedBools,
+        NibbleSeparated,
+    };
+    use postcard::{
+        serialize_with_nibble_flavor, to_nibble_vec_packed, to_nibble_vec_separated, Error,
+    };
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Report<'a> {
+        id: u32,
+        kind: DataEnum,
+        inner: EnumStruct,
+        name: &'a str,
+        total: u64,
+    }
+
+    let report = Report {
+        id: 0x1234_5678,
+        kind: DataEnum::Chi { a: 3, b: 0xFFFF },
+        inner: EnumStruct { eight: 5, sixt: 6 },
+        name: "a name long enough to be split over several steps",
+        total: u64::MAX,
+    };
+    let expected: Vec<u8, 128> = to_nibble_vec(&report).unwrap();
+
+    for budget in [2, 3, 8, 33, 1000] {
+        let mut flavor = NibbleBudgeted::new(NibbleHVec::<128>::default(), budget);
+        let mut steps = 0;
+        loop {
+            steps += 1;
+            match flavor.step(&report) {
+                Ok(()) => break,
+                Err(Error::SerializeBudgetExhausted) => assert!(flavor.used() <= budget),
+                Err(e) => panic!("{:?}", e),
+            }
+        }
+        if budget >= expected.len() * 2 {
+            assert_eq!(steps, 1);
+        } else {
+            assert!(steps > 1);
+        }
+        assert_eq!(flavor.finalize().unwrap(), expected);
+    }
+
+    // The budget fires, and nothing is repeated when continuing, including the field hooks
+    let separated: Vec<u8, 128> = to_nibble_vec_separated(&report).unwrap();
+    let mut flavor = NibbleBudgeted::new(NibbleSeparated::new(NibbleHVec::<128>::default()), 16);
+    assert_eq!(flavor.step(&report), Err(Error::SerializeBudgetExhausted));
+    assert_eq!(flavor.used(), 16);
+    while flavor.step(&report) == Err(Error::SerializeBudgetExhausted) {}
+    assert_eq!(flavor.finalize().unwrap(), separated);
+
+    // Booleans and variants reach a modifier flavor unchanged
+    let data = (true, false, DataEnum::Chi { a: 3, b: 7 }, true, "abc");
+    let packed: Vec<u8, 128> = to_nibble_vec_packed(&data).unwrap();
+    let mut flavor = NibbleBudgeted::new(NibblePackedBools::new(NibbleHVec::<128>::default()), 4);
+    while flavor.step(&data) == Err(Error::SerializeBudgetExhausted) {}
+    assert_eq!(flavor.finalize().unwrap(), packed);
+
+    let named = serialize_with_nibble_flavor(
+        &data,
+        NibbleNamedVariants::new(NibbleHVec::<128>::default()),
+    )
+    .unwrap();
+    let mut flavor = NibbleBudgeted::new(NibbleNamedVariants::new(NibbleHVec::<128>::default()), 4);
+    while flavor.step(&data) == Err(Error::SerializeBudgetExhausted) {}
+    assert_eq!(flavor.finalize().unwrap(), named);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn type_descriptors() {
+    use postcard::type_descriptor::{is_compatible, NibbleTypeDescriptor, NibbleTypeKind};
+
+    #[derive(Deserialize)]
+    struct RenamedEnumStruct {
+        _first: u8,
+        _second: u16,
+    }
+
+    #[derive(Deserialize)]
+    struct WiderEnumStruct {
+        _eight: u8,
+        _sixt: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct LongerEnumStruct {
+        _eight: u8,
+        _sixt: u16,
+        _extra: bool,
+    }
+
+    fn describe<'de, T: Deserialize<'de>>() -> NibbleTypeDescriptor {
+        NibbleTypeDescriptor::of::<T>().unwrap()
+    }
+
+    let base = describe::<EnumStruct>();
+    assert_eq!(
+        base.kinds(),
+        &[
+            NibbleTypeKind::Struct(2),
+            NibbleTypeKind::U8,
+            NibbleTypeKind::U16
+        ]
+    );
+
+    // Field names, tuples and newtype wrappers don't change the encoding
+    assert!(is_compatible(&base, &describe::<RenamedEnumStruct>()));
+    assert!(is_compatible(&base, &describe::<(u8, u16)>()));
+    assert!(is_compatible(&base, &describe::<TupleStruct>()));
+    assert!(is_compatible(
+        &describe::<NewTypeStruct>(),
+        &describe::<u32>()
+    ));
+    assert!(is_compatible(
+        &describe::<(Option<u8>, DataEnum)>(),
+        &describe::<(Option<u8>, BasicEnum)>()
+    ));
+
+    // Different widths, signedness or field counts do
+    assert!(!is_compatible(&base, &describe::<WiderEnumStruct>()));
+    assert!(!is_compatible(&base, &describe::<LongerEnumStruct>()));
+    assert!(!is_compatible(&base, &describe::<(u8, i16)>()));
+    assert!(!is_compatible(&base, &describe::<(u8, (u16,))>()));
+    assert!(!is_compatible(
+        &describe::<std::vec::Vec<u8>>(),
+        &describe::<&str>()
+    ));
+
+    // Descriptors can be sent to the peer
+    let ser = postcard::to_nibble_allocvec(&base).unwrap();
+    assert_eq!(
+        postcard::from_nibbles::<NibbleTypeDescriptor>(&ser).unwrap(),
+        base
+    );
+}
+
+#[test]
+fn borrowed_flavors() {
+    use postcard::de_nibble_flavors::{NibbleFlavor as _, NibbleSlice as DeSlice};
+    use postcard::ser_nibble_flavors::{NibbleFlavor as _, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+
+    const TAG: u16 = 0xCAFE;
+    let data = EnumStruct {
+        eight: 9,
+        sixt: 0x40,
+    };
+
+    let mut buf = [0u8; 8];
+    let mut flavor = NibbleSlice::new(&mut buf);
+    for shift in [12, 8, 4, 0] {
+        flavor.try_push_nib((TAG >> shift) as u8 & 0xF).unwrap();
+    }
+    serialize_with_nibble_flavor(&data, &mut flavor).unwrap();
+    assert_eq!(flavor.nibbles_written(), 9);
+    // The tag, then 9 and 64 as nibble varints
+    let used = flavor.finalize().unwrap();
+    assert_eq!(used, &[0xCA, 0xFE, 0x91, 0x98, 0x00]);
+
+    let mut flavor = DeSlice::new(used);
+    let mut tag = 0;
+    for _ in 0..4 {
+        tag = tag << 4 | flavor.try_take_nib().unwrap() as u16;
+    }
+    assert_eq!(tag, TAG);
+    let (back, ()) = from_nibbles_with_flavor::<EnumStruct, _>(&mut flavor).unwrap();
+    assert_eq!(back, data);
+    // The remainder starts with the partially read byte
+    assert_eq!(flavor.finalize().unwrap(), &[0x00]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn in_place() {
+    use postcard::from_nibbles_in_place;
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 7,
+    };
+    let ser: Vec<u8, 32> = to_nibble_vec(&data).unwrap();
+
+    let mut place = BasicU8S {
+        st: 1,
+        ei: 2,
+        sf: 3,
+        tt: 4,
+    };
+    from_nibbles_in_place(&ser, &mut place).unwrap();
+    assert_eq!(place, data);
+
+    // Errors are reported as usual
+    assert!(from_nibbles_in_place(&ser[..3], &mut place).is_err());
+
+    // Derived structs are decoded field by field, so a truncated message leaves the fields
+    // after the error untouched
+    #[cfg(feature = "deserialize-in-place")]
+    {
+        let mut place = BasicU8S {
+            st: 1,
+            ei: 2,
+            sf: 3,
+            tt: 4,
+        };
+        assert!(from_nibbles_in_place(&ser[..6], &mut place).is_err());
+        assert_eq!((place.st, place.ei, place.tt), (0xABCD, 0xFE, 4));
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn nibble_sizes() {
+    use postcard::{nibble_size, nibble_size_bytes};
+
+    assert_eq!(nibble_size(&BasicEnum::Bim).unwrap(), 1);
+    assert_eq!(nibble_size_bytes(&BasicEnum::Bim).unwrap(), 1);
+    assert_eq!(nibble_size(&()).unwrap(), 0);
+    assert_eq!(nibble_size_bytes(&()).unwrap(), 0);
+
+    // The padding before aligned bytes counts, the one after the last nibble only in bytes
+    fn check<T: Serialize>(value: &T) {
+        let ser: Vec<u8, 64> = to_nibble_vec(value).unwrap();
+        let nibbles = nibble_size(value).unwrap();
+        assert_eq!(nibble_size_bytes(value).unwrap(), ser.len());
+        assert_eq!(nibbles.div_ceil(2), ser.len());
+    }
+    check(&(true, 5u8, "hi"));
+    check(&(true, "hi", 5u8));
+    check(&DataEnum::Chi { a: 3, b: 0xFFFF });
+    check(&RefStruct {
+        bytes: &[1, 2, 3],
+        str_s: "abc",
+    });
+    assert_eq!(nibble_size(&(true, 5u8, "hi")).unwrap(), 8);
+    assert_eq!(nibble_size(&(true, "hi", 5u8)).unwrap(), 7);
+}
+
+#[test]
+fn line_safe_frames() {
+    use postcard::de_nibble_flavors::{
+        NibbleLineSafe as DeLineSafe, NibbleNamedVariants as DeNamed,
+    };
+    use postcard::ser_nibble_flavors::{NibbleLineSafe, NibbleNamedVariants, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    // Newlines and control bytes in the message itself
+    let first = (10u8, "two\nlines\r\n\0\x1B", true);
+    let second = DataEnum::Chi { a: 10, b: 0x0A0A };
+
+    let mut buf = [0u8; 128];
+    let len = {
+        let frame =
+            serialize_with_nibble_flavor(&first, NibbleLineSafe::new(NibbleSlice::new(&mut buf)))
+                .unwrap();
+        let (last, line) = frame.split_last().unwrap();
+        assert_eq!(*last, b'\n');
+        assert!(line.iter().all(|b| (b'a'..=b'p').contains(b)));
+        frame.len()
+    };
+    let more = serialize_with_nibble_flavor(
+        &second,
+        NibbleLineSafe::new(NibbleSlice::new(&mut buf[len..])),
+    )
+    .unwrap()
+    .len();
+    let stream = &mut buf[..len + more];
+    assert_eq!(stream.iter().filter(|b| **b == b'\n').count(), 2);
+
+    // Lines are decoded one after another
+    let (back, rest) =
+        from_nibbles_with_flavor::<(u8, &str, bool), _>(DeLineSafe::new(stream).unwrap()).unwrap();
+    assert_eq!(back, first);
+    let mut next = [0u8; 64];
+    let next = &mut next[..rest.len()];
+    next.copy_from_slice(rest);
+    let (back, rest) =
+        from_nibbles_with_flavor::<DataEnum, _>(DeLineSafe::new(next).unwrap()).unwrap();
+    assert_eq!(back, second);
+    assert!(rest.is_empty());
+
+    // A line that isn't complete yet, and one with a foreign character
+    assert_eq!(
+        DeLineSafe::new(&mut { *b"bh" }).err(),
+        Some(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(
+        DeLineSafe::new(&mut { *b"bH\n" }).err(),
+        Some(Error::DeserializeBadEncoding)
+    );
+
+    // Variant names are turned into text as well
+    let mut buf = [0u8; 64];
+    let frame = serialize_with_nibble_flavor(
+        &second,
+        NibbleNamedVariants::new(NibbleLineSafe::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    let (last, line) = frame.split_last().unwrap();
+    assert_eq!(*last, b'\n');
+    assert!(line.iter().all(|b| (b'a'..=b'p').contains(b)));
+    let flavor = DeNamed::new(DeLineSafe::new(frame).unwrap());
+    assert_eq!(
+        from_nibbles_with_flavor::<DataEnum, _>(flavor).map(|(v, _)| v),
+        Ok(second)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn hex_strings() {
+    use postcard::{from_nibble_hex, to_nibble_hex, Error};
+
+    let data = DataEnum::Sho(0x6969, 0x07);
+    let hex = to_nibble_hex(&data).unwrap();
+    assert_eq!(hex, "5ecdd170");
+    assert_eq!(from_nibble_hex::<DataEnum>(&hex), Ok(data));
+    assert_eq!(
+        from_nibble_hex::<DataEnum>("5ECDD17"),
+        Ok(DataEnum::Sho(0x6969, 0x07))
+    );
+
+    assert_eq!(
+        from_nibble_hex::<DataEnum>("5ecdd17g"),
+        Err(Error::DeserializeBadHex)
+    );
+    assert_eq!(
+        from_nibble_hex::<DataEnum>("5ecd d170"),
+        Err(Error::DeserializeBadHex)
+    );
+    assert_eq!(
+        from_nibble_hex::<DataEnum>("5ecd"),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn enum_dict() {
+    use postcard::enum_dict::NibbleEnumDict;
+    use postcard::{from_nibbles, to_nibble_allocvec, Error};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    enum Sample {
+        Off,
+        Calibrating,
+        Overrange,
+        Underrange,
+        Fault(u8),
+        Pair(u8, bool),
+        Detail { code: u16, fatal: bool },
+        Reserved,
+        Sensor(u16),
+    }
+
+    fn loopback(samples: &[Sample]) -> (usize, usize) {
+        let dict = NibbleEnumDict(samples.to_vec());
+        let ser = to_nibble_allocvec(&dict).unwrap();
+        assert_eq!(from_nibbles::<NibbleEnumDict<Sample>>(&ser), Ok(dict));
+        (ser.len(), to_nibble_allocvec(samples).unwrap().len())
+    }
+
+    // Only one variant: the two nibble index is written once, and no positions at all
+    let readings: std::vec::Vec<Sample> = (0..32).map(|i| Sample::Sensor(i % 4)).collect();
+    let (dict, plain) = loopback(&readings);
+    assert_eq!(dict, 19);
+    assert_eq!(plain, 49);
+    let ser = to_nibble_allocvec(&NibbleEnumDict(readings[..2].to_vec())).unwrap();
+    assert_eq!(ser.as_slice(), &[0x19, 0x02, 0x01]);
+
+    // Mostly one variant, with the occasional other ones
+    let mut mixed = readings.clone();
+    mixed[3] = Sample::Fault(9);
+    mixed[10] = Sample::Detail {
+        code: 0x1234,
+        fatal: true,
+    };
+    mixed[11] = Sample::Off;
+    mixed[20] = Sample::Pair(3, false);
+    let (dict, plain) = loopback(&mixed);
+    assert!(dict < plain, "{} >= {}", dict, plain);
+
+    // Every element of its own variant, and none at all
+    loopback(&[
+        Sample::Off,
+        Sample::Calibrating,
+        Sample::Overrange,
+        Sample::Underrange,
+        Sample::Reserved,
+    ]);
+    loopback(&[]);
+
+    // Elements have to be enums, and their positions within the dictionary
+    assert_eq!(
+        to_nibble_allocvec(&NibbleEnumDict(vec![1u8, 2])),
+        Err(Error::SerdeSerCustom)
+    );
+    let ser = to_nibble_allocvec(&NibbleEnumDict(vec![Sample::Off, Sample::Reserved])).unwrap();
+    let mut bad = ser.clone();
+    let last = bad.len() - 1;
+    // The positions 0 and 1, the second one replaced by 2
+    assert_eq!(ser.as_slice(), &[0x20, 0x72, 0x01]);
+    bad[last] = 0x02;
+    assert!(from_nibbles::<NibbleEnumDict<Sample>>(&bad).is_err());
+
+    // A dictionary entry beyond the variants, 9 instead of Sensor's 8
+    assert_eq!(
+        from_nibbles::<NibbleEnumDict<Sample>>(&[0x19, 0x12, 0x01]),
+        Err(Error::DeserializeBadEnum { index: 9, count: 9 })
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn max_nibble_size() {
+    use postcard::max_nibble_size::MaxNibbleSize;
+    use postcard::nibble_size;
+
+    assert_eq!(<u32 as MaxNibbleSize>::MAX_NIBBLES, 11);
+    assert_eq!(<u8 as MaxNibbleSize>::MAX_NIBBLES, 3);
+    assert_eq!(<[u16; 4] as MaxNibbleSize>::MAX_NIBBLES, 24);
+    assert_eq!(<(bool, Option<u8>) as MaxNibbleSize>::MAX_NIBBLE_BYTES, 3);
+
+    // Values at their largest fit into that many bytes, and decode back from them
+    fn round_trip<T>(value: T)
+    where
+        T: Serialize + DeserializeOwned + MaxNibbleSize + PartialEq + Debug,
+    {
+        let mut buf = [0u8; 64];
+        let used = postcard::to_nibble_slice(&value, &mut buf[..T::MAX_NIBBLE_BYTES]).unwrap();
+        assert_eq!(from_nibbles::<T>(used), Ok(value));
+    }
+    round_trip((true, Some(u8::MAX)));
+    round_trip([Some(u16::MAX), Some(u16::MAX)]);
+    round_trip(Some(Some(i8::MIN)));
+    round_trip((Err::<u8, u32>(u32::MAX), Ok::<u8, u32>(u8::MAX)));
+
+    fn check<T: Serialize + MaxNibbleSize>(value: &T) {
+        let nibbles = nibble_size(value).unwrap();
+        assert!(
+            nibbles <= T::MAX_NIBBLES,
+            "{} > {}",
+            nibbles,
+            T::MAX_NIBBLES
+        );
+        let mut buf = [0u8; 256];
+        assert!(postcard::to_nibble_slice(value, &mut buf[..T::MAX_NIBBLE_BYTES]).is_ok());
+    }
+
+    // xorshift64*, spread over all widths by shifting out a random number of bits
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        let r = state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        r >> (r % 64)
+    };
+    for _ in 0..2000 {
+        let (a, b, c) = (next(), next(), next());
+        check(&(a as u8, a as i8, a as u16, a as i16));
+        check(&(b as u32, b as i32, b, b as i64));
+        check(&((a as u128) << 64 | b as u128, (c as i128) << 64 | a as i128));
+        check(&(
+            a as usize,
+            b as isize,
+            f32::from_bits(c as u32),
+            f64::from_bits(a),
+        ));
+        check(&(
+            char::from_u32(c as u32 % 0x11_0000).unwrap_or('\u{10FFFF}'),
+            Some(a % 2 == 0),
+            [a as u16, b as u16, c as u16],
+        ));
+        let mut s = String::<8>::new();
+        for _ in 0..(a % 3) {
+            s.push(char::from_u32(0x1F600 + (b as u32 % 64)).unwrap())
+                .unwrap();
+        }
+        let v: Vec<u64, 4> = [a, b, c].iter().take((c % 4) as usize).copied().collect();
+        check(&(true, s, v, Some(c as i32)));
+    }
+
+    // The extremes
+    check(&(u8::MAX, i8::MIN, u16::MAX, i16::MIN, u32::MAX, i32::MIN));
+    check(&(u64::MAX, i64::MIN, u128::MAX, i128::MIN));
+    check(&(usize::MAX, isize::MIN, f64::NAN, '\u{10FFFF}'));
+    let full: String<8> = String::from("12345678");
+    check(&(false, full));
+}
+
+#[test]
+fn step_limit() {
+    use postcard::de_nibble_flavors::{NibbleSlice, NibbleStepLimit};
+    use postcard::{from_nibbles_limited, from_nibbles_with_flavor, Error};
+
+    let data = DataEnum::Chi { a: 3, b: 0xFFFF };
+    let mut buf = [0u8; 16];
+    let ser = postcard::to_nibble_slice(&data, &mut buf).unwrap();
+
+    let mut flavor = NibbleStepLimit::new(NibbleSlice::new(ser), usize::MAX);
+    let (back, ()) = from_nibbles_with_flavor::<DataEnum, _>(&mut flavor).unwrap();
+    assert_eq!(back, data);
+    let steps = flavor.steps();
+    assert!(steps > 3 && steps < 16, "{}", steps);
+
+    assert_eq!(from_nibbles_limited(ser, steps), Ok(data));
+    assert_eq!(
+        from_nibbles_limited::<DataEnum>(ser, steps - 1),
+        Err(Error::DeserializeStepLimit)
+    );
+    assert_eq!(
+        from_nibbles_limited::<DataEnum>(ser, 0),
+        Err(Error::DeserializeStepLimit)
+    );
+
+    // Five bytes claiming 2^30 zero sized elements
+    #[cfg(feature = "alloc")]
+    {
+        let bomb = [0xFF, 0xFF, 0xFF, 0xFF, 0xF7];
+        assert_eq!(
+            from_nibbles_limited::<std::vec::Vec<()>>(&bomb, 10_000),
+            Err(Error::DeserializeStepLimit)
+        );
+        // Too short to decode that many values with actual contents
+        assert_eq!(
+            from_nibbles_limited::<std::vec::Vec<u8>>(&bomb, 10_000),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+    }
+}
+
+#[cfg(all(feature = "heapless", feature = "alloc"))]
+#[test]
+fn nested_seqs() {
+    use std::vec::Vec as StdVec;
+
+    // Each level has its own length prefix, an empty sequence is just its length
+    test_one(StdVec::<StdVec<u16>>::new(), &[0x00]);
+    test_one(vec![StdVec::<u16>::new()], &[0x10]);
+    test_one(
+        vec![vec![1u16, 2], vec![], vec![0xFFFF]],
+        &[0x32, 0x12, 0x01, 0x9F, 0xFF, 0xF7],
+    );
+    test_one(vec![vec![], vec![], vec![3u16]], &[0x30, 0x01, 0x30]);
+    // Inner lengths that take more than one nibble
+    test_one(vec![vec![7u16; 9]], &[0x19, 0x17, 0x77, 0x77, 0x77, 0x77]);
+
+    let mut nested: Vec<Vec<u16, 4>, 4> = Vec::new();
+    nested.push(Vec::new()).unwrap();
+    nested.push(Vec::from_slice(&[8, 0]).unwrap()).unwrap();
+    test_one(nested, &[0x20, 0x29, 0x00]);
+
+    // Deeper nesting and longer sequences survive a loopback
+    let deep: StdVec<StdVec<StdVec<u16>>> = (0..10)
+        .map(|i| {
+            (0..i)
+                .map(|j| (0..j * 7).map(|k| k * 1000).collect())
+                .collect()
+        })
+        .collect();
+    let ser: Vec<u8, 4096> = to_nibble_vec(&deep).unwrap();
+    assert_eq!(from_nibbles::<StdVec<StdVec<StdVec<u16>>>>(&ser), Ok(deep));
+
+    // An inner length running past the end of the message is not mistaken for the outer one
+    assert_eq!(
+        from_nibbles::<StdVec<StdVec<u16>>>(&[0x13, 0x10]),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn max_depth() {
+    use postcard::{from_nibbles_with_depth, Error, NIBBLE_DEFAULT_MAX_DEPTH};
+    use std::vec::Vec as StdVec;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Nest(StdVec<Nest>);
+
+    // `levels` sequences, each holding the next one, the innermost one empty
+    fn nest(levels: usize) -> StdVec<u8> {
+        let mut nibbles = vec![1u8; levels - 1];
+        // The empty innermost sequence and a padding nibble
+        nibbles.extend([0, 0]);
+        nibbles.chunks_exact(2).map(|n| n[0] << 4 | n[1]).collect()
+    }
+
+    // Every `Nest` is two levels, the newtype struct and the sequence in it
+    let deep = nest(NIBBLE_DEFAULT_MAX_DEPTH / 2);
+    let back: Nest = from_nibbles(&deep).unwrap();
+    assert_eq!(postcard::to_nibble_allocvec(&back).unwrap(), deep);
+
+    let too_deep = nest(NIBBLE_DEFAULT_MAX_DEPTH / 2 + 1);
+    assert_eq!(
+        from_nibbles::<Nest>(&too_deep),
+        Err(Error::DeserializeMaxDepthExceeded)
+    );
+    assert!(from_nibbles_with_depth::<Nest>(&too_deep, NIBBLE_DEFAULT_MAX_DEPTH + 2).is_ok());
+
+    // A hostile message nesting far deeper fails early, instead of exhausting the stack
+    let hostile = nest(1_000_000);
+    assert_eq!(
+        from_nibbles::<Nest>(&hostile),
+        Err(Error::DeserializeMaxDepthExceeded)
+    );
+
+    // Every sequence, struct and enum is one level
+    type Vec3 = StdVec<StdVec<StdVec<u8>>>;
+    let data: Vec3 = vec![vec![vec![1, 2]], vec![]];
+    let ser = postcard::to_nibble_allocvec(&data).unwrap();
+    assert_eq!(from_nibbles_with_depth::<Vec3>(&ser, 3), Ok(data));
+    assert_eq!(
+        from_nibbles_with_depth::<Vec3>(&ser, 2),
+        Err(Error::DeserializeMaxDepthExceeded)
+    );
+    assert_eq!(
+        from_nibbles_with_depth::<(u8, DataEnum)>(&[0x20, 0x00], 1),
+        Err(Error::DeserializeMaxDepthExceeded)
+    );
+    assert_eq!(
+        from_nibbles_with_depth::<(u8, DataEnum)>(&[0x20, 0x00], 2),
+        Ok((2, DataEnum::Bib(0)))
+    );
+
+    // So is every `Some` and newtype struct, recursing through them is limited as well
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct List(Option<Box<List>>);
+
+    let list = List(Some(Box::new(List(Some(Box::new(List(None)))))));
+    let ser = postcard::to_nibble_allocvec(&list).unwrap();
+    assert_eq!(ser, &[0x11, 0x00]);
+    assert_eq!(from_nibbles_with_depth::<List>(&ser, 5), Ok(list));
+    assert_eq!(
+        from_nibbles_with_depth::<List>(&ser, 4),
+        Err(Error::DeserializeMaxDepthExceeded)
+    );
+    let hostile = vec![0x11; 2_000_000];
+    assert_eq!(
+        from_nibbles::<List>(&hostile),
+        Err(Error::DeserializeMaxDepthExceeded)
+    );
+}
+
+#[test]
+fn located_errors() {
+    use postcard::{from_nibbles_located, Error};
+
+    type Msg<'a> = (u8, u16, &'a str);
+    // 7, then 300 as C D 4, the length 5, a padding nibble and the bytes of "hello"
+    let good = [0x7C, 0xD4, 0x50, b'h', b'e', b'l', b'l', b'o'];
+    assert_eq!(from_nibbles_located::<Msg>(&good), Ok((7, 300, "hello")));
+
+    let end = |len: usize| from_nibbles_located::<Msg>(&good[..len]).unwrap_err();
+    assert_eq!(end(0), (Error::DeserializeUnexpectedEnd, 0));
+    // Within the u16, after all nibbles of the input were consumed
+    assert_eq!(end(1), (Error::DeserializeUnexpectedEnd, 2));
+    // At the string length
+    assert_eq!(end(2), (Error::DeserializeUnexpectedEnd, 4));
+    // At the start of the string bytes, which don't fit anymore
+    for len in 3..good.len() {
+        assert_eq!(end(len), (Error::DeserializeUnexpectedEnd, 6));
+    }
+
+    // Malformed data reports the position after the offending nibbles
+    assert_eq!(
+        from_nibbles_located::<(u8, bool)>(&[0x72]),
+        Err((Error::DeserializeBadBool, 2))
+    );
+}
+
+#[test]
+fn aligned_runs() {
+    use postcard::de_nibble_flavors::{
+        NibbleAligned as DeAligned, NibbleNamedVariants as DeNamed, NibbleSlice as DeSlice,
+    };
+    use postcard::ser_nibble_flavors::{NibbleAligned, NibbleNamedVariants, NibbleSlice};
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+
+    type Msg<'a> = (bool, &'a str, u16, f32, u8);
+    let data: Msg = (true, "odd", 0x1234, 1.5, 7);
+
+    for align in [1, 2, 4] {
+        let mut buf = [0xAAu8; 64];
+        let used = serialize_with_nibble_flavor(
+            &data,
+            NibbleAligned::new(NibbleSlice::new(&mut buf), align),
+        )
+        .unwrap();
+        assert_eq!(used.len() % align, 0, "align {}", align);
+        // The bool, the length and padding, then the string
+        let start = used.windows(3).position(|w| w == b"odd").unwrap();
+        assert_eq!(start % align, 0, "align {}", align);
+
+        let (back, rest) =
+            from_nibbles_with_flavor::<Msg, _>(DeAligned::new(DeSlice::new(used), align)).unwrap();
+        assert_eq!(back, data);
+        assert!(rest.is_empty());
+    }
+
+    // The same as the plain format when aligned to bytes
+    let mut plain = [0u8; 64];
+    let plain = postcard::to_nibble_slice(&data, &mut plain).unwrap();
+    let mut buf = [0u8; 64];
+    let used =
+        serialize_with_nibble_flavor(&data, NibbleAligned::new(NibbleSlice::new(&mut buf), 1))
+            .unwrap();
+    assert_eq!(used, plain);
+
+    // Padding mid-stream, to a boundary independent of the configured one
+    let mut buf = [0u8; 64];
+    let mut flavor = NibbleAligned::new(NibbleSlice::new(&mut buf), 2);
+    serialize_with_nibble_flavor(&7u8, &mut flavor).unwrap();
+    flavor.align_to(4).unwrap();
+    serialize_with_nibble_flavor(&"hi", &mut flavor).unwrap();
+    let used = postcard::ser_nibble_flavors::NibbleFlavor::finalize(flavor).unwrap();
+    assert_eq!(used, &[0x70, 0x00, 0x00, 0x00, 0x20, 0x00, b'h', b'i']);
+
+    let mut flavor = DeAligned::new(DeSlice::new(used), 2);
+    let (seven, ()) = from_nibbles_with_flavor::<u8, _>(&mut flavor).unwrap();
+    flavor.align_to(4).unwrap();
+    let (hi, ()) = from_nibbles_with_flavor::<&str, _>(&mut flavor).unwrap();
+    assert_eq!((seven, hi), (7, "hi"));
+    let rest = postcard::de_nibble_flavors::NibbleFlavor::finalize(flavor).unwrap();
+    assert!(rest.is_empty());
+
+    // Variants written by name around the alignment
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Idle,
+        Run(u8),
+    }
+    let modes = (Mode::Run(3), Mode::Idle);
+    let mut buf = [0u8; 64];
+    let used = serialize_with_nibble_flavor(
+        &modes,
+        NibbleNamedVariants::new(NibbleAligned::new(NibbleSlice::new(&mut buf), 4)),
+    )
+    .unwrap();
+    assert_eq!(used.len() % 4, 0);
+    let flavor = DeNamed::new(DeAligned::new(DeSlice::new(used), 4));
+    assert_eq!(
+        from_nibbles_with_flavor::<(Mode, Mode), _>(flavor).map(|(v, _)| v),
+        Ok(modes)
+    );
+
+    // Rewinding for the second alternative also rewinds the position
+    type Either<'a> = NibbleUntagged<(u8, bool), (u8, &'a str)>;
+    let value: Either = NibbleUntagged::Second((5, "hi"));
+    let mut buf = [0u8; 64];
+    let used =
+        serialize_with_nibble_flavor(&value, NibbleAligned::new(NibbleSlice::new(&mut buf), 4))
+            .unwrap();
+    let flavor = DeAligned::new(DeSlice::new(used), 4);
+    assert_eq!(
+        from_nibbles_with_flavor::<Either, _>(flavor).map(|(v, _)| v),
+        Ok(value)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn error_paths() {
+    use postcard::{from_nibbles_with_path, Error, NibblePathError};
+    use std::collections::BTreeMap;
+    use std::string::String as StdString;
+    use std::vec::Vec as StdVec;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Item {
+        id: u8,
+        value: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Kind {
+        Plain,
+        Tagged { tag: u8, items: StdVec<Item> },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Outer {
+        items: StdVec<Item>,
+        kind: Kind,
+        names: BTreeMap<u8, StdString>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Msg {
+        version: u8,
+        outer: Outer,
+    }
+
+    let item = |id| Item { id, value: true };
+    let msg = Msg {
+        version: 1,
+        outer: Outer {
+            items: (0..5).map(item).collect(),
+            kind: Kind::Tagged {
+                tag: 2,
+                items: vec![item(7)],
+            },
+            names: vec![(1, "one".into()), (2, "two".into())]
+                .into_iter()
+                .collect(),
+        },
+    };
+    let good = postcard::to_nibble_allocvec(&msg).unwrap();
+    assert_eq!(from_nibbles_with_path::<Msg>(&good), Ok(msg));
+
+    // Corrupt a single nibble, found by the position of the value in the message
+    let corrupt = |nibble: usize, value: u8| {
+        let mut bad = good.clone();
+        let shift = if nibble & 1 == 0 { 4 } else { 0 };
+        bad[nibble / 2] = bad[nibble / 2] & !(0xF << shift) | value << shift;
+        from_nibbles_with_path::<Msg>(&bad).unwrap_err()
+    };
+    let err = |error, path: &str| NibblePathError {
+        error,
+        path: path.into(),
+    };
+
+    // version, items length, then id and value of each item
+    assert_eq!(
+        corrupt(1 + 1 + 3 * 2 + 1, 9),
+        err(Error::DeserializeBadBool, "outer.items[3].value")
+    );
+    // The variant index after the items
+    assert_eq!(
+        corrupt(1 + 1 + 5 * 2, 5),
+        err(
+            Error::DeserializeBadEnum { index: 5, count: 2 },
+            "outer.kind"
+        )
+    );
+    // The value of the item within the variant: variant, tag, length, id
+    assert_eq!(
+        corrupt(1 + 1 + 5 * 2 + 3 + 1, 3),
+        err(
+            Error::DeserializeBadBool,
+            "outer.kind::Tagged.items[0].value"
+        )
+    );
+
+    // A truncated message ends within the map, in the first string of its second entry
+    let truncated = &good[..good.len() - 2];
+    let failed = from_nibbles_with_path::<Msg>(truncated).unwrap_err();
+    assert_eq!(
+        failed,
+        err(Error::DeserializeUnexpectedEnd, "outer.names[1]")
+    );
+    assert_eq!(
+        std::format!("{}", failed),
+        "Hit the end of buffer, expected more data at `outer.names[1]`"
+    );
+
+    // A failure of the message as a whole has no path
+    assert_eq!(
+        from_nibbles_with_path::<u8>(&[]),
+        Err(err(Error::DeserializeUnexpectedEnd, ""))
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn xor_obfuscation() {
+    use postcard::de_nibble_flavors::{NibblePackedBools as DePacked, NibbleXor as DeXor};
+    use postcard::ser_nibble_flavors::{NibblePackedBools, NibbleSlice, NibbleXor};
+    use postcard::{
+        from_nibbles_with_flavor, from_nibbles_xor, serialize_with_nibble_flavor, to_nibble_vec_xor,
+    };
+
+    let data = (true, 0x1234u16, "hello there", [3u8, 1, 4], 2.5f32, 9u8);
+    let key = [0x5A, 0xC3, 0x99];
+
+    let plain: Vec<u8, 64> = to_nibble_vec(&data).unwrap();
+    let obfuscated: Vec<u8, 64> = to_nibble_vec_xor(&data, &key).unwrap();
+    assert_eq!(obfuscated.len(), plain.len());
+    // Exactly the plain bytes XORed with the repeating key
+    for (i, (o, p)) in obfuscated.iter().zip(plain.iter()).enumerate() {
+        assert_eq!(*o, p ^ key[i % key.len()], "byte {}", i);
+        assert_ne!(o, p);
+    }
+
+    let mut buf = obfuscated.clone();
+    assert_eq!(from_nibbles_xor(&mut buf, &key), Ok(data));
+
+    // A wrong key gives garbage: an error or other values, but never a crash
+    for wrong in [[0x5A, 0xC3, 0x98], [0x00, 0x00, 0x00], [0xFF, 0x3C, 0x66]].iter() {
+        let mut buf = obfuscated.clone();
+        let res = from_nibbles_xor::<(bool, u16, &str, [u8; 3], f32, u8)>(&mut buf, wrong);
+        assert_ne!(res, Ok(data));
+    }
+    // An empty key leaves the message unchanged
+    let unchanged: Vec<u8, 64> = to_nibble_vec_xor(&data, &[]).unwrap();
+    assert_eq!(unchanged, plain);
+
+    // Any single key leaves short messages decodable, as long as both sides use it
+    for k in 0..=255u8 {
+        let mut buf: Vec<u8, 8> = to_nibble_vec_xor(&(k, false), &[k]).unwrap();
+        assert_eq!(from_nibbles_xor(&mut buf, &[k]), Ok((k, false)));
+    }
+
+    // Packed booleans are obfuscated like any other data
+    let flags = ([true, false, true, true, true], 7u8);
+    let packed: Vec<u8, 8> = postcard::to_nibble_vec_packed(&flags).unwrap();
+    let mut buf = [0u8; 8];
+    let used = serialize_with_nibble_flavor(
+        &flags,
+        NibblePackedBools::new(NibbleXor::new(NibbleSlice::new(&mut buf), &key)),
+    )
+    .unwrap();
+    assert_eq!(used.len(), packed.len());
+    for (i, (o, p)) in used.iter().zip(packed.iter()).enumerate() {
+        assert_eq!(*o, p ^ key[i % key.len()], "byte {}", i);
+    }
+    let flavor = DePacked::new(DeXor::new(used, &key));
+    assert_eq!(
+        from_nibbles_with_flavor::<([bool; 5], u8), _>(flavor).map(|(v, _)| v),
+        Ok(flags)
+    );
+}
+
+#[test]
+fn fletcher16() {
+    use postcard::de_nibble_flavors::{NibbleFletcher16 as DeFletcher, NibbleSlice as DeSlice};
+    use postcard::ser_nibble_flavors::{NibbleFletcher16, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Reading<'a> {
+        id: u16,
+        ok: bool,
+        label: &'a str,
+        value: u32,
+        flags: u8,
+    }
+
+    let reading = Reading {
+        id: 0x1234,
+        ok: true,
+        label: "temp",
+        value: 21_500,
+        flags: 5,
+    };
+
+    let mut buf = [0u8; 32];
+    let used =
+        serialize_with_nibble_flavor(&reading, NibbleFletcher16::new(NibbleSlice::new(&mut buf)))
+            .unwrap()
+            .len();
+    let mut plain = [0u8; 32];
+    let plain = postcard::to_nibble_slice(&reading, &mut plain).unwrap();
+    assert_eq!(&buf[..plain.len()], &plain[..]);
+    assert_eq!(used, plain.len() + 2);
+
+    let frame = &buf[..used];
+    let (decoded, rest) =
+        from_nibbles_with_flavor::<Reading, _>(DeFletcher::new(DeSlice::new(frame))).unwrap();
+    assert_eq!(decoded, reading);
+    assert!(rest.is_empty());
+
+    // Flipping any bit of the message or of the checksum is detected
+    for i in 0..used {
+        for bit in 0..8 {
+            let mut corrupt = [0u8; 32];
+            corrupt[..used].copy_from_slice(frame);
+            corrupt[i] ^= 1 << bit;
+            let res = from_nibbles_with_flavor::<Reading, _>(DeFletcher::new(DeSlice::new(
+                &corrupt[..used],
+            )));
+            assert!(res.is_err(), "byte {} bit {}", i, bit);
+        }
+    }
+    // A changed value byte that still decodes fails on the checksum
+    let mut corrupt = [0u8; 32];
+    corrupt[..used].copy_from_slice(frame);
+    corrupt[used - 3] ^= 0x01;
+    let res =
+        from_nibbles_with_flavor::<Reading, _>(DeFletcher::new(DeSlice::new(&corrupt[..used])));
+    assert_eq!(res.err(), Some(Error::DeserializeBadChecksum));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn fletcher16_passes_hooks_on() {
+    use postcard::de_nibble_flavors::{
+        NibbleFletcher16 as DeFletcher, NibbleNamedVariants as DeNamed,
+        NibblePackedBools as DePacked, NibbleSlice as DeSlice,
+    };
+    use postcard::ser_nibble_flavors::{
+        NibbleFletcher16, NibbleNamedVariants, NibblePackedBools, NibbleSlice,
+    };
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    // Booleans are packed by the inner flavor, then the checksum follows
+    let flags = ([true, false, true, true, false], 7u8, "on");
+    let mut buf = [0u8; 32];
+    let frame = serialize_with_nibble_flavor(
+        &flags,
+        NibbleFletcher16::new(NibblePackedBools::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    let packed: Vec<u8, 32> = postcard::to_nibble_vec_packed(&flags).unwrap();
+    assert_eq!(&frame[..frame.len() - 2], &packed[..]);
+    let (back, _) = from_nibbles_with_flavor::<([bool; 5], u8, &str), _>(DeFletcher::new(
+        DePacked::new(DeSlice::new(frame)),
+    ))
+    .unwrap();
+    assert_eq!(back, flags);
+    let len = frame.len();
+    frame[len - 1] ^= 0x01;
+    let res = from_nibbles_with_flavor::<([bool; 5], u8, &str), _>(DeFletcher::new(DePacked::new(
+        DeSlice::new(frame),
+    )));
+    assert_eq!(res.err(), Some(Error::DeserializeBadChecksum));
+
+    // Variants are written by name by the inner flavor
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Idle,
+        Run(u8),
+    }
+    let modes = [Mode::Run(3), Mode::Idle];
+    let mut buf = [0u8; 32];
+    let frame = serialize_with_nibble_flavor(
+        &modes,
+        NibbleFletcher16::new(NibbleNamedVariants::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    let named: Vec<u8, 32> = postcard::to_nibble_vec_named_variants(&modes).unwrap();
+    assert_eq!(&frame[..frame.len() - 2], &named[..]);
+    let (back, _) = from_nibbles_with_flavor::<[Mode; 2], _>(DeFletcher::new(DeNamed::new(
+        DeSlice::new(frame),
+    )))
+    .unwrap();
+    assert_eq!(back, modes);
+
+    // The map hooks reach the inner flavor
+    let mut map = FnvIndexMap::<u8, bool, 4>::new();
+    map.insert(1, true).unwrap();
+    map.insert(2, false).unwrap();
+    let mut buf = [0u8; 32];
+    let (_, calls) = serialize_with_nibble_flavor(
+        &map,
+        NibbleFletcher16::new(MapHooks::new(NibbleSlice::new(&mut buf))),
+    )
+    .unwrap();
+    assert_eq!(calls, [1, 2, 1]);
+
+    // Rewinding for the second alternative also rewinds the checksum
+    type Either = NibbleUntagged<(bool, bool), (u8, u8)>;
+    let value: (Either, Either) = (
+        NibbleUntagged::Second((5, 7)),
+        NibbleUntagged::First((true, false)),
+    );
+    let mut buf = [0u8; 32];
+    let frame =
+        serialize_with_nibble_flavor(&value, NibbleFletcher16::new(NibbleSlice::new(&mut buf)))
+            .unwrap();
+    let (back, rest) =
+        from_nibbles_with_flavor::<(Either, Either), _>(DeFletcher::new(DeSlice::new(frame)))
+            .unwrap();
+    assert_eq!(back, value);
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn bit_flavor() {
+    use postcard::de_nibble_flavors::BitFlavor as DeBits;
+    use postcard::ser_nibble_flavors::BitFlavor;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor, Error};
+
+    let fields: &[(u64, u32)] = &[
+        (1, 1),
+        (0b101, 3),
+        (0b10011, 5),
+        (0x55, 7),
+        (0, 1),
+        (0b111, 3),
+        (0, 5),
+        (0x7F, 7),
+        (0x1234_5678_9ABC_DEF0, 64),
+        (0b01, 2),
+    ];
+    let total: u32 = fields.iter().map(|(_, n)| n).sum();
+
+    let mut buf = [0u8; 32];
+    let mut bits = BitFlavor::new(&mut buf);
+    for (value, nbits) in fields {
+        bits.push_bits(*value, *nbits).unwrap();
+    }
+    assert_eq!(bits.bits_written(), total as usize);
+    let used = serialize_with_nibble_flavor(&(0xA5u8, "hi", 300u16), bits).unwrap();
+    let used = &used[..];
+
+    let mut bits = DeBits::new(used);
+    for (value, nbits) in fields {
+        assert_eq!(bits.take_bits(*nbits), Ok(*value));
+    }
+    let (value, rest) = from_nibbles_with_flavor::<(u8, &str, u16), _>(bits).unwrap();
+    assert_eq!(value, (0xA5, "hi", 300));
+    assert!(rest.is_empty());
+
+    // Bits beyond the field width are not written
+    let mut buf = [0xFFu8; 1];
+    let mut bits = BitFlavor::new(&mut buf);
+    bits.push_bits(0xFF, 3).unwrap();
+    assert_eq!(
+        serialize_with_nibble_flavor(&(), bits).unwrap(),
+        &[0b0000_0111]
+    );
+
+    // Running out of room or input
+    let mut buf = [0u8; 1];
+    let mut bits = BitFlavor::new(&mut buf);
+    bits.push_bits(0, 5).unwrap();
+    assert_eq!(bits.push_bits(0, 4), Err(Error::SerializeBufferFull));
+    let mut bits = DeBits::new(&[0x00]);
+    assert_eq!(bits.take_bits(5), Ok(0));
+    assert_eq!(bits.take_bits(4), Err(Error::DeserializeUnexpectedEnd));
+
+    // Without byte runs, the nibble format with the halves of each byte swapped
+    let data = (0x1234u16, true, 0xC3u8, 70_000u32, false);
+    let mut buf = [0u8; 16];
+    let swapped = serialize_with_nibble_flavor(&data, BitFlavor::new(&mut buf)).unwrap();
+    let mut plain = [0u8; 16];
+    let plain = postcard::to_nibble_slice(&data, &mut plain).unwrap();
+    assert_eq!(swapped.len(), plain.len());
+    for (s, p) in swapped.iter().zip(plain.iter()) {
+        assert_eq!(*s, p.rotate_left(4));
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn nibble_size_estimate() {
+    use postcard::max_nibble_size::{MaxNibbleSize, NibbleSizeEstimate};
+    use postcard::nibble_size;
+
+    type Msg = (u32, Vec<u8, 16>, u64, bool);
+
+    // xorshift64*
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    };
+    // A sample distribution: integers of a uniformly random bit width, sequences of a uniformly
+    // random length
+    let int = |r: u64, width: u64, bits: u64| {
+        r.checked_shr(64 - (width % (bits + 1)) as u32).unwrap_or(0)
+    };
+
+    let count = 1000;
+    let mut total = 0;
+    for _ in 0..count {
+        let len = next() % 17;
+        let v: Vec<u8, 16> = (0..len).map(|_| int(next(), next(), 8) as u8).collect();
+        let msg: Msg = (
+            int(next(), next(), 32) as u32,
+            v,
+            int(next(), next(), 64),
+            next() & 1 == 1,
+        );
+        let nibbles = nibble_size(&msg).unwrap();
+        assert!(nibbles <= NibbleSizeEstimate::new(100).nibbles::<Msg>());
+        total += nibbles;
+    }
+    let average = total / count;
+
+    // The sequence is half full of elements using about half their width, so typical messages
+    // use well under half of the bound. Assuming 40%, the estimate from the bound is within a
+    // fifth of the actual average.
+    let estimate = NibbleSizeEstimate::new(40).nibbles::<Msg>();
+    assert!(
+        estimate * 5 >= average * 4 && estimate * 5 <= average * 6,
+        "estimated {}, average {}",
+        estimate,
+        average
+    );
+
+    // Monotonic in the fill ratio, and never beyond the bound
+    let mut last = 0;
+    for percent in 0..=255u8 {
+        let estimate = NibbleSizeEstimate::new(percent);
+        assert!(estimate.nibbles::<Msg>() >= last);
+        assert!(estimate.nibbles::<Msg>() <= Msg::MAX_NIBBLES);
+        assert!(estimate.bytes::<Msg>() <= Msg::MAX_NIBBLE_BYTES);
+        last = estimate.nibbles::<Msg>();
+    }
+    assert_eq!(NibbleSizeEstimate::new(0).nibbles::<Msg>(), 0);
+    assert_eq!(NibbleSizeEstimate::new(200).fill_percent(), 100);
+}
+
+#[test]
+fn seq_reader() {
+    use postcard::{to_nibble_slice, Error, NibbleSeqReader};
+
+    // Three packed messages, the middle one ending in the middle of a byte
+    let mut log = [0u8; 16];
+    let mut used = 0;
+    for value in [7u16, 0x1234, 300].iter() {
+        used += to_nibble_slice(value, &mut log[used..]).unwrap().len();
+    }
+    let log = &log[..used];
+
+    let mut reader = NibbleSeqReader::<u16>::new(log);
+    assert_eq!(reader.next(), Some(Ok(7)));
+    assert_eq!(reader.next(), Some(Ok(0x1234)));
+    assert_eq!(reader.next(), Some(Ok(300)));
+    assert_eq!(reader.next(), None);
+    assert_eq!(reader.next(), None);
+    assert!(reader.remaining().is_empty());
+
+    let all: Result<std::vec::Vec<u16>, Error> = NibbleSeqReader::new(log).collect();
+    assert_eq!(all, Ok(std::vec![7, 0x1234, 300]));
+
+    // A partial trailing message yields its error, then the iteration ends
+    let mut reader = NibbleSeqReader::<u16>::new(&log[..used - 1]);
+    assert_eq!(reader.next(), Some(Ok(7)));
+    assert_eq!(reader.next(), Some(Ok(0x1234)));
+    assert_eq!(reader.next(), Some(Err(Error::DeserializeUnexpectedEnd)));
+    assert_eq!(reader.next(), None);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn start_at_nibble() {
+    use postcard::de_nibble_flavors::{NibbleFlavor as _, NibbleSlice as DeSlice};
+    use postcard::ser_nibble_flavors::{NibbleHVec, NibbleSlice};
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+
+    // A tag nibble written by hand, the rest of the buffer holding stale data
+    let mut buf = [0xFFu8; 8];
+    buf[0] = 0xA << 4;
+    let used = serialize_with_nibble_flavor(&200u8, NibbleSlice::new_at_nibble(&mut buf, 1))
+        .unwrap()
+        .len();
+    // The tag and the first nibble of the value share a byte
+    assert_eq!(&buf[..used], &[0xAB, 0x90]);
+
+    let mut de = DeSlice::new(&buf[..used]);
+    assert_eq!(de.try_take_nib(), Ok(0xA));
+    let (value, ()) = from_nibbles_with_flavor::<u8, _>(&mut de).unwrap();
+    assert_eq!(value, 200);
+
+    // At even offsets, whole bytes are kept
+    let mut buf = [0x12u8, 0xFF, 0xFF];
+    let used = serialize_with_nibble_flavor(&(true, 3u8), NibbleSlice::new_at_nibble(&mut buf, 2))
+        .unwrap();
+    assert_eq!(used, &[0x12, 0x13]);
+
+    // Offset 0 is the same as `new`
+    let data = (7u16, "hi", false);
+    let mut a = [0xEEu8; 16];
+    let mut b = [0xEEu8; 16];
+    let a = serialize_with_nibble_flavor(&data, NibbleSlice::new(&mut a)).unwrap();
+    let b = serialize_with_nibble_flavor(&data, NibbleSlice::new_at_nibble(&mut b, 0)).unwrap();
+    assert_eq!(a, b);
+
+    // The same for `NibbleHVec`, dropping anything after the offset
+    let mut vec = Vec::<u8, 8>::new();
+    vec.extend_from_slice(&[0xAF, 0xFF]).unwrap();
+    let out = serialize_with_nibble_flavor(&200u8, NibbleHVec::new_at_nibble(vec, 1)).unwrap();
+    assert_eq!(out, &[0xAB, 0x90][..]);
+    let out = serialize_with_nibble_flavor(&data, NibbleHVec::<16>::new_at_nibble(Vec::new(), 0))
+        .unwrap();
+    assert_eq!(out, a);
+}
+
+#[test]
+fn single_nibble_unit_enums() {
+    use core::cmp::Ordering;
+    use postcard::{nibble_size, to_nibble_slice, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Compared(#[serde(with = "postcard::nibble_ordering")] Ordering);
+
+    for &(ordering, nib) in [
+        (Ordering::Less, 0u8),
+        (Ordering::Equal, 1),
+        (Ordering::Greater, 2),
+    ]
+    .iter()
+    {
+        let value = Compared(ordering);
+        assert_eq!(nibble_size(&value), Ok(1));
+        let mut buf = [0u8; 4];
+        let used = to_nibble_slice(&value, &mut buf).unwrap();
+        assert_eq!(used, &[nib << 4]);
+        assert_eq!(from_nibbles::<Compared>(used), Ok(value));
+    }
+    assert_eq!(
+        from_nibbles::<Compared>(&[0x30]),
+        Err(Error::DeserializeBadEnum { index: 3, count: 3 })
+    );
+
+    // Several of them share a byte
+    let many = [
+        Compared(Ordering::Greater),
+        Compared(Ordering::Less),
+        Compared(Ordering::Equal),
+    ];
+    let mut buf = [0u8; 4];
+    assert_eq!(to_nibble_slice(&many, &mut buf).unwrap(), &[0x20, 0x10]);
+
+    // The same goes for the tags of `Option` and `Result`
+    let options = [None, Some(5u8), Some(200)];
+    let mut buf = [0u8; 8];
+    let used = to_nibble_slice(&options, &mut buf).unwrap();
+    assert_eq!(used, &[0x01, 0x51, 0xB9, 0x00]);
+    assert_eq!(from_nibbles::<[Option<u8>; 3]>(used), Ok(options));
+    assert_eq!(
+        from_nibbles::<Option<u8>>(&[0x25]),
+        Err(Error::DeserializeBadOption)
+    );
+
+    let results = [Ok::<u8, bool>(7), Err(true)];
+    let used = to_nibble_slice(&results, &mut buf).unwrap();
+    assert_eq!(used, &[0x07, 0x11]);
+    assert_eq!(from_nibbles::<[Result<u8, bool>; 2]>(used), Ok(results));
+
+    // Just like `u8`, `i8` is a nibble varint of its two's complement byte
+    let signed = [0i8, 5, -3, i8::MIN, i8::MAX];
+    let used = to_nibble_slice(&signed, &mut buf).unwrap();
+    assert_eq!(used, &[0x05, 0xBF, 0x5A, 0x80, 0x9F, 0x70]);
+    assert_eq!(from_nibbles::<[i8; 5]>(used), Ok(signed));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn string_intern() {
+    use postcard::de_nibble_flavors::{NibbleSlice as DeSlice, NibbleStringIntern as DeIntern};
+    use postcard::ser_nibble_flavors::{NibbleAllocVec, NibbleStringIntern};
+    use postcard::{
+        from_nibbles_with_flavor, serialize_with_nibble_flavor, to_nibble_allocvec, Error,
+    };
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Reading<'a> {
+        sensor: &'a str,
+        unit: &'a str,
+        value: u16,
+        note: &'a str,
+    }
+
+    let sensors = ["boiler", "return", "outside"];
+    let readings: std::vec::Vec<Reading> = (0..12u16)
+        .map(|i| Reading {
+            sensor: sensors[i as usize % 3],
+            unit: "degC",
+            value: i * 37,
+            note: "",
+        })
+        .collect();
+
+    let plain = to_nibble_allocvec(&readings).unwrap();
+    let interned =
+        serialize_with_nibble_flavor(&readings, NibbleStringIntern::new(NibbleAllocVec::new()))
+            .unwrap();
+    // Five distinct strings, every one of them written once
+    assert!(
+        interned.len() * 2 < plain.len(),
+        "{} vs {}",
+        interned.len(),
+        plain.len()
+    );
+    for s in sensors.iter().chain(["degC"].iter()) {
+        let found = interned
+            .windows(s.len())
+            .filter(|w| w == &s.as_bytes())
+            .count();
+        assert_eq!(found, 1, "{}", s);
+    }
+
+    let (decoded, _) = from_nibbles_with_flavor::<std::vec::Vec<Reading>, _>(DeIntern::new(
+        DeSlice::new(&interned),
+    ))
+    .unwrap();
+    assert_eq!(decoded, readings);
+
+    // Strings written with `collect_str` are entries of the manifest as well
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Labels<'a>(
+        #[serde(serialize_with = "collect")] &'a str,
+        &'a str,
+        &'a str,
+        u8,
+    );
+    fn collect<S: serde::Serializer>(s: &&str, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(s)
+    }
+    let labels = Labels("abc", "abc", "xy", 3);
+    let flavor = NibbleStringIntern::new(NibbleAllocVec::new());
+    let out = serialize_with_nibble_flavor(&labels, flavor).unwrap();
+    assert_eq!(out, &[0x03, b'a', b'b', b'c', 0x10, 0x20, b'x', b'y', 0x30]);
+    let flavor = DeIntern::new(DeSlice::new(&out));
+    assert_eq!(from_nibbles_with_flavor(flavor).map(|(l, _)| l), Ok(labels));
+
+    // References to entries that don't exist
+    let flavor = DeIntern::new(DeSlice::new(&[0x03, b'a', b'b', b'c', 0x20]));
+    assert_eq!(
+        from_nibbles_with_flavor::<(&str, &str), _>(flavor).map(|(v, _)| v),
+        Err(Error::DeserializeBadEncoding)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn string_intern_passes_hooks_on() {
+    use postcard::de_nibble_flavors::{
+        NibblePackedBools as DePacked, NibbleSlice as DeSlice, NibbleStringIntern as DeIntern,
+    };
+    use postcard::ser_nibble_flavors::{NibbleAllocVec, NibblePackedBools, NibbleStringIntern};
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, serialize_with_nibble_flavor};
+
+    // Booleans are packed by the inner flavor
+    type Flags<'a> = (&'a str, [bool; 4], &'a str, [bool; 3]);
+    let flags: Flags = ("on", [true, false, true, true], "on", [false, true, true]);
+    let out = serialize_with_nibble_flavor(
+        &flags,
+        NibbleStringIntern::new(NibblePackedBools::new(NibbleAllocVec::new())),
+    )
+    .unwrap();
+    assert_eq!(out, &[0x02, b'o', b'n', 0xD1, 0x60]);
+    let flavor = DeIntern::new(DePacked::new(DeSlice::new(&out)));
+    assert_eq!(from_nibbles_with_flavor(flavor).map(|(v, _)| v), Ok(flags));
+
+    // The first alternative decodes a new string before it fails, rewinding drops it from
+    // the manifest again
+    type Either<'a> = NibbleUntagged<(&'a str, bool), (&'a str, u8)>;
+    let value: (Either, &str, &str) = (NibbleUntagged::Second(("ab", 5)), "cd", "cd");
+    let out = serialize_with_nibble_flavor(&value, NibbleStringIntern::new(NibbleAllocVec::new()))
+        .unwrap();
+    let flavor = DeIntern::new(DeSlice::new(&out));
+    assert_eq!(from_nibbles_with_flavor(flavor).map(|(v, _)| v), Ok(value));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn push_nibbles() {
+    use postcard::ser_nibble_flavors::{NibbleFlavor, NibbleHVec, NibbleSlice};
+
+    fn one_by_one<F: NibbleFlavor>(mut flav: F, lead: &[u8], nibs: &[u8]) -> F::Output {
+        lead.iter().for_each(|n| flav.try_push_nib(*n).unwrap());
+        nibs.iter().for_each(|n| flav.try_push_nib(*n).unwrap());
+        flav.finalize().unwrap()
+    }
+    fn batched<F: NibbleFlavor>(mut flav: F, lead: &[u8], nibs: &[u8]) -> F::Output {
+        lead.iter().for_each(|n| flav.try_push_nib(*n).unwrap());
+        flav.try_push_nibbles(nibs).unwrap();
+        flav.finalize().unwrap()
+    }
+
+    let nibs = [0x0A, 0x0B, 0x0C];
+    let single = one_by_one(NibbleHVec::<8>::default(), &[], &nibs);
+    let batch = batched(NibbleHVec::<8>::default(), &[], &nibs);
+    assert_eq!(batch, single);
+    assert_eq!(batch, &[0xAB, 0xC0][..]);
+
+    // Starting mid-byte, with the upper bits of the elements ignored
+    let nibs = [0xF1, 0x02, 0x33, 0x04, 0x55];
+    for &lead in [&[][..], &[0x7][..], &[0x7, 0x8][..]].iter() {
+        for n in 0..=nibs.len() {
+            let single = one_by_one(NibbleHVec::<8>::default(), lead, &nibs[..n]);
+            let batch = batched(NibbleHVec::<8>::default(), lead, &nibs[..n]);
+            assert_eq!(batch, single, "lead {:?}, {} nibbles", lead, n);
+
+            // The default implementation of other flavors agrees
+            let mut buf = [0u8; 8];
+            let slice = batched(NibbleSlice::new(&mut buf), lead, &nibs[..n]);
+            assert_eq!(slice, &single[..]);
+        }
+    }
+
+    // Running out of room
+    let mut flav = NibbleHVec::<1>::default();
+    assert_eq!(
+        flav.try_push_nibbles(&[1, 2, 3]),
+        Err(postcard::Error::SerializeBufferFull)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn prefix_versioned() {
+    use postcard::{from_nibbles_prefix_versioned, to_nibble_vec_prefix_versioned, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Position {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ReportV1<'a> {
+        id: u16,
+        pos: Position,
+        name: &'a str,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ReportV2<'a> {
+        id: u16,
+        pos: Position,
+        name: &'a str,
+        battery: u8,
+        tags: [bool; 3],
+        extra: Position,
+    }
+
+    let v2 = ReportV2 {
+        id: 0x1234,
+        pos: Position { x: -5, y: 70_000 },
+        name: "probe",
+        battery: 87,
+        tags: [true, false, true],
+        extra: Position { x: 1, y: 2 },
+    };
+    let frame: Vec<u8, 64> = to_nibble_vec_prefix_versioned(&v2).unwrap();
+    // Six fields, those of nested structs not counted
+    assert_eq!(frame[0] >> 4, 6);
+
+    // An old reader decodes the common prefix
+    let v1: ReportV1 = from_nibbles_prefix_versioned(&frame).unwrap();
+    assert_eq!(
+        v1,
+        ReportV1 {
+            id: 0x1234,
+            pos: Position { x: -5, y: 70_000 },
+            name: "probe",
+        }
+    );
+    // A reader of the same version decodes everything
+    assert_eq!(from_nibbles_prefix_versioned(&frame), Ok(v2));
+
+    // A new reader notices that an old frame lacks fields
+    let frame: Vec<u8, 64> = to_nibble_vec_prefix_versioned(&v1).unwrap();
+    assert_eq!(frame[0] >> 4, 3);
+    assert_eq!(
+        from_nibbles_prefix_versioned::<ReportV2>(&frame),
+        Err(Error::DeserializeMissingFields)
+    );
+
+    // Values other than structs have no fields
+    let frame: Vec<u8, 8> = to_nibble_vec_prefix_versioned(&(1u8, true)).unwrap();
+    assert_eq!(frame.as_slice(), &[0x01, 0x10]);
+    assert_eq!(from_nibbles_prefix_versioned(&frame), Ok((1u8, true)));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn field_limit_passes_hooks_on() {
+    use postcard::de_nibble_flavors::{
+        NibbleFieldLimit, NibblePackedBools as DePacked, NibbleSlice as DeSlice,
+    };
+    use postcard::untagged::NibbleUntagged;
+    use postcard::{from_nibbles_with_flavor, to_nibble_vec_packed};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Flags {
+        id: u8,
+        on: [bool; 3],
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Pair {
+        x: u8,
+        y: u8,
+    }
+
+    // Booleans are taken from the inner flavor
+    let flags = Flags {
+        id: 4,
+        on: [true, true, false],
+    };
+    let frame: Vec<u8, 8> = to_nibble_vec_packed(&flags).unwrap();
+    let flavor = NibbleFieldLimit::new(DePacked::new(DeSlice::new(&frame)), 2);
+    assert_eq!(from_nibbles_with_flavor(flavor).map(|(v, _)| v), Ok(flags));
+
+    // The first alternative starts a field before it fails, rewinding gives it back
+    let value: NibbleUntagged<Flags, Pair> = NibbleUntagged::Second(Pair { x: 5, y: 7 });
+    let frame: Vec<u8, 8> = postcard::to_nibble_vec(&value).unwrap();
+    let flavor = NibbleFieldLimit::new(DeSlice::new(&frame), 2);
+    assert_eq!(from_nibbles_with_flavor(flavor).map(|(v, _)| v), Ok(value));
+}
+
+#[test]
+fn floats() {
+    use postcard::to_nibble_slice;
+
+    let mut buf = [0u8; 16];
+    let used = to_nibble_slice(&1.5f32, &mut buf).unwrap();
+    // Little endian IEEE-754, already aligned
+    assert_eq!(used, &[0x00, 0x00, 0xC0, 0x3F]);
+    assert_eq!(from_nibbles::<f32>(used), Ok(1.5));
+
+    let used = to_nibble_slice(&f64::NAN, &mut buf).unwrap();
+    assert_eq!(used, &f64::NAN.to_bits().to_le_bytes());
+    let nan: f64 = from_nibbles(used).unwrap();
+    assert_eq!(nan.to_bits(), f64::NAN.to_bits());
+    // Other bit patterns are kept as well
+    for &f in [
+        -0.0f64,
+        f64::INFINITY,
+        f64::MIN_POSITIVE,
+        f64::from_bits(0x7FF0_0000_0000_0001),
+    ]
+    .iter()
+    {
+        let used = to_nibble_slice(&f, &mut buf).unwrap();
+        assert_eq!(from_nibbles::<f64>(used).map(f64::to_bits), Ok(f.to_bits()));
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Sample {
+        id: u8,
+        value: f32,
+        flag: bool,
+        precise: f64,
+    }
+
+    let sample = Sample {
+        id: 5,
+        value: -2.25,
+        flag: true,
+        precise: 0.1,
+    };
+    let used = to_nibble_slice(&sample, &mut buf).unwrap();
+    // Both floats follow an odd number of nibbles, and start after a padding nibble
+    let mut expected = std::vec![0x50];
+    expected.extend_from_slice(&(-2.25f32).to_le_bytes());
+    expected.push(0x10);
+    expected.extend_from_slice(&0.1f64.to_le_bytes());
+    assert_eq!(used, &expected[..]);
+    assert_eq!(from_nibbles::<Sample>(used), Ok(sample));
+
+    // Truncated floats
+    assert_eq!(
+        from_nibbles::<(u8, f32)>(&[0x50, 0x00, 0x00, 0xC0]),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn byte_cow() {
+    use postcard::to_nibble_allocvec;
+    use std::borrow::Cow;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Blob<'a> {
+        id: u8,
+        #[serde(borrow, with = "postcard::byte_cow")]
+        data: Cow<'a, [u8]>,
+        flag: bool,
+    }
+
+    let raw: std::vec::Vec<u8> = (0..40u8).collect();
+    let borrowed = Blob {
+        id: 3,
+        data: Cow::Borrowed(&raw),
+        flag: true,
+    };
+    let owned = Blob {
+        id: 3,
+        data: Cow::Owned(raw.clone()),
+        flag: true,
+    };
+
+    // Both are written as the same aligned run of bytes
+    let ser = to_nibble_allocvec(&borrowed).unwrap();
+    assert_eq!(to_nibble_allocvec(&owned).unwrap(), ser);
+    assert_eq!(&ser[..2], &[0x3D, 0x00]);
+    assert_eq!(&ser[2..42], &raw[..]);
+    assert_eq!(&ser[42..], &[0x10]);
+
+    // The decoded data borrows from the input
+    let back: Blob = from_nibbles(&ser).unwrap();
+    assert_eq!(back, owned);
+    match back.data {
+        Cow::Borrowed(data) => assert_eq!(data.as_ptr(), ser[2..].as_ptr()),
+        Cow::Owned(_) => panic!("decoded data was copied"),
+    }
+
+    // Empty data, directly after an odd number of nibbles
+    let empty = Blob {
+        id: 1,
+        data: Cow::Owned(std::vec::Vec::new()),
+        flag: false,
+    };
+    let ser = to_nibble_allocvec(&empty).unwrap();
+    assert_eq!(ser, &[0x10, 0x00]);
+    let back: Blob = from_nibbles(&ser).unwrap();
+    assert_eq!(back, empty);
+    assert!(matches!(back.data, Cow::Borrowed(&[])));
+}
+
+#[test]
+fn measured() {
+    use postcard::{from_nibbles_measured, take_from_nibbles, to_nibble_slice};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Off,
+        Idle,
+        Run,
+    }
+
+    let mut buf = [0u8; 16];
+    let used = to_nibble_slice(&Mode::Run, &mut buf).unwrap();
+    assert_eq!(used, &[0x20]);
+
+    let (mode, rest) = take_from_nibbles::<Mode>(used).unwrap();
+    assert_eq!(mode, Mode::Run);
+    // The remainder starts at the next byte, the padding nibble counted as consumed
+    assert!(rest.is_empty());
+    assert_eq!(from_nibbles_measured::<Mode>(used), Ok((Mode::Run, 1)));
+
+    // Followed by more data, and with byte runs
+    assert_eq!(
+        from_nibbles_measured::<Mode>(&[0x1A, 0xBC]),
+        Ok((Mode::Idle, 1))
+    );
+    let used = to_nibble_slice(&(Mode::Off, "hey", true), &mut buf).unwrap();
+    assert_eq!(used, &[0x03, b'h', b'e', b'y', 0x10]);
+    assert_eq!(
+        from_nibbles_measured::<(Mode, &str, bool)>(used),
+        Ok(((Mode::Off, "hey", true), 9))
+    );
+
+    assert_eq!(
+        from_nibbles_measured::<(Mode, u16)>(&[0x09]),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_vec_ref() {
+    use postcard::ser_nibble_flavors::NibbleAllocVecRef;
+    use postcard::{serialize_with_nibble_flavor, Error, NibbleSeqReader};
+
+    let mut log = std::vec![0xEE];
+    let values = [7u16, 0x1234, 300];
+    let mut appended = 0;
+    for value in values.iter() {
+        appended += serialize_with_nibble_flavor(value, NibbleAllocVecRef::new(&mut log)).unwrap();
+    }
+    assert_eq!(appended, log.len() - 1);
+    assert_eq!(log[0], 0xEE);
+
+    let all: Result<std::vec::Vec<u16>, Error> = NibbleSeqReader::new(&log[1..]).collect();
+    assert_eq!(all, Ok(values.to_vec()));
+
+    // Continuing mid-byte fills the low nibble of the last byte, clearing stale bits first
+    let mut buf = std::vec![0x2F];
+    let flavor = NibbleAllocVecRef::new_at_boundary(&mut buf, false);
+    assert!(!flavor.is_at_byte_boundary());
+    assert_eq!(serialize_with_nibble_flavor(&5u8, flavor), Ok(0));
+    assert_eq!(buf, &[0x25]);
+    assert_eq!(
+        serialize_with_nibble_flavor(&true, NibbleAllocVecRef::new_at_boundary(&mut buf, true)),
+        Ok(1)
+    );
+    assert_eq!(buf, &[0x25, 0x10]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn field_sizes() {
+    use postcard::nibble_size;
+    use postcard::ser_nibble_flavors::NibbleFieldSizes;
+    use postcard::serialize_with_nibble_flavor;
+
+    let data = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let sizes = serialize_with_nibble_flavor(&data, NibbleFieldSizes::default()).unwrap();
+    assert_eq!(sizes, &[("st", 6), ("ei", 3), ("sf", 21), ("tt", 10)]);
+    let total: usize = sizes.iter().map(|&(_, len)| len).sum();
+    assert_eq!(Ok(total), nibble_size(&data));
+
+    // Nested fields follow their parent, fields within sequences are listed per element
+    #[derive(Serialize)]
+    struct Outer<'a> {
+        id: u8,
+        items: &'a [Inner],
+    }
+    #[derive(Serialize)]
+    struct Inner {
+        a: u8,
+    }
+    let data = Outer {
+        id: 1,
+        items: &[Inner { a: 2 }, Inner { a: 200 }],
+    };
+    let sizes = serialize_with_nibble_flavor(&data, NibbleFieldSizes::default()).unwrap();
+    assert_eq!(sizes, &[("id", 1), ("items", 5), ("a", 1), ("a", 3)]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn nibble_iter() {
+    use postcard::{to_nibble_allocvec, to_nibble_iter, Error};
+
+    #[derive(Serialize)]
+    struct Log<'a> {
+        id: u16,
+        flags: [bool; 5],
+        lines: &'a [&'a str],
+        values: &'a [u8],
+    }
+
+    // Long enough to take many chunks, with byte runs starting mid-byte and across chunks
+    let values: std::vec::Vec<u8> = (0..=255).collect();
+    let data = Log {
+        id: 0x1234,
+        flags: [true, false, true, true, false],
+        lines: &[
+            "",
+            "a",
+            "a longer line of text, longer than one chunk",
+            "",
+            "bc",
+        ],
+        values: &values,
+    };
+    let bytes: Result<std::vec::Vec<u8>, Error> = to_nibble_iter(&data).collect();
+    assert_eq!(bytes, to_nibble_allocvec(&data));
+
+    // Ending in the middle of a byte
+    let bytes: Result<std::vec::Vec<u8>, Error> = to_nibble_iter(&(1u8, 2u8, 3u8)).collect();
+    assert_eq!(bytes, Ok(std::vec![0x12, 0x30]));
+
+    // Many empty strings, each padded after its length
+    let empty: &[&str] = &[""; 100];
+    let bytes: Result<std::vec::Vec<u8>, Error> = to_nibble_iter(&(7u8, empty)).collect();
+    assert_eq!(bytes, to_nibble_allocvec(&(7u8, empty)));
+
+    // Errors end the iteration
+    struct Failing;
+    impl Serialize for Failing {
+        fn serialize<S: serde::Serializer>(&self, _s: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("failing"))
+        }
+    }
+    let mut iter = to_nibble_iter(&(0x12u8, Failing));
+    assert_eq!(iter.next(), Some(Err(Error::SerdeSerCustom)));
+    assert_eq!(iter.next(), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn nibble_frame() {
+    use postcard::{read_nibble_frame, to_nibble_frame, Error};
+
+    // Two frames in one buffer, the first message ending mid-byte
+    let mut stream = to_nibble_frame(&(1u8, 2u8, 3u8)).unwrap();
+    assert_eq!(stream, &[0x20, 0x12, 0x30]);
+    let text = "a message longer than eight bytes";
+    stream.extend(to_nibble_frame(&(text, 0xABCDu16)).unwrap());
+
+    let (first, rest) = read_nibble_frame::<(u8, u8, u8)>(&stream).unwrap();
+    assert_eq!(first, (1, 2, 3));
+    let (second, rest) = read_nibble_frame::<(&str, u16)>(rest).unwrap();
+    assert_eq!(second, (text, 0xABCD));
+    assert!(rest.is_empty());
+
+    // Truncated within the message, or within the length
+    assert_eq!(
+        read_nibble_frame::<(u8, u8, u8)>(&stream[..2]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(
+        read_nibble_frame::<(u8, u8, u8)>(&[0x9A]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(
+        read_nibble_frame::<(u8, u8, u8)>(&[]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+
+    // A declared length that doesn't match the message
+    assert_eq!(
+        read_nibble_frame::<(u8, u8, u8)>(&[0x30, 0x12, 0x30, 0x00]),
+        Err(Error::DeserializeUnexpectedLength)
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn with_trailer() {
+    use postcard::{from_nibbles_with_trailer, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Header<'a> {
+        kind: u8,
+        name: &'a str,
+        ok: bool,
+    }
+
+    let header = Header {
+        kind: 3,
+        name: "img",
+        ok: true,
+    };
+    let blob = [0xFFu8, 0x00, 0x5A, 0xA5];
+    let mut data: Vec<u8, 16> = to_nibble_vec(&header).unwrap();
+    assert_eq!(data.as_slice(), &[0x33, b'i', b'm', b'g', 0x10]);
+    data.extend_from_slice(&blob).unwrap();
+
+    assert_eq!(
+        from_nibbles_with_trailer::<Header>(&data, blob.len()),
+        Ok((header, &blob[..]))
+    );
+
+    // An empty trailer, a wrong length and a trailer longer than the data
+    assert_eq!(
+        from_nibbles_with_trailer::<(u8, bool)>(&[0x31], 0),
+        Ok(((3, true), &[][..]))
+    );
+    assert_eq!(
+        from_nibbles_with_trailer::<Header>(&data, blob.len() - 1),
+        Err(Error::DeserializeUnexpectedLength)
+    );
+    assert_eq!(
+        from_nibbles_with_trailer::<Header>(&data, blob.len() + 1),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(
+        from_nibbles_with_trailer::<Header>(&data, data.len() + 1),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+}